@@ -0,0 +1,348 @@
+use crate::error::XskillError;
+use crate::render::SkillFormat;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File under `~/.xskill` that users can edit to add agents or correct paths.
+pub const AGENTS_OVERLAY_FILE: &str = "agents.toml";
+
+/// A single agent (IDE / CLI) that xskill knows how to read skills from and sync skills to.
+///
+/// All paths are relative to the home directory (for global skills) or to a
+/// project root (for project-local skills).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AgentDef {
+    pub key: String,
+    pub display_name: String,
+    pub skills_subdir: String,
+    pub detect_subdir: String,
+    /// What a synced skill is turned into in `skills_subdir`.
+    #[serde(default)]
+    pub format: SkillFormat,
+    /// Directories this agent's skills were synced into before: paths earlier xskill
+    /// versions used, and the built-in directory when `agents.toml` moves it. Sync and prune
+    /// clean up the copies left there.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub legacy_subdirs: Vec<String>,
+}
+
+impl AgentDef {
+    fn new(key: &str, display_name: &str, skills_subdir: &str, detect_subdir: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            display_name: display_name.to_string(),
+            skills_subdir: skills_subdir.to_string(),
+            detect_subdir: detect_subdir.to_string(),
+            format: SkillFormat::SkillDir,
+            legacy_subdirs: Vec::new(),
+        }
+    }
+
+    fn rendered(mut self, format: SkillFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn moved_from(mut self, subdir: &str) -> Self {
+        self.legacy_subdirs.push(subdir.to_string());
+        self
+    }
+}
+
+/// One `[[agents]]` entry in `~/.xskill/agents.toml`.
+///
+/// An entry whose `key` matches a built-in agent overrides only the fields it sets;
+/// an entry with a new `key` adds an agent (and must set `skills_subdir`).
+/// `disabled = true` removes the agent from the registry, and `format` opts the agent into
+/// a rendered rule file per skill instead of the copied directory. Point `skills_subdir` at
+/// the agent's rules folder along with it; copies synced to the previous folder are retired
+/// on the next sync.
+///
+/// ```toml
+/// [[agents]]
+/// key = "roo_code"
+/// skills_subdir = ".roo-code/skills"
+///
+/// [[agents]]
+/// key = "cursor"
+/// skills_subdir = ".cursor/rules"
+/// format = "cursor_mdc"
+///
+/// [[agents]]
+/// key = "acme_agent"
+/// display_name = "Acme Agent"
+/// skills_subdir = ".acme/skills"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AgentOverride {
+    pub key: String,
+    pub display_name: Option<String>,
+    pub skills_subdir: Option<String>,
+    pub detect_subdir: Option<String>,
+    pub format: Option<SkillFormat>,
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AgentsFile {
+    #[serde(default)]
+    agents: Vec<AgentOverride>,
+}
+
+/// The set of agents xskill supports: the built-in table plus the user overlay.
+#[derive(Debug, Clone)]
+pub struct AgentRegistry {
+    agents: Vec<AgentDef>,
+}
+
+impl AgentRegistry {
+    /// The agents shipped with xskill, without any user overrides.
+    pub fn builtin() -> Self {
+        let agents = vec![
+            AgentDef::new("cursor",         "Cursor",         ".cursor/skills",             ".cursor"),
+            AgentDef::new("vscode",         "VS Code",        ".vscode/skills",             ".vscode"),
+            AgentDef::new("claude_code",    "Claude Code",    ".claude/skills",             ".claude"),
+            AgentDef::new("opencode",       "OpenCode",       ".config/opencode/skills",    ".config/opencode"),
+            AgentDef::new("windsurf",       "Windsurf",       ".codeium/windsurf/skills",   ".codeium/windsurf"),
+            AgentDef::new("trae",           "Trae",           ".trae/skills",               ".trae"),
+            AgentDef::new("gemini_cli",     "Gemini CLI",     ".gemini/skills",             ".gemini"),
+            AgentDef::new("antigravity",    "Antigravity",    ".gemini/antigravity/skills", ".gemini/antigravity").moved_from(".gemini/antigravity/global_skills"),
+            AgentDef::new("github_copilot", "GitHub Copilot", ".copilot/skills",            ".copilot"),
+            AgentDef::new("amp",            "Amp",            ".config/agents/skills",      ".config/agents"),
+            AgentDef::new("goose",          "Goose",          ".config/goose/skills",       ".config/goose"),
+            AgentDef::new("codex",          "Codex",          ".codex/skills",              ".codex"),
+            AgentDef::new("kode",           "Kode",           ".kode/skills",               ".kode"),
+            AgentDef::new("roo_code",       "Roo Code",       ".roo/skills",                ".roo").moved_from(".roo-code/skills"),
+            AgentDef::new("kilo_code",      "Kilo Code",      ".kilocode/skills",           ".kilocode"),
+            AgentDef::new("clawdbot",       "Clawdbot",       ".clawdbot/skills",           ".clawdbot"),
+            AgentDef::new("droid",          "Droid",          ".factory/skills",            ".factory"),
+            AgentDef::new("qoder",          "Qoder",          ".qoder/skills",              ".qoder"),
+            AgentDef::new("augment",        "Augment",        ".augment/rules",             ".augment").rendered(SkillFormat::AugmentRule),
+            AgentDef::new("cline",          "Cline",          ".cline/skills",              ".cline"),
+            AgentDef::new("kimi_cli",       "Kimi CLI",       ".kimi/skills",               ".kimi"),
+            AgentDef::new("openclaw",       "OpenClaw",       ".openclaw/skills",           ".openclaw"),
+            AgentDef::new("codebuddy",      "CodeBuddy",      ".codebuddy/skills",          ".codebuddy"),
+            AgentDef::new("continue_dev",   "Continue",       ".continue/skills",           ".continue"),
+            AgentDef::new("crush",          "Crush",          ".crush/skills",              ".crush"),
+            AgentDef::new("junie",          "Junie",          ".junie/skills",              ".junie"),
+        ];
+        Self { agents }
+    }
+
+    /// Built-in agents merged with `~/.xskill/agents.toml`, if present.
+    pub fn load() -> Result<Self, XskillError> {
+        let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+        Self::load_from_home(&home)
+    }
+
+    /// An overlay that cannot be read or applied is a `Config` error naming the file, never
+    /// a silent fall back to the built-in agents.
+    pub fn load_from_home(home: &Path) -> Result<Self, XskillError> {
+        let mut registry = Self::builtin();
+        let path = overlay_path(home);
+        if !path.exists() {
+            return Ok(registry);
+        }
+        let content = fs::read_to_string(&path).map_err(|e| XskillError::io(&path, e))?;
+        let file: AgentsFile = toml::from_str(&content)
+            .map_err(|e| XskillError::Config { path: path.clone(), message: e.to_string() })?;
+        registry.apply_overrides(file.agents).map_err(|message| XskillError::Config { path, message })?;
+        Ok(registry)
+    }
+
+    pub fn apply_overrides(&mut self, overrides: Vec<AgentOverride>) -> Result<(), String> {
+        for o in overrides {
+            if o.key.trim().is_empty() {
+                return Err("Agent entry is missing a key".to_string());
+            }
+            if o.disabled {
+                self.agents.retain(|a| a.key != o.key);
+                continue;
+            }
+            if let Some(existing) = self.agents.iter_mut().find(|a| a.key == o.key) {
+                if let Some(v) = o.display_name {
+                    existing.display_name = v;
+                }
+                if let Some(v) = o.skills_subdir {
+                    // Copies synced to the folder being replaced are retired like legacy ones
+                    let previous = std::mem::replace(&mut existing.skills_subdir, v);
+                    existing.legacy_subdirs.retain(|d| *d != existing.skills_subdir);
+                    if previous != existing.skills_subdir {
+                        existing.legacy_subdirs.push(previous);
+                    }
+                }
+                if let Some(v) = o.detect_subdir {
+                    existing.detect_subdir = v;
+                }
+                if let Some(v) = o.format {
+                    existing.format = v;
+                }
+                continue;
+            }
+
+            let skills_subdir = o
+                .skills_subdir
+                .ok_or_else(|| format!("Agent '{}' must set skills_subdir", o.key))?;
+            // Default detection to the directory that contains the skills folder
+            let detect_subdir = o.detect_subdir.unwrap_or_else(|| {
+                Path::new(&skills_subdir)
+                    .parent()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .filter(|p| !p.is_empty())
+                    .unwrap_or_else(|| skills_subdir.clone())
+            });
+            self.agents.push(AgentDef {
+                display_name: o.display_name.unwrap_or_else(|| o.key.clone()),
+                key: o.key,
+                skills_subdir,
+                detect_subdir,
+                format: o.format.unwrap_or_default(),
+                legacy_subdirs: Vec::new(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn agents(&self) -> &[AgentDef] {
+        &self.agents
+    }
+
+    pub fn into_agents(self) -> Vec<AgentDef> {
+        self.agents
+    }
+
+    pub fn get(&self, key: &str) -> Option<&AgentDef> {
+        self.agents.iter().find(|a| a.key == key)
+    }
+
+    /// Absolute skills directory for `key` under `root` (home or project directory).
+    pub fn skills_dir(&self, key: &str, root: &Path) -> Option<PathBuf> {
+        self.get(key).map(|a| root.join(&a.skills_subdir))
+    }
+
+    /// Directories under `root` where earlier versions put `key`'s skill directories.
+    pub fn legacy_dirs(&self, key: &str, root: &Path) -> Vec<PathBuf> {
+        self.get(key).map(|a| a.legacy_subdirs.iter().map(|d| root.join(d)).collect()).unwrap_or_default()
+    }
+}
+
+pub fn overlay_path(home: &Path) -> PathBuf {
+    home.join(".xskill").join(AGENTS_OVERLAY_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_builtin_keys_are_unique() {
+        let registry = AgentRegistry::builtin();
+        let mut keys: Vec<&str> = registry.agents().iter().map(|a| a.key.as_str()).collect();
+        let total = keys.len();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), total);
+    }
+
+    #[test]
+    fn test_only_augment_renders_by_default_and_old_dirs_are_kept() {
+        let registry = AgentRegistry::builtin();
+        let format = |key: &str| registry.get(key).unwrap().format;
+        for key in ["cursor", "windsurf", "cline", "github_copilot", "claude_code"] {
+            assert_eq!(format(key), SkillFormat::SkillDir, "{}", key);
+        }
+        assert_eq!(format("augment"), SkillFormat::AugmentRule);
+        assert_eq!(registry.get("cursor").unwrap().skills_subdir, ".cursor/skills");
+
+        let home = Path::new("/home/me");
+        assert_eq!(registry.legacy_dirs("roo_code", home), vec![home.join(".roo-code/skills")]);
+        assert_eq!(registry.legacy_dirs("antigravity", home), vec![home.join(".gemini/antigravity/global_skills")]);
+        for key in ["cursor", "augment", "claude_code"] {
+            assert!(registry.legacy_dirs(key, home).is_empty(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_overlay_overrides_adds_and_disables() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path();
+        fs::create_dir_all(home.join(".xskill")).unwrap();
+        fs::write(
+            overlay_path(home),
+            r#"
+[[agents]]
+key = "roo_code"
+skills_subdir = ".roo-code/skills"
+
+[[agents]]
+key = "acme"
+display_name = "Acme Agent"
+skills_subdir = ".acme/agent/skills"
+
+[[agents]]
+key = "qoder"
+disabled = true
+"#,
+        )
+        .unwrap();
+
+        let registry = AgentRegistry::load_from_home(home).unwrap();
+
+        let roo = registry.get("roo_code").unwrap();
+        assert_eq!(roo.skills_subdir, ".roo-code/skills");
+        assert_eq!(roo.display_name, "Roo Code");
+        assert_eq!(roo.legacy_subdirs, vec![".roo/skills".to_string()]);
+
+        let acme = registry.get("acme").unwrap();
+        assert_eq!(acme.display_name, "Acme Agent");
+        assert_eq!(acme.detect_subdir, ".acme/agent");
+        assert_eq!(registry.skills_dir("acme", home).unwrap(), home.join(".acme/agent/skills"));
+
+        assert!(registry.get("qoder").is_none());
+        assert_eq!(registry.get("augment").unwrap().format, SkillFormat::AugmentRule);
+    }
+
+    #[test]
+    fn test_broken_overlay_is_a_config_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path();
+        fs::create_dir_all(home.join(".xskill")).unwrap();
+
+        fs::write(overlay_path(home), "[[agents]\nkey = ").unwrap();
+        let err = AgentRegistry::load_from_home(home).unwrap_err();
+        assert_eq!(err.code(), "config");
+        assert!(err.to_string().contains("agents.toml"));
+
+        fs::write(overlay_path(home), "[[agents]]\nkey = \"nowhere\"\n").unwrap();
+        let err = AgentRegistry::load_from_home(home).unwrap_err();
+        assert_eq!(err.code(), "config");
+        assert!(err.to_string().contains("must set skills_subdir"));
+    }
+
+    #[test]
+    fn test_overlay_sets_format() {
+        let mut registry = AgentRegistry::builtin();
+        let overlay: AgentsFile = toml::from_str("[[agents]]\nkey = \"cursor\"\nskills_subdir = \".cursor/rules\"\nformat = \"cursor_mdc\"\n").unwrap();
+        registry.apply_overrides(overlay.agents).unwrap();
+        let cursor = registry.get("cursor").unwrap();
+        assert_eq!(cursor.format, SkillFormat::CursorMdc);
+        assert_eq!(cursor.skills_subdir, ".cursor/rules");
+        assert_eq!(cursor.legacy_subdirs, vec![".cursor/skills".to_string()]);
+        assert_eq!(registry.get("windsurf").unwrap().format, SkillFormat::SkillDir);
+
+        assert!(toml::from_str::<AgentsFile>("[[agents]]\nkey = \"cursor\"\nformat = \"rtf\"\n").is_err());
+    }
+
+    #[test]
+    fn test_overlay_new_agent_requires_skills_subdir() {
+        let mut registry = AgentRegistry::builtin();
+        let result = registry.apply_overrides(vec![AgentOverride {
+            key: "nowhere".to_string(),
+            ..Default::default()
+        }]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,390 @@
+use crate::error::XskillError;
+use crate::skill_manager::{write_skill_meta, SkillMeta};
+use crate::trust::{SignatureMethod, SignatureRecord};
+use crate::utils::COPY_IGNORED;
+use crate::xskill::Xskill;
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Largest archive `install_location` downloads. Skills are text and a few scripts; anything
+/// bigger is refused before it fills the disk.
+const MAX_DOWNLOAD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Archive formats skills can be distributed as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    /// Format implied by a file name or URL (`.zip`, `.tar.gz`, `.tgz`), ignoring any query.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.split(['?', '#']).next().unwrap_or(name).to_ascii_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else {
+            None
+        }
+    }
+
+    fn strip<'a>(&self, file_name: &'a str) -> &'a str {
+        let lower = file_name.to_ascii_lowercase();
+        let len = [".zip", ".tar.gz", ".tgz"].iter().find(|ext| lower.ends_with(*ext)).map_or(0, |ext| ext.len());
+        &file_name[..file_name.len() - len]
+    }
+}
+
+/// Where an install comes from when it is not a git repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    /// Skill directory on disk, copied as is.
+    Dir(PathBuf),
+    /// `.zip` or `.tar.gz` on disk.
+    File(PathBuf, ArchiveFormat),
+    /// `.zip` or `.tar.gz` to download.
+    Url(String, ArchiveFormat),
+}
+
+impl Location {
+    /// Classify an install location. `None` means it is a git repository (local clones
+    /// included) and goes through the git install.
+    pub fn parse(location: &str) -> Option<Self> {
+        let path = Path::new(location.strip_prefix("file://").unwrap_or(location));
+        if location.starts_with("http://") || location.starts_with("https://") {
+            return ArchiveFormat::from_name(location).map(|f| Self::Url(location.to_string(), f));
+        }
+        if path.is_dir() {
+            let is_repo = path.join(".git").exists() || (path.join("HEAD").is_file() && path.join("objects").is_dir());
+            return (!is_repo).then(|| Self::Dir(path.to_path_buf()));
+        }
+        if path.is_file() {
+            return ArchiveFormat::from_name(location).map(|f| Self::File(path.to_path_buf(), f));
+        }
+        None
+    }
+
+    /// Hub name to fall back to when the skill has no valid frontmatter name and the
+    /// archive has no wrapping directory.
+    fn default_name(&self) -> String {
+        let (raw, format) = match self {
+            Self::Dir(path) => return path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+            Self::File(path, format) => (path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(), format),
+            Self::Url(url, format) => {
+                let path = url.split(['?', '#']).next().unwrap_or(url);
+                (path.rsplit('/').next().unwrap_or_default().to_string(), format)
+            }
+        };
+        format.strip(&raw).to_string()
+    }
+}
+
+/// Hex SHA-256 of a file.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Fail unless `path` hashes to `expected` (hex, case-insensitive, optional `sha256:` prefix).
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<String, String> {
+    let expected = expected.trim().trim_start_matches("sha256:").to_ascii_lowercase();
+    let actual = sha256_file(path)?;
+    if actual != expected {
+        return Err(format!("Checksum mismatch: expected sha256 {}, got {}", expected, actual));
+    }
+    Ok(actual)
+}
+
+/// Unpack `archive` into `dest`. Entries that would land outside `dest`, symlinks and
+/// hard links are skipped.
+pub fn extract(archive: &Path, format: ArchiveFormat, dest: &Path) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to open {}: {}", archive.display(), e))?;
+    fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+    match format {
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip archive: {}", e))?;
+            for i in 0..zip.len() {
+                let mut entry = zip.by_index(i).map_err(|e| format!("Invalid zip archive: {}", e))?;
+                let Some(relative) = entry.enclosed_name() else { continue };
+                if entry.is_symlink() {
+                    continue;
+                }
+                let target = dest.join(relative);
+                if entry.is_dir() {
+                    fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+                    continue;
+                }
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                let mut out = File::create(&target).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+                std::io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to extract {}: {}", target.display(), e))?;
+                #[cfg(unix)]
+                if let Some(mode) = entry.unix_mode() {
+                    use std::os::unix::fs::PermissionsExt;
+                    let _ = fs::set_permissions(&target, fs::Permissions::from_mode(mode & 0o755));
+                }
+            }
+        }
+        ArchiveFormat::TarGz => {
+            let mut tar = tar::Archive::new(GzDecoder::new(file));
+            tar.set_preserve_permissions(false);
+            for entry in tar.entries().map_err(|e| format!("Invalid tar archive: {}", e))? {
+                let mut entry = entry.map_err(|e| format!("Invalid tar archive: {}", e))?;
+                let kind = entry.header().entry_type();
+                if !(kind.is_file() || kind.is_dir()) {
+                    continue;
+                }
+                // unpack_in refuses paths that escape `dest`
+                entry.unpack_in(dest).map_err(|e| format!("Failed to extract archive: {}", e))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Directory holding `SKILL.md` in an unpacked archive: the top level, or the single
+/// directory release archives usually wrap their content in.
+fn skill_root(dir: &Path) -> Result<PathBuf, String> {
+    if dir.join("SKILL.md").is_file() {
+        return Ok(dir.to_path_buf());
+    }
+    let entries: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            !COPY_IGNORED.contains(&name.as_str()) && name != "__MACOSX"
+        })
+        .map(|e| e.path())
+        .collect();
+    match entries.as_slice() {
+        [only] if only.join("SKILL.md").is_file() => Ok(only.clone()),
+        _ => Err("No SKILL.md found at the top of the archive".to_string()),
+    }
+}
+
+/// Stream `url` into `dest`, giving up once it passes `max_bytes`.
+async fn download<F>(url: &str, dest: &Path, max_bytes: u64, progress: &mut F) -> Result<(), XskillError>
+where
+    F: FnMut(String),
+{
+    progress(format!("Downloading {}...", url));
+    let failed = |e: &dyn std::fmt::Display| XskillError::network(format!("Failed to download {}: {}", url, e));
+    let mut resp = crate::forge::get(url, std::time::Duration::from_secs(120), None).await.map_err(|e| failed(&e))?;
+    if !resp.status().is_success() {
+        return Err(failed(&resp.status()));
+    }
+    let too_large = || XskillError::invalid(format!("{} is larger than {:.1} MiB", url, max_bytes as f64 / (1024.0 * 1024.0)));
+    if resp.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(too_large());
+    }
+    let mut file = File::create(dest).map_err(|e| XskillError::io(dest, e))?;
+    let mut received: u64 = 0;
+    while let Some(chunk) = resp.chunk().await.map_err(|e| failed(&e))? {
+        received += chunk.len() as u64;
+        if received > max_bytes {
+            return Err(too_large());
+        }
+        file.write_all(&chunk).map_err(|e| XskillError::io(dest, e))?;
+    }
+    progress(format!("Downloaded {:.1} KiB", received as f64 / 1024.0));
+    Ok(())
+}
+
+/// Check, unpack and (for packages) verify an archive in `staging`. Returns the skill
+/// directory, the archive checksum, the name to fall back to and the package signature.
+fn unpack_staged<F>(
+    location: &Location,
+    archive: &Path,
+    format: ArchiveFormat,
+    staging: &Path,
+    sha256: Option<&str>,
+    progress: &mut F,
+) -> Result<(PathBuf, Option<String>, String, Option<SignatureRecord>), XskillError>
+where
+    F: FnMut(String),
+{
+    let checksum = match sha256 {
+        Some(expected) => {
+            progress("Verifying checksum...".to_string());
+            verify_sha256(archive, expected).map_err(XskillError::integrity)?
+        }
+        None => sha256_file(archive)?,
+    };
+    progress("Extracting archive...".to_string());
+    let unpacked = staging.join("unpacked");
+    extract(archive, format, &unpacked)?;
+    if unpacked.join(crate::pack::MANIFEST_FILE).is_file() {
+        progress("Verifying package...".to_string());
+        let (manifest, signer, root) = crate::pack::verify_unpacked(&unpacked).map_err(XskillError::integrity)?;
+        let signature = signer.map(|key| SignatureRecord { method: SignatureMethod::Package, key_id: crate::pack::key_id(&key), content_hash: None });
+        return Ok((root, Some(checksum), manifest.name, signature));
+    }
+    let root = skill_root(&unpacked)?;
+    let fallback = match root.strip_prefix(&unpacked) {
+        Ok(wrapper) if !wrapper.as_os_str().is_empty() => wrapper.to_string_lossy().to_string(),
+        _ => location.default_name(),
+    };
+    Ok((root, Some(checksum), fallback, None))
+}
+
+/// Install a skill from a local directory, a local archive or an archive URL into the Hub,
+/// with the same placement, metadata and conflict handling as git installs. `sha256`, when
+/// given, must match the archive before anything is unpacked. Like every install, the skill is
+/// audited while staged and only moved into the Hub when the audit lets it through.
+pub async fn install_location<F>(xskill: &Xskill, location: &Location, original: &str, sha256: Option<&str>, mut progress: F) -> Result<PathBuf, XskillError>
+where
+    F: FnMut(String),
+{
+    let hub = xskill.hub();
+    fs::create_dir_all(hub).map_err(|e| XskillError::io(hub, e))?;
+    // Staged next to the Hub so the final move is a rename
+    let staging = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(xskill.home().join(".xskill"))
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    let (root, checksum, fallback, signature) = match location {
+        Location::Dir(path) => {
+            if sha256.is_some() {
+                return Err(XskillError::invalid("A sha256 can only be checked for archives"));
+            }
+            if !path.join("SKILL.md").is_file() {
+                return Err(XskillError::invalid(format!("No SKILL.md found in {}", path.display())));
+            }
+            progress(format!("Copying {}...", path.display()));
+            let copy = staging.path().join("skill");
+            crate::utils::copy_dir_all(&path.to_path_buf(), &copy)?;
+            (copy, None, location.default_name(), None)
+        }
+        Location::File(path, format) => unpack_staged(location, path, *format, staging.path(), sha256, &mut progress)?,
+        Location::Url(url, format) => {
+            let file = staging.path().join("download");
+            download(url, &file, MAX_DOWNLOAD_BYTES, &mut progress).await?;
+            unpack_staged(location, &file, *format, staging.path(), sha256, &mut progress)?
+        }
+    };
+
+    let raw = fs::read_to_string(root.join("SKILL.md")).unwrap_or_default();
+    let name = crate::frontmatter::parse(&raw)
+        .ok()
+        .and_then(|(manifest, _)| manifest.name)
+        .filter(|n| crate::scaffold::validate_skill_name(n).is_ok())
+        .unwrap_or(fallback);
+    crate::scaffold::validate_hub_name(&name)?;
+
+    let target = hub.join(&name);
+    if target.exists() {
+        return Err(XskillError::already_exists(&name, &target));
+    }
+    let signature = signature.map(|s| s.seal(&root)).transpose().map_err(XskillError::integrity)?;
+    let meta = SkillMeta { original_url: Some(original.to_string()), sha256: checksum, signature, ..Default::default() };
+    write_skill_meta(&root, &meta)?;
+    // Audited while still staged: a blocked skill never reaches the Hub
+    xskill.audit_staged(&root, &mut progress)?;
+
+    fs::rename(&root, &target).map_err(|e| XskillError::io(&target, e))?;
+    xskill.refresh_lock_quietly();
+    progress(format!("Installed {}", name));
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_location_parse() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("pdf");
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(Location::parse(dir.to_str().unwrap()), Some(Location::Dir(dir.clone())));
+        fs::create_dir_all(dir.join(".git")).unwrap();
+        assert_eq!(Location::parse(dir.to_str().unwrap()), None);
+
+        assert_eq!(
+            Location::parse("https://example.com/releases/pdf-1.0.tgz?x=1"),
+            Some(Location::Url("https://example.com/releases/pdf-1.0.tgz?x=1".to_string(), ArchiveFormat::TarGz))
+        );
+        assert_eq!(Location::parse("https://github.com/acme/skills"), None);
+        assert_eq!(Location::Url("https://x/y/pdf-1.0.tar.gz".to_string(), ArchiveFormat::TarGz).default_name(), "pdf-1.0");
+    }
+
+    #[test]
+    fn test_zip_extract_skips_escaping_entries() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("skill.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("pdf/SKILL.md", options).unwrap();
+        zip.write_all(b"---\nname: pdf\n---\n").unwrap();
+        zip.start_file("../evil.txt", options).unwrap();
+        zip.write_all(b"evil").unwrap();
+        zip.finish().unwrap();
+
+        let dest = tmp.path().join("out");
+        extract(&archive, ArchiveFormat::Zip, &dest).unwrap();
+        assert!(dest.join("pdf/SKILL.md").exists());
+        assert!(!tmp.path().join("evil.txt").exists());
+        assert_eq!(skill_root(&dest).unwrap(), dest.join("pdf"));
+
+        let sum = sha256_file(&archive).unwrap();
+        assert!(verify_sha256(&archive, &format!("sha256:{}", sum.to_uppercase())).is_ok());
+        assert!(verify_sha256(&archive, "00").unwrap_err().contains("Checksum mismatch"));
+    }
+
+    /// Serve `body` once over plain HTTP, optionally without a Content-Length.
+    fn serve_once(body: Vec<u8>, content_length: bool) -> String {
+        use std::io::BufRead;
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/skill.zip", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).map(|n| n > 2).unwrap_or(false) {
+                line.clear();
+            }
+            let mut stream = stream;
+            let length = if content_length { format!("Content-Length: {}\r\n", body.len()) } else { String::new() };
+            let _ = write!(stream, "HTTP/1.1 200 OK\r\n{}Connection: close\r\n\r\n", length);
+            let _ = stream.write_all(&body);
+        });
+        url
+    }
+
+    #[test]
+    fn test_download_is_capped() {
+        let tmp = TempDir::new().unwrap();
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let dest = tmp.path().join("download");
+
+        let url = serve_once(vec![b'x'; 100], true);
+        rt.block_on(download(&url, &dest, 100, &mut |_| {})).unwrap();
+        assert_eq!(fs::read(&dest).unwrap().len(), 100);
+
+        // Refused from the header, and while streaming when the server does not say
+        for content_length in [true, false] {
+            let url = serve_once(vec![b'x'; 4096], content_length);
+            let err = rt.block_on(download(&url, &dest, 1024, &mut |_| {})).unwrap_err();
+            assert_eq!(err.code(), "invalid_input", "{}", err);
+        }
+    }
+}
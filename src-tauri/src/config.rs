@@ -1,3 +1,159 @@
+use crate::audit::Severity;
+use crate::error::XskillError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User settings, kept in `~/.xskill/config.toml`.
+pub const CONFIG_FILE: &str = ".xskill/config.toml";
+
+/// ```toml
+/// [trust]
+/// sync_policy = "block-unverified"
+///
+/// [audit]
+/// block = "high"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct XskillConfig {
+    #[serde(default)]
+    pub trust: TrustConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustConfig {
+    #[serde(default)]
+    pub sync_policy: SyncPolicy,
+}
+
+/// Which skills may be synced to agents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncPolicy {
+    /// Sync every skill.
+    #[default]
+    Allow,
+    /// Refuse skills installed from elsewhere unless a trusted publisher signed them.
+    BlockUnverified,
+}
+
+impl std::str::FromStr for SyncPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(Self::Allow),
+            "block-unverified" => Ok(Self::BlockUnverified),
+            _ => Err(format!("Unknown sync policy '{}' (expected allow or block-unverified)", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for SyncPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::BlockUnverified => "block-unverified",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Refuse installs and imports with security findings at or above this severity;
+    /// `"off"` only reports them.
+    #[serde(default = "default_audit_block", with = "severity_or_off")]
+    pub block: Option<Severity>,
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self { block: default_audit_block() }
+    }
+}
+
+fn default_audit_block() -> Option<Severity> {
+    Some(Severity::Critical)
+}
+
+mod severity_or_off {
+    use super::Severity;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &Option<Severity>, s: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(severity) => s.serialize_str(&severity.to_string()),
+            None => s.serialize_str("off"),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Severity>, D::Error> {
+        match String::deserialize(d)?.as_str() {
+            "off" => Ok(None),
+            other => other.parse().map(Some).map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+fn config_path(home: &Path) -> PathBuf {
+    home.join(CONFIG_FILE)
+}
+
+impl XskillConfig {
+    pub fn load() -> Result<Self, String> {
+        let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+        Self::load_from_home(&home)
+    }
+
+    pub fn load_from_home(home: &Path) -> Result<Self, String> {
+        let path = config_path(home);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    pub fn save_to_home(&self, home: &Path) -> Result<(), String> {
+        let path = config_path(home);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+/// Settings that `xskill config get/set` read and change, as `section.key`.
+pub const SETTINGS: &[&str] = &["trust.sync_policy", "audit.block"];
+
+impl XskillConfig {
+    /// Current value of a setting, spelled as it is in config.toml.
+    pub fn get(&self, key: &str) -> Result<String, XskillError> {
+        match key {
+            "trust.sync_policy" => Ok(self.trust.sync_policy.to_string()),
+            "audit.block" => Ok(self.audit.block.map(|s| s.to_string()).unwrap_or_else(|| "off".to_string())),
+            _ => Err(unknown_setting(key)),
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), XskillError> {
+        match key {
+            "trust.sync_policy" => self.trust.sync_policy = value.parse().map_err(XskillError::invalid)?,
+            "audit.block" if value == "off" => self.audit.block = None,
+            "audit.block" => self.audit.block = Some(value.parse().map_err(XskillError::invalid)?),
+            _ => return Err(unknown_setting(key)),
+        }
+        Ok(())
+    }
+}
+
+fn unknown_setting(key: &str) -> XskillError {
+    XskillError::invalid(format!("Unknown setting '{}' (expected one of {})", key, SETTINGS.join(", ")))
+}
+
 pub fn init_config() {
     // TODO: init local sqlite / json store
 }
@@ -1,8 +1,9 @@
-use crate::skill_manager::CENTRAL_SKILLS_DIR;
+use crate::error::XskillError;
+use crate::xskill::Xskill;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const CONFIG_FILE: &str = "skills_config.json";
 
@@ -15,6 +16,10 @@ pub struct SkillConfig {
 
 fn get_config_path() -> Result<PathBuf, String> {
     let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+    config_path_in(&home)
+}
+
+fn config_path_in(home: &Path) -> Result<PathBuf, String> {
     let config_dir = home.join(".xskill");
     if !config_dir.exists() {
         fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
@@ -22,22 +27,25 @@ fn get_config_path() -> Result<PathBuf, String> {
     Ok(config_dir.join(CONFIG_FILE))
 }
 
-fn load_all_configs() -> Result<HashMap<String, SkillConfig>, String> {
-    let path = get_config_path()?;
+pub fn load_all_configs() -> Result<HashMap<String, SkillConfig>, String> {
+    load_configs_from(&get_config_path()?)
+}
+
+fn load_configs_from(path: &Path) -> Result<HashMap<String, SkillConfig>, String> {
     if !path.exists() {
         return Ok(HashMap::new());
     }
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
     serde_json::from_str(&content).map_err(|e| e.to_string())
 }
 
-fn save_all_configs(configs: &HashMap<String, SkillConfig>) -> Result<(), String> {
+pub fn save_all_configs(configs: &HashMap<String, SkillConfig>) -> Result<(), String> {
     let path = get_config_path()?;
     let content = serde_json::to_string_pretty(configs).map_err(|e| e.to_string())?;
     fs::write(&path, content).map_err(|e| e.to_string())
 }
 
-pub fn detect_default_config(skill_path: &PathBuf) -> Option<SkillConfig> {
+pub fn detect_default_config(skill_path: &Path) -> Option<SkillConfig> {
     if !skill_path.exists() {
         return None;
     }
@@ -97,27 +105,37 @@ pub fn detect_default_config(skill_path: &PathBuf) -> Option<SkillConfig> {
     None
 }
 
-#[tauri::command]
-pub fn get_skill_config(skill_name: String, skill_path: Option<String>) -> Result<SkillConfig, String> {
-    let configs = load_all_configs()?;
-    if let Some(config) = configs.get(&skill_name) {
-        return Ok(config.clone());
+impl Xskill {
+    /// MCP server settings saved for `skill_name`, or detected from the skill at `skill_path`
+    /// (its Hub copy by default).
+    pub fn skill_config(&self, skill_name: &str, skill_path: Option<&Path>) -> Result<SkillConfig, XskillError> {
+        let configs = load_configs_from(&config_path_in(self.home())?)?;
+        if let Some(config) = configs.get(skill_name) {
+            return Ok(config.clone());
+        }
+
+        // Auto-detect if no config exists
+        let path = skill_path.map(Path::to_path_buf).unwrap_or_else(|| self.hub().join(skill_name));
+        Ok(detect_default_config(&path).unwrap_or_default())
+    }
+
+    pub fn save_skill_config(&self, skill_name: &str, config: SkillConfig) -> Result<(), XskillError> {
+        let path = config_path_in(self.home())?;
+        let mut configs = load_configs_from(&path)?;
+        configs.insert(skill_name.to_string(), config);
+        let content = serde_json::to_string_pretty(&configs).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| XskillError::io(&path, e))
     }
-    
-    // Auto-detect if no config exists
-    let path = skill_path.map(PathBuf::from).unwrap_or_else(|| {
-        let home = crate::utils::get_home_dir().unwrap_or_default();
-        home.join(CENTRAL_SKILLS_DIR).join(&skill_name)
-    });
-
-    Ok(detect_default_config(&path).unwrap_or_default())
 }
 
 #[tauri::command]
-pub fn save_skill_config(skill_name: String, config: SkillConfig) -> Result<(), String> {
-    let mut configs = load_all_configs()?;
-    configs.insert(skill_name, config);
-    save_all_configs(&configs)
+pub fn get_skill_config(skill_name: String, skill_path: Option<String>) -> Result<SkillConfig, XskillError> {
+    Xskill::load()?.skill_config(&skill_name, skill_path.as_deref().map(Path::new))
+}
+
+#[tauri::command]
+pub fn save_skill_config(skill_name: String, config: SkillConfig) -> Result<(), XskillError> {
+    Xskill::load()?.save_skill_config(&skill_name, config)
 }
 
 #[cfg(test)]
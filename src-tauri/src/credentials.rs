@@ -0,0 +1,224 @@
+use crate::error::XskillError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const KEYRING_SERVICE: &str = "xskill";
+/// Hosts that have a token and where it is kept. Never holds the tokens themselves.
+const INDEX_FILE: &str = ".xskill/credentials.json";
+/// Plain-text host -> token map, used when no OS keyring is available. Like
+/// `git-credential-store`, it is only protected by being readable by its owner alone.
+const TOKENS_FILE: &str = ".xskill/tokens.json";
+
+/// Where a token is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStore {
+    /// macOS Keychain, Windows Credential Manager or the Secret Service on Linux.
+    Keyring,
+    /// `~/.xskill/tokens.json`, stored unencrypted (see `TOKENS_FILE`).
+    File,
+}
+
+/// A host with a stored token, as shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredCredential {
+    pub host: String,
+    pub store: TokenStore,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CredentialIndex {
+    #[serde(default)]
+    hosts: BTreeMap<String, TokenStore>,
+}
+
+/// Store for new tokens: the OS keyring unless `XSKILL_CREDENTIAL_STORE=file` is set. Test
+/// homes never use it, so tests cannot leak tokens into the developer's keychain.
+pub fn default_store() -> TokenStore {
+    if std::env::var("XSKILL_TEST_HOME").is_err() && std::env::var("XSKILL_CREDENTIAL_STORE").as_deref() != Ok("file") {
+        TokenStore::Keyring
+    } else {
+        TokenStore::File
+    }
+}
+
+fn home() -> Result<PathBuf, XskillError> {
+    crate::utils::get_home_dir().ok_or_else(|| XskillError::from("Could not find home directory"))
+}
+
+fn normalize(host: &str) -> String {
+    host.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// A missing index means no tokens; one that cannot be parsed is an error, so a later
+/// write never replaces it and loses track of tokens kept in the keyring.
+fn read_index(home: &Path) -> Result<CredentialIndex, XskillError> {
+    let path = home.join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(CredentialIndex::default());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| XskillError::io(&path, e))?;
+    serde_json::from_str(&content).map_err(|e| XskillError::Config { path: path.clone(), message: e.to_string() })
+}
+
+fn write_index(home: &Path, index: &CredentialIndex) -> Result<(), XskillError> {
+    let json = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    write_private(&home.join(INDEX_FILE), json.as_bytes())
+}
+
+/// Write `data` readable by the current user only.
+fn write_private(path: &Path, data: &[u8]) -> Result<(), XskillError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+    }
+    fs::write(path, data).map_err(|e| XskillError::io(path, e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(|e| XskillError::io(path, e))?;
+    }
+    Ok(())
+}
+
+fn read_tokens(home: &Path) -> Result<BTreeMap<String, String>, XskillError> {
+    let path = home.join(TOKENS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| XskillError::io(&path, e))?;
+    serde_json::from_str(&content).map_err(|e| XskillError::Config { path: path.clone(), message: e.to_string() })
+}
+
+fn write_tokens(home: &Path, tokens: &BTreeMap<String, String>) -> Result<(), XskillError> {
+    let json = serde_json::to_string_pretty(tokens).map_err(|e| e.to_string())?;
+    write_private(&home.join(TOKENS_FILE), json.as_bytes())
+}
+
+fn keyring_entry(host: &str) -> Result<keyring::Entry, XskillError> {
+    keyring::Entry::new(KEYRING_SERVICE, host).map_err(|e| XskillError::from(e.to_string()))
+}
+
+/// Store `token` for `host` in `store`, falling back to the file when the OS keyring is
+/// unavailable. Replaces any token the host already had. Returns where it went.
+pub fn set_token_in(home: &Path, host: &str, token: &str, store: TokenStore) -> Result<TokenStore, XskillError> {
+    let host = normalize(host);
+    if host.is_empty() || token.trim().is_empty() {
+        return Err(XskillError::invalid("Host and token must not be empty"));
+    }
+    delete_token_in(home, &host)?;
+
+    let in_keyring = store == TokenStore::Keyring && keyring_entry(&host).and_then(|e| e.set_password(token.trim()).map_err(|e| XskillError::from(e.to_string()))).is_ok();
+    let store = if in_keyring {
+        TokenStore::Keyring
+    } else {
+        let mut tokens = read_tokens(home)?;
+        tokens.insert(host.clone(), token.trim().to_string());
+        write_tokens(home, &tokens)?;
+        TokenStore::File
+    };
+
+    let mut index = read_index(home)?;
+    index.hosts.insert(host, store);
+    write_index(home, &index)?;
+    Ok(store)
+}
+
+/// Token stored for `host`, from whichever store holds it.
+pub fn get_token_in(home: &Path, host: &str) -> Option<String> {
+    let host = normalize(host);
+    match read_index(home).ok()?.hosts.get(&host)? {
+        TokenStore::Keyring => keyring_entry(&host).ok()?.get_password().ok(),
+        TokenStore::File => read_tokens(home).ok()?.remove(&host),
+    }
+}
+
+/// Forget the token for `host`. Returns whether there was one.
+pub fn delete_token_in(home: &Path, host: &str) -> Result<bool, XskillError> {
+    let host = normalize(host);
+    let mut index = read_index(home)?;
+    let Some(store) = index.hosts.remove(&host) else {
+        return Ok(false);
+    };
+    match store {
+        TokenStore::Keyring => {
+            if let Ok(entry) = keyring_entry(&host) {
+                let _ = entry.delete_credential();
+            }
+        }
+        TokenStore::File => {
+            let mut tokens = read_tokens(home)?;
+            tokens.remove(&host);
+            write_tokens(home, &tokens)?;
+        }
+    }
+    write_index(home, &index)?;
+    Ok(true)
+}
+
+pub fn list_in(home: &Path) -> Result<Vec<StoredCredential>, XskillError> {
+    Ok(read_index(home)?.hosts.into_iter().map(|(host, store)| StoredCredential { host, store }).collect())
+}
+
+pub fn get_token(host: &str) -> Option<String> {
+    get_token_in(&home().ok()?, host)
+}
+
+#[tauri::command]
+pub async fn set_forge_token(host: String, token: String) -> Result<TokenStore, XskillError> {
+    set_token_in(&home()?, &host, &token, default_store())
+}
+
+#[tauri::command]
+pub async fn delete_forge_token(host: String) -> Result<bool, XskillError> {
+    delete_token_in(&home()?, &host)
+}
+
+#[tauri::command]
+pub async fn list_forge_tokens() -> Result<Vec<StoredCredential>, XskillError> {
+    list_in(&home()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_store_roundtrip_is_owner_only() {
+        let home = TempDir::new().unwrap();
+
+        assert_eq!(set_token_in(home.path(), "Git.Acme.Corp", "glpat-secret", TokenStore::File).unwrap(), TokenStore::File);
+        set_token_in(home.path(), "github.acme.corp", "ghp-other", TokenStore::File).unwrap();
+        assert_eq!(get_token_in(home.path(), "git.acme.corp").as_deref(), Some("glpat-secret"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(home.path().join(TOKENS_FILE)).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(!fs::read_to_string(home.path().join(INDEX_FILE)).unwrap().contains("secret"));
+
+        set_token_in(home.path(), "git.acme.corp", "glpat-rotated", TokenStore::File).unwrap();
+        assert_eq!(get_token_in(home.path(), "git.acme.corp").as_deref(), Some("glpat-rotated"));
+
+        assert!(delete_token_in(home.path(), "git.acme.corp").unwrap());
+        assert!(!delete_token_in(home.path(), "git.acme.corp").unwrap());
+        assert_eq!(get_token_in(home.path(), "git.acme.corp"), None);
+        assert_eq!(list_in(home.path()).unwrap(), vec![StoredCredential { host: "github.acme.corp".to_string(), store: TokenStore::File }]);
+    }
+
+    #[test]
+    fn test_corrupt_index_is_an_error_and_left_alone() {
+        let home = TempDir::new().unwrap();
+        let index = home.path().join(INDEX_FILE);
+        fs::create_dir_all(index.parent().unwrap()).unwrap();
+        fs::write(&index, "{ not json").unwrap();
+
+        assert_eq!(list_in(home.path()).unwrap_err().code(), "config");
+        assert_eq!(set_token_in(home.path(), "git.acme.corp", "glpat-secret", TokenStore::File).unwrap_err().code(), "config");
+        assert_eq!(fs::read_to_string(&index).unwrap(), "{ not json");
+    }
+}
@@ -0,0 +1,228 @@
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Error returned by xskill commands.
+///
+/// Each variant has a stable `code()` that the UI and scripts can match on. The frontend
+/// receives it as `{ "code": ..., "message": ..., <context fields> }` and the CLI exits
+/// with `exit_code()`. Functions that still return `Result<_, String>` convert in both
+/// directions with `?`; plain strings become `Other`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XskillError {
+    /// A skill of that name is already installed at `path`.
+    AlreadyExists { skill: String, path: PathBuf },
+    SkillNotFound { skill: String },
+    PathNotFound { path: PathBuf },
+    InvalidName { name: String, reason: String },
+    UnknownAgent { agent: String },
+    /// A malformed argument: URL, option value, conflicting flags.
+    InvalidInput { message: String },
+    PermissionDenied { path: PathBuf, message: String },
+    Io { path: Option<PathBuf>, message: String },
+    Git { message: String },
+    Network { message: String },
+    /// Checksum, package manifest or signature did not verify.
+    Integrity { message: String },
+    /// Refused by the audit or trust policy.
+    Blocked { skill: String, message: String },
+    /// The destination has changes xskill will not overwrite.
+    Conflict { path: PathBuf, message: String },
+    Config { path: PathBuf, message: String },
+    Other { message: String },
+}
+
+impl XskillError {
+    /// Stable identifier, e.g. `"already_exists"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            XskillError::AlreadyExists { .. } => "already_exists",
+            XskillError::SkillNotFound { .. } => "skill_not_found",
+            XskillError::PathNotFound { .. } => "path_not_found",
+            XskillError::InvalidName { .. } => "invalid_name",
+            XskillError::UnknownAgent { .. } => "unknown_agent",
+            XskillError::InvalidInput { .. } => "invalid_input",
+            XskillError::PermissionDenied { .. } => "permission_denied",
+            XskillError::Io { .. } => "io",
+            XskillError::Git { .. } => "git",
+            XskillError::Network { .. } => "network",
+            XskillError::Integrity { .. } => "integrity",
+            XskillError::Blocked { .. } => "blocked",
+            XskillError::Conflict { .. } => "conflict",
+            XskillError::Config { .. } => "config",
+            XskillError::Other { .. } => "other",
+        }
+    }
+
+    /// Process exit code for the CLI. 1 is a generic failure, 2 a usage error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            XskillError::Other { .. } => 1,
+            XskillError::InvalidInput { .. } | XskillError::InvalidName { .. } | XskillError::UnknownAgent { .. } | XskillError::Config { .. } => 2,
+            XskillError::SkillNotFound { .. } | XskillError::PathNotFound { .. } => 3,
+            XskillError::AlreadyExists { .. } | XskillError::Conflict { .. } => 4,
+            XskillError::PermissionDenied { .. } | XskillError::Io { .. } => 5,
+            XskillError::Git { .. } | XskillError::Network { .. } => 6,
+            XskillError::Integrity { .. } => 7,
+            XskillError::Blocked { .. } => 8,
+        }
+    }
+
+    /// Classify an I/O error on `path` by its kind.
+    pub fn io(path: &Path, err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => XskillError::PathNotFound { path: path.to_path_buf() },
+            std::io::ErrorKind::PermissionDenied => XskillError::PermissionDenied { path: path.to_path_buf(), message: err.to_string() },
+            _ => XskillError::Io { path: Some(path.to_path_buf()), message: err.to_string() },
+        }
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        XskillError::InvalidInput { message: message.into() }
+    }
+
+    pub fn git(message: impl Into<String>) -> Self {
+        XskillError::Git { message: message.into() }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        XskillError::Network { message: message.into() }
+    }
+
+    pub fn integrity(message: impl Into<String>) -> Self {
+        XskillError::Integrity { message: message.into() }
+    }
+
+    pub fn already_exists(skill: &str, path: &Path) -> Self {
+        XskillError::AlreadyExists { skill: skill.to_string(), path: path.to_path_buf() }
+    }
+
+    pub fn path_not_found(path: &Path) -> Self {
+        XskillError::PathNotFound { path: path.to_path_buf() }
+    }
+
+    /// Prefix the message with `context` (e.g. the agent a sync failed for), keeping the
+    /// variant. Errors without a message already name their skill, path or agent.
+    pub fn context(mut self, context: &str) -> Self {
+        match &mut self {
+            XskillError::InvalidInput { message }
+            | XskillError::PermissionDenied { message, .. }
+            | XskillError::Io { message, .. }
+            | XskillError::Git { message }
+            | XskillError::Network { message }
+            | XskillError::Integrity { message }
+            | XskillError::Blocked { message, .. }
+            | XskillError::Conflict { message, .. }
+            | XskillError::Config { message, .. }
+            | XskillError::Other { message } => *message = format!("{}: {}", context, message),
+            XskillError::AlreadyExists { .. }
+            | XskillError::SkillNotFound { .. }
+            | XskillError::PathNotFound { .. }
+            | XskillError::InvalidName { .. }
+            | XskillError::UnknownAgent { .. } => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for XskillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            XskillError::AlreadyExists { skill, path } => write!(f, "Skill '{}' already exists at {}", skill, path.display()),
+            XskillError::SkillNotFound { skill } => write!(f, "Skill '{}' not found", skill),
+            XskillError::PathNotFound { path } => write!(f, "Path does not exist: {}", path.display()),
+            XskillError::InvalidName { name, reason } => write!(f, "Invalid name '{}': {}", name, reason),
+            XskillError::UnknownAgent { agent } => write!(f, "Unknown agent: {}", agent),
+            XskillError::PermissionDenied { path, message } => write!(f, "Permission denied for {}: {}", path.display(), message),
+            XskillError::Io { path: Some(path), message } => write!(f, "{}: {}", path.display(), message),
+            XskillError::Config { path, message } => write!(f, "Invalid config {}: {}", path.display(), message),
+            XskillError::Conflict { message, .. } | XskillError::Blocked { message, .. } => f.write_str(message),
+            XskillError::Io { path: None, message }
+            | XskillError::InvalidInput { message }
+            | XskillError::Git { message }
+            | XskillError::Network { message }
+            | XskillError::Integrity { message }
+            | XskillError::Other { message } => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for XskillError {}
+
+impl Serialize for XskillError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            XskillError::AlreadyExists { skill, path } => {
+                map.serialize_entry("skill", skill)?;
+                map.serialize_entry("path", path)?;
+            }
+            XskillError::SkillNotFound { skill } | XskillError::Blocked { skill, .. } => map.serialize_entry("skill", skill)?,
+            XskillError::InvalidName { name, .. } => map.serialize_entry("skill", name)?,
+            XskillError::UnknownAgent { agent } => map.serialize_entry("agent", agent)?,
+            XskillError::PathNotFound { path }
+            | XskillError::PermissionDenied { path, .. }
+            | XskillError::Io { path: Some(path), .. }
+            | XskillError::Conflict { path, .. }
+            | XskillError::Config { path, .. } => map.serialize_entry("path", path)?,
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+impl From<String> for XskillError {
+    fn from(message: String) -> Self {
+        XskillError::Other { message }
+    }
+}
+
+impl From<&str> for XskillError {
+    fn from(message: &str) -> Self {
+        XskillError::Other { message: message.to_string() }
+    }
+}
+
+impl From<std::io::Error> for XskillError {
+    fn from(err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::PermissionDenied => XskillError::PermissionDenied { path: PathBuf::new(), message: err.to_string() },
+            _ => XskillError::Io { path: None, message: err.to_string() },
+        }
+    }
+}
+
+impl From<XskillError> for String {
+    fn from(err: XskillError) -> Self {
+        err.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_as_tagged_object_with_context() {
+        let err = XskillError::already_exists("pdf", Path::new("/hub/pdf"));
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "already_exists");
+        assert_eq!(json["skill"], "pdf");
+        assert_eq!(json["path"], "/hub/pdf");
+        assert_eq!(json["message"], "Skill 'pdf' already exists at /hub/pdf");
+        assert_eq!(err.exit_code(), 4);
+
+        let denied = XskillError::io(Path::new("/etc/x"), std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert_eq!(denied.code(), "permission_denied");
+        let plain: XskillError = "boom".to_string().into();
+        assert_eq!((plain.code(), plain.exit_code()), ("other", 1));
+        assert_eq!(String::from(plain), "boom");
+
+        let io = XskillError::io(Path::new("/a/b"), std::io::Error::other("disk full")).context("cursor");
+        assert_eq!(io.code(), "io");
+        assert_eq!(io.to_string(), "/a/b: cursor: disk full");
+        assert_eq!(XskillError::git("fetch failed").context("cursor").to_string(), "cursor: fetch failed");
+    }
+}
@@ -0,0 +1,330 @@
+//! In-memory `GitBackend` for tests that should not depend on a `git` binary or libgit2.
+//!
+//! Remotes are kept in memory with a linear history per branch. A clone is a plain
+//! directory whose refs and objects are stored as JSON in `.git/fake.json`
+//! (`fake.json` for bare clones).
+
+use crate::error::XskillError;
+use crate::git_backend::{CloneOptions, GitBackend, Progress, TreeFile};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Commit {
+    parent: Option<String>,
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Remote {
+    default_branch: String,
+    branches: BTreeMap<String, String>,
+    tags: BTreeMap<String, String>,
+    commits: HashMap<String, Commit>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LocalClone {
+    url: String,
+    bare: bool,
+    head: String,
+    branch: Option<String>,
+    default_branch: String,
+    branches: BTreeMap<String, String>,
+    tags: BTreeMap<String, String>,
+    commits: HashMap<String, Commit>,
+}
+
+#[derive(Default)]
+pub struct FakeGit {
+    remotes: Mutex<HashMap<String, Remote>>,
+    /// Every backend call, as `"<method> <arg>"`.
+    pub calls: Mutex<Vec<String>>,
+}
+
+fn blob_id(content: &[u8]) -> String {
+    Sha256::digest(content).iter().take(20).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn state_file(path: &Path) -> PathBuf {
+    if path.join("fake.json").exists() {
+        path.join("fake.json")
+    } else {
+        path.join(".git/fake.json")
+    }
+}
+
+fn load(path: &Path) -> Result<LocalClone, XskillError> {
+    let content = fs::read_to_string(state_file(path)).map_err(|_| XskillError::git(format!("Not a repository: {}", path.display())))?;
+    serde_json::from_str(&content).map_err(|e| XskillError::git(e.to_string()))
+}
+
+fn save(path: &Path, clone: &LocalClone) -> Result<(), XskillError> {
+    let file = if clone.bare { path.join("fake.json") } else { path.join(".git/fake.json") };
+    fs::create_dir_all(file.parent().unwrap()).map_err(|e| XskillError::io(path, e))?;
+    fs::write(&file, serde_json::to_string(clone).unwrap()).map_err(|e| XskillError::io(&file, e))
+}
+
+impl LocalClone {
+    fn resolve(&self, rev: &str) -> Result<String, XskillError> {
+        let rev = rev.trim_end_matches("^{commit}");
+        let branch = rev
+            .strip_prefix("refs/heads/")
+            .or_else(|| rev.strip_prefix("refs/remotes/origin/"))
+            .or_else(|| rev.strip_prefix("origin/"))
+            .unwrap_or(rev);
+        let found = match branch {
+            "HEAD" if rev == "HEAD" && !self.bare => Some(self.head.clone()),
+            "HEAD" => self.branches.get(&self.default_branch).cloned(),
+            _ => self
+                .branches
+                .get(branch)
+                .or_else(|| self.tags.get(rev.strip_prefix("refs/tags/").unwrap_or(rev)))
+                .cloned()
+                .or_else(|| (rev.len() >= 7).then(|| self.commits.keys().find(|id| id.starts_with(rev)).cloned()).flatten()),
+        };
+        found.ok_or_else(|| XskillError::git(format!("Revision {} not found in repository", rev)))
+    }
+
+    /// `id` and its ancestors, newest first.
+    fn history(&self, id: &str) -> Vec<String> {
+        let mut ids = Vec::new();
+        let mut next = Some(id.to_string());
+        while let Some(id) = next {
+            next = self.commits.get(&id).and_then(|c| c.parent.clone());
+            ids.push(id);
+        }
+        ids
+    }
+
+    fn files(&self, id: &str) -> &BTreeMap<String, Vec<u8>> {
+        &self.commits[id].files
+    }
+
+    fn write_worktree(&self, path: &Path, from: Option<&str>, to: &str) -> Result<(), XskillError> {
+        if let Some(from) = from {
+            for rel in self.files(from).keys() {
+                let _ = fs::remove_file(path.join(rel));
+            }
+        }
+        for (rel, content) in self.files(to) {
+            let target = path.join(rel);
+            fs::create_dir_all(target.parent().unwrap()).map_err(|e| XskillError::io(&target, e))?;
+            fs::write(&target, content).map_err(|e| XskillError::io(&target, e))?;
+        }
+        Ok(())
+    }
+}
+
+fn under<'a>(path: &'a str, subpath: Option<&str>) -> Option<&'a str> {
+    match subpath.map(|p| p.trim_matches('/')).filter(|p| !p.is_empty()) {
+        Some(sub) => path.strip_prefix(sub).and_then(|rest| rest.strip_prefix('/')),
+        None => Some(path),
+    }
+}
+
+impl FakeGit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Commit `files` on top of `branch` in the remote at `url` and return the commit id.
+    /// The first branch committed to becomes the default branch.
+    pub fn commit(&self, url: &str, branch: &str, files: &[(&str, &str)]) -> String {
+        let mut remotes = self.remotes.lock().unwrap();
+        let remote = remotes.entry(url.to_string()).or_default();
+        if remote.default_branch.is_empty() {
+            remote.default_branch = branch.to_string();
+        }
+        let parent = remote.branches.get(branch).cloned();
+        let mut tree = parent.as_ref().map(|p| remote.commits[p].files.clone()).unwrap_or_default();
+        for (path, content) in files {
+            tree.insert(path.to_string(), content.as_bytes().to_vec());
+        }
+        let id = blob_id(format!("{}:{:?}:{:?}", remote.commits.len(), parent, tree).as_bytes());
+        remote.commits.insert(id.clone(), Commit { parent, files: tree });
+        remote.branches.insert(branch.to_string(), id.clone());
+        id
+    }
+
+    pub fn tag(&self, url: &str, tag: &str, commit: &str) {
+        let mut remotes = self.remotes.lock().unwrap();
+        remotes.get_mut(url).expect("unknown remote").tags.insert(tag.to_string(), commit.to_string());
+    }
+
+    fn record(&self, method: &str, arg: impl std::fmt::Display) {
+        self.calls.lock().unwrap().push(format!("{} {}", method, arg));
+    }
+
+    fn remote(&self, url: &str) -> Result<Remote, XskillError> {
+        self.remotes.lock().unwrap().get(url).cloned().ok_or_else(|| XskillError::git(format!("git clone failed: repository '{}' not found", url)))
+    }
+}
+
+impl GitBackend for FakeGit {
+    fn clone_repo(&self, url: &str, dest: &Path, opts: &CloneOptions, progress: Progress) -> Result<(), XskillError> {
+        self.record("clone", url);
+        let remote = self.remote(url)?;
+        let count = remote.commits.len();
+        progress(format!("Receiving objects: 100% ({}/{}), 0.1 KiB", count, count));
+        let head = remote.branches[&remote.default_branch].clone();
+        let clone = LocalClone {
+            url: url.to_string(),
+            bare: opts.bare,
+            head: head.clone(),
+            branch: Some(remote.default_branch.clone()),
+            default_branch: remote.default_branch,
+            branches: remote.branches,
+            tags: remote.tags,
+            commits: remote.commits,
+        };
+        fs::create_dir_all(dest).map_err(|e| XskillError::io(dest, e))?;
+        if !opts.bare {
+            clone.write_worktree(dest, None, &head)?;
+        }
+        save(dest, &clone)
+    }
+
+    fn fetch(&self, repo: &Path, progress: Progress) -> Result<(), XskillError> {
+        self.record("fetch", repo.display());
+        let mut clone = load(repo)?;
+        let remote = self.remote(&clone.url)?;
+        progress("Receiving objects: 100% (0/0), 0.0 KiB".to_string());
+        clone.branches = remote.branches;
+        clone.tags = remote.tags;
+        clone.commits.extend(remote.commits);
+        save(repo, &clone)
+    }
+
+    fn resolve(&self, repo: &Path, rev: &str) -> Result<String, XskillError> {
+        load(repo)?.resolve(rev)
+    }
+
+    fn checkout(&self, repo: &Path, rev: &str) -> Result<String, XskillError> {
+        self.record("checkout", rev);
+        let mut clone = load(repo)?;
+        let id = clone.resolve(rev)?;
+        clone.write_worktree(repo, Some(&clone.head), &id)?;
+        clone.head = id.clone();
+        clone.branch = None;
+        save(repo, &clone)?;
+        Ok(id)
+    }
+
+    fn fast_forward(&self, repo: &Path) -> Result<String, XskillError> {
+        self.record("fast_forward", repo.display());
+        let mut clone = load(repo)?;
+        let branch = clone.branch.clone().ok_or_else(|| XskillError::git("HEAD is detached; nothing to fast-forward"))?;
+        let target = clone.resolve(&branch)?;
+        if !clone.history(&target).contains(&clone.head) {
+            return Err(XskillError::git("Not possible to fast-forward: the local branch has diverged"));
+        }
+        clone.write_worktree(repo, Some(&clone.head), &target)?;
+        clone.head = target.clone();
+        save(repo, &clone)?;
+        Ok(target)
+    }
+
+    fn current_branch(&self, repo: &Path) -> Result<Option<String>, XskillError> {
+        Ok(load(repo)?.branch)
+    }
+
+    fn remote_url(&self, repo: &Path) -> Result<String, XskillError> {
+        Ok(load(repo)?.url)
+    }
+
+    fn ahead_behind(&self, repo: &Path, local: &str, upstream: &str) -> Result<(usize, usize), XskillError> {
+        let clone = load(repo)?;
+        let local: HashSet<String> = clone.history(&clone.resolve(local)?).into_iter().collect();
+        let upstream: HashSet<String> = clone.history(&clone.resolve(upstream)?).into_iter().collect();
+        Ok((local.difference(&upstream).count(), upstream.difference(&local).count()))
+    }
+
+    fn count_commits(&self, repo: &Path, from: &str, to: &str, subpath: Option<&str>) -> Result<usize, XskillError> {
+        let clone = load(repo)?;
+        let from = clone.history(&clone.resolve(from)?);
+        let subtree = |id: &str| -> Vec<(String, Vec<u8>)> {
+            clone.files(id).iter().filter(|(p, _)| under(p, subpath).is_some()).map(|(p, c)| (p.clone(), c.clone())).collect()
+        };
+        Ok(clone
+            .history(&clone.resolve(to)?)
+            .iter()
+            .take_while(|id| !from.contains(id))
+            .filter(|id| {
+                let parent = clone.commits[id.as_str()].parent.as_deref();
+                subpath.is_none() || parent.map(subtree).unwrap_or_default() != subtree(id)
+            })
+            .count())
+    }
+
+    fn changed_paths(&self, repo: &Path, from: &str, to: &str, subpath: Option<&str>) -> Result<Vec<String>, XskillError> {
+        let clone = load(repo)?;
+        let to = clone.resolve(to)?;
+        let from = clone.history(&clone.resolve(from)?);
+        let base = clone.history(&to).into_iter().find(|id| from.contains(id)).ok_or_else(|| XskillError::git("No common ancestor"))?;
+        let (old, new) = (clone.files(&base), clone.files(&to));
+        let mut paths: Vec<String> = old
+            .keys()
+            .chain(new.keys())
+            .filter(|p| old.get(*p) != new.get(*p))
+            .filter_map(|p| under(p, subpath).map(str::to_string))
+            .collect();
+        paths.sort();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    fn modified_paths(&self, repo: &Path) -> Result<Vec<String>, XskillError> {
+        let clone = load(repo)?;
+        Ok(clone
+            .files(&clone.head)
+            .iter()
+            .filter(|(rel, content)| fs::read(repo.join(rel)).ok().as_ref() != Some(*content))
+            .map(|(rel, _)| rel.clone())
+            .collect())
+    }
+
+    fn list_files(&self, repo: &Path, rev: &str, subpath: Option<&str>) -> Result<Vec<TreeFile>, XskillError> {
+        let clone = load(repo)?;
+        let files: Vec<TreeFile> = clone
+            .files(&clone.resolve(rev)?)
+            .iter()
+            .filter_map(|(p, content)| {
+                under(p, subpath).map(|rel| TreeFile { path: rel.to_string(), id: blob_id(content), mode: 0o100644 })
+            })
+            .collect();
+        if files.is_empty() && subpath.is_some() {
+            return Err(XskillError::git(format!("Subdirectory {} not found in repository", subpath.unwrap_or_default())));
+        }
+        Ok(files)
+    }
+
+    fn read_file(&self, repo: &Path, rev: &str, path: &str) -> Result<Vec<u8>, XskillError> {
+        let clone = load(repo)?;
+        clone.files(&clone.resolve(rev)?).get(path).cloned().ok_or_else(|| XskillError::git(format!("{} not found at {}", path, rev)))
+    }
+
+    fn read_blob(&self, repo: &Path, id: &str) -> Result<Vec<u8>, XskillError> {
+        let clone = load(repo)?;
+        clone
+            .commits
+            .values()
+            .flat_map(|c| c.files.values())
+            .find(|content| blob_id(content) == id)
+            .cloned()
+            .ok_or_else(|| XskillError::git(format!("Blob {} not found", id)))
+    }
+
+    fn read_tag(&self, _repo: &Path, _name: &str) -> Result<Option<(Vec<u8>, String)>, XskillError> {
+        // Fake tags are lightweight
+        Ok(None)
+    }
+
+    fn hash_file(&self, path: &Path) -> Result<String, XskillError> {
+        fs::read(path).map(|content| blob_id(&content)).map_err(|e| XskillError::io(path, e))
+    }
+}
@@ -1,16 +1,11 @@
+use crate::error::XskillError;
 use serde_json::Value;
 
 #[tauri::command]
-pub async fn fetch_feed(url: String) -> Result<Value, String> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| e.to_string())?;
-
-    let response = client.get(&url)
-        .send()
+pub async fn fetch_feed(url: String) -> Result<Value, XskillError> {
+    let response = crate::forge::get(&url, std::time::Duration::from_secs(10), None)
         .await
-        .map_err(|e| format!("Failed to fetch feed: {}", e))?;
+        .map_err(|e| XskillError::network(format!("Failed to fetch feed: {}", e)))?;
 
     let json: Value = response
         .json()
@@ -28,6 +23,8 @@ mod tests {
     async fn test_fetch_feed_invalid_url() {
         let result = fetch_feed("not-a-url".to_string()).await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Failed to fetch feed"));
+        let err = result.unwrap_err();
+        assert_eq!(err.code(), "network");
+        assert!(err.to_string().contains("Failed to fetch feed"));
     }
 }
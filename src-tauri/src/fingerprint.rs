@@ -4,7 +4,8 @@ use std::io;
 use std::path::Path;
 use walkdir::WalkDir;
 
-const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "dist", "target", "build", ".idea", ".vscode"];
+/// Directories left out of fingerprints (and of packages, which carry a fingerprint).
+pub const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "dist", "target", "build", ".idea", ".vscode"];
 
 pub fn calculate_dir_hash(root_path: &Path) -> Result<String, String> {
     let mut hasher = Sha256::new();
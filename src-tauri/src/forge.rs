@@ -0,0 +1,430 @@
+use crate::error::XskillError;
+use reqwest::header::{ACCEPT, AUTHORIZATION, LOCATION, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File under `~/.xskill` that declares self-hosted forges.
+pub const FORGES_FILE: &str = "forges.toml";
+
+/// Kind of server a repository URL points at. GitHub Enterprise is `Github` on another host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+    Gitea,
+    /// Plain git server (usually SSH) without a web UI xskill understands.
+    Git,
+}
+
+impl std::str::FromStr for ForgeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "github" => Ok(Self::Github),
+            "gitlab" => Ok(Self::Gitlab),
+            "gitea" | "forgejo" => Ok(Self::Gitea),
+            "git" | "ssh" => Ok(Self::Git),
+            _ => Err(format!("Unknown forge kind '{}' (expected github, gitlab, gitea or git)", s)),
+        }
+    }
+}
+
+/// One `[[forges]]` entry in `~/.xskill/forges.toml`.
+///
+/// ```toml
+/// [[forges]]
+/// host = "git.acme.corp"
+/// kind = "gitlab"
+///
+/// [[forges]]
+/// host = "github.acme.corp"
+/// kind = "github"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForgeHost {
+    pub host: String,
+    pub kind: ForgeKind,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ForgesFile {
+    #[serde(default)]
+    forges: Vec<ForgeHost>,
+}
+
+const BUILTIN: &[(&str, ForgeKind)] = &[
+    ("github.com", ForgeKind::Github),
+    ("raw.githubusercontent.com", ForgeKind::Github),
+    ("gitlab.com", ForgeKind::Gitlab),
+    ("codeberg.org", ForgeKind::Gitea),
+    ("gitea.com", ForgeKind::Gitea),
+];
+
+/// The forge a URL belongs to, with its host normalised for token lookup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forge {
+    /// Host tokens are stored under; `raw.githubusercontent.com` maps to `github.com`.
+    pub host: String,
+    pub kind: ForgeKind,
+}
+
+/// Host of an `https://`, `ssh://` or scp-style (`git@host:owner/repo`) URL, lowercased.
+pub fn host_of(url: &str) -> Option<String> {
+    if let Ok(parsed) = url::Url::parse(url) {
+        if let Some(host) = parsed.host_str() {
+            return Some(host.to_ascii_lowercase());
+        }
+    }
+    // scp-like syntax: [user@]host:path
+    let (before, _) = url.split_once(':')?;
+    let host = before.rsplit('@').next()?;
+    (!host.is_empty() && !host.contains('/')).then(|| host.to_ascii_lowercase())
+}
+
+/// Known forge hosts: the built-in public ones merged with `~/.xskill/forges.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct Forges {
+    hosts: Vec<ForgeHost>,
+}
+
+impl Forges {
+    pub fn builtin() -> Self {
+        let hosts = BUILTIN.iter().map(|(host, kind)| ForgeHost { host: host.to_string(), kind: *kind }).collect();
+        Self { hosts }
+    }
+
+    /// Built-in forges plus `~/.xskill/forges.toml`, if present.
+    pub fn load() -> Result<Self, String> {
+        let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+        Self::load_from_home(&home)
+    }
+
+    pub fn load_from_home(home: &Path) -> Result<Self, String> {
+        let mut forges = Self::builtin();
+        for entry in read_file(&forges_path(home))?.forges {
+            forges.add(entry);
+        }
+        Ok(forges)
+    }
+
+    /// Add or replace the entry for `entry.host`.
+    pub fn add(&mut self, entry: ForgeHost) {
+        let host = entry.host.to_ascii_lowercase();
+        self.hosts.retain(|h| h.host != host);
+        self.hosts.push(ForgeHost { host, ..entry });
+    }
+
+    pub fn hosts(&self) -> &[ForgeHost] {
+        &self.hosts
+    }
+
+    /// Forge for `url`. Hosts that are not configured are recognised by their URL layout
+    /// (`/-/` for GitLab, `/src/branch/` for Gitea, `/tree/` or `/blob/` for GitHub Enterprise)
+    /// and otherwise treated as plain git.
+    pub fn detect(&self, url: &str) -> Forge {
+        let host = host_of(url).unwrap_or_default();
+        let kind = self.hosts.iter().find(|h| h.host == host).map(|h| h.kind).unwrap_or_else(|| guess_kind(url));
+        let host = if host == "raw.githubusercontent.com" { "github.com".to_string() } else { host };
+        Forge { host, kind }
+    }
+}
+
+fn guess_kind(url: &str) -> ForgeKind {
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        ForgeKind::Git
+    } else if url.contains("/-/") {
+        ForgeKind::Gitlab
+    } else if ["/src/branch/", "/src/tag/", "/src/commit/", "/raw/branch/"].iter().any(|p| url.contains(p)) {
+        ForgeKind::Gitea
+    } else if url.contains("/tree/") || url.contains("/blob/") {
+        ForgeKind::Github
+    } else {
+        ForgeKind::Git
+    }
+}
+
+fn forges_path(home: &Path) -> PathBuf {
+    home.join(".xskill").join(FORGES_FILE)
+}
+
+fn read_file(path: &Path) -> Result<ForgesFile, String> {
+    if !path.exists() {
+        return Ok(ForgesFile::default());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    toml::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Declare `host` as a forge of `kind` in `~/.xskill/forges.toml`.
+pub fn save_forge(home: &Path, host: &str, kind: ForgeKind) -> Result<(), XskillError> {
+    let path = forges_path(home);
+    let mut file = read_file(&path).map_err(|message| XskillError::Config { path: path.clone(), message })?;
+    let host = host.trim().to_ascii_lowercase();
+    if host.is_empty() {
+        return Err(XskillError::invalid("Forge host must not be empty"));
+    }
+    file.forges.retain(|f| f.host != host);
+    file.forges.push(ForgeHost { host, kind });
+    let content = toml::to_string_pretty(&file).map_err(|e| e.to_string())?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+    }
+    fs::write(&path, content).map_err(|e| XskillError::io(&path, e))
+}
+
+impl Forge {
+    /// Split a web URL that points into a repository (`/tree/<ref>/<path>` on GitHub,
+    /// `/-/tree/<ref>/<path>` on GitLab, `/src/branch/<ref>/<path>` on Gitea) into
+    /// repository URL, ref and path. Plain git URLs accept the GitHub layout.
+    pub fn parse_tree_url(&self, url: &str) -> Option<(String, String, String)> {
+        let (repo, rest) = match self.kind {
+            ForgeKind::Github | ForgeKind::Git => url.split_once("/tree/")?,
+            ForgeKind::Gitlab => url.split_once("/-/tree/")?,
+            ForgeKind::Gitea => {
+                let (repo, rest) = url.split_once("/src/")?;
+                let (_, rest) = rest.split_once('/').filter(|(kind, _)| ["branch", "tag", "commit"].contains(kind))?;
+                (repo, rest)
+            }
+        };
+        let (git_ref, subpath) = rest.split_once('/')?;
+        if git_ref.is_empty() || subpath.trim_matches('/').is_empty() {
+            return None;
+        }
+        Some((repo.to_string(), git_ref.to_string(), subpath.trim_end_matches('/').to_string()))
+    }
+
+    /// Web URL for `subpath` of `repo` at `git_ref`, the inverse of `parse_tree_url`.
+    pub fn tree_url(&self, repo: &str, git_ref: &str, subpath: &str) -> String {
+        match self.kind {
+            ForgeKind::Gitlab => format!("{}/-/tree/{}/{}", repo, git_ref, subpath),
+            ForgeKind::Gitea => format!("{}/src/branch/{}/{}", repo, git_ref, subpath),
+            ForgeKind::Github | ForgeKind::Git => format!("{}/tree/{}/{}", repo, git_ref, subpath),
+        }
+    }
+
+    /// URL that serves the raw content of a file link (a `blob` / `src` page or an already
+    /// raw URL). GitHub Enterprise goes through the contents API, which accepts tokens.
+    pub fn raw_url(&self, url: &str) -> Option<String> {
+        match self.kind {
+            ForgeKind::Github if url.contains("raw.githubusercontent.com") => Some(url.to_string()),
+            ForgeKind::Github if self.host == "github.com" => {
+                url.contains("/blob/").then(|| url.replace("github.com", "raw.githubusercontent.com").replace("/blob/", "/"))
+            }
+            ForgeKind::Github => {
+                if url.contains("/api/v3/repos/") {
+                    return Some(url.to_string());
+                }
+                let (repo, rest) = url.split_once("/blob/")?;
+                let (git_ref, path) = rest.split_once('/')?;
+                let owner_repo = repo.split_once(&self.host)?.1.trim_start_matches('/');
+                Some(format!("https://{}/api/v3/repos/{}/contents/{}?ref={}", self.host, owner_repo, path, git_ref))
+            }
+            ForgeKind::Gitlab if url.contains("/-/raw/") => Some(url.to_string()),
+            ForgeKind::Gitlab => url.contains("/-/blob/").then(|| url.replace("/-/blob/", "/-/raw/")),
+            ForgeKind::Gitea if url.contains("/raw/") => Some(url.to_string()),
+            ForgeKind::Gitea => url.contains("/src/").then(|| url.replacen("/src/", "/raw/", 1)),
+            ForgeKind::Git => None,
+        }
+    }
+
+    /// HTTP header that carries `token` on this forge's web and API endpoints.
+    pub fn auth_header(&self, token: &str) -> (&'static str, String) {
+        match self.kind {
+            ForgeKind::Gitlab => ("PRIVATE-TOKEN", token.to_string()),
+            ForgeKind::Gitea => (AUTHORIZATION.as_str(), format!("token {}", token)),
+            ForgeKind::Github | ForgeKind::Git => (AUTHORIZATION.as_str(), format!("Bearer {}", token)),
+        }
+    }
+
+    /// User name to pair with a token for git over HTTPS.
+    pub fn git_username(&self) -> &'static str {
+        match self.kind {
+            ForgeKind::Github => "x-access-token",
+            ForgeKind::Gitlab => "oauth2",
+            ForgeKind::Gitea | ForgeKind::Git => "xskill",
+        }
+    }
+
+    /// Token stored for this forge's host, if any.
+    pub fn token(&self) -> Option<String> {
+        crate::credentials::get_token(&self.host)
+    }
+}
+
+/// Forge for `url` using the configured hosts; configuration errors fall back to the built-ins.
+pub fn detect(url: &str) -> Forge {
+    Forges::load().unwrap_or_else(|_| Forges::builtin()).detect(url)
+}
+
+/// Repository URL, ref and path of a tree URL on any known forge.
+pub fn parse_tree_url(url: &str) -> Option<(String, String, String)> {
+    detect(url).parse_tree_url(url)
+}
+
+/// Redirects [`get`] follows before giving up.
+const MAX_REDIRECTS: usize = 10;
+
+/// Whether a token stored for `origin` may be sent to `target`: only over https, and only
+/// to the same host and port.
+fn sends_token(origin: &reqwest::Url, target: &reqwest::Url) -> bool {
+    origin.scheme() == "https"
+        && target.scheme() == "https"
+        && origin.host_str() == target.host_str()
+        && origin.port_or_known_default() == target.port_or_known_default()
+}
+
+/// GET `url` with the user agent, an optional `Accept` header and, when a token is stored
+/// for its host, the forge's auth header.
+///
+/// Redirects are followed here rather than by reqwest, which strips `Authorization` on a
+/// host change but not GitLab's `PRIVATE-TOKEN`: the token only goes where [`sends_token`]
+/// allows.
+pub async fn get(url: &str, timeout: std::time::Duration, accept: Option<&str>) -> Result<reqwest::Response, String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let origin = reqwest::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    let forge = detect(url);
+    let token = if origin.scheme() == "https" { forge.token() } else { None };
+
+    let mut target = origin.clone();
+    for _ in 0..=MAX_REDIRECTS {
+        let mut request = client.get(target.clone()).header(USER_AGENT, "xskill");
+        if let Some(accept) = accept {
+            request = request.header(ACCEPT, accept);
+        }
+        if let Some(token) = token.as_deref().filter(|_| sends_token(&origin, &target)) {
+            let (name, value) = forge.auth_header(token);
+            request = request.header(name, value);
+        }
+        let resp = request.send().await.map_err(|e| e.to_string())?;
+        let location = resp.headers().get(LOCATION).and_then(|l| l.to_str().ok());
+        match location {
+            Some(location) if resp.status().is_redirection() => {
+                target = target.join(location).map_err(|e| format!("Invalid redirect to {}: {}", location, e))?;
+            }
+            _ => return Ok(resp),
+        }
+    }
+    Err(format!("Too many redirects fetching {}", url))
+}
+
+/// Download the raw content of a file link on any known forge.
+pub async fn fetch_raw(url: &str) -> Result<String, String> {
+    let forge = detect(url);
+    let raw_url = forge.raw_url(url).ok_or("Unsupported URL format")?;
+
+    let accept = (forge.kind == ForgeKind::Github).then_some("application/vnd.github.raw");
+    let resp = get(&raw_url, std::time::Duration::from_secs(10), accept).await?;
+
+    if !resp.status().is_success() {
+        return Err(format!("Failed to fetch: {}", resp.status()));
+    }
+
+    resp.text().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_forges() -> Result<Vec<ForgeHost>, XskillError> {
+    Ok(Forges::load()?.hosts().to_vec())
+}
+
+#[tauri::command]
+pub async fn add_forge(host: String, kind: ForgeKind) -> Result<(), XskillError> {
+    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+    save_forge(&home, &host, kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn forges() -> Forges {
+        let mut forges = Forges::builtin();
+        forges.add(ForgeHost { host: "git.acme.corp".to_string(), kind: ForgeKind::Gitlab });
+        forges.add(ForgeHost { host: "GitHub.Acme.Corp".to_string(), kind: ForgeKind::Github });
+        forges
+    }
+
+    #[test]
+    fn test_detect_known_and_guessed_hosts() {
+        let f = forges();
+        assert_eq!(f.detect("https://github.com/acme/skills").kind, ForgeKind::Github);
+        assert_eq!(f.detect("https://raw.githubusercontent.com/a/b/main/x").host, "github.com");
+        assert_eq!(f.detect("https://git.acme.corp/team/skills.git").kind, ForgeKind::Gitlab);
+        assert_eq!(f.detect("https://github.acme.corp/team/skills").kind, ForgeKind::Github);
+        assert_eq!(f.detect("https://code.example.org/a/b/-/tree/main/x").kind, ForgeKind::Gitlab);
+        assert_eq!(f.detect("https://code.example.org/a/b/src/branch/main/x").kind, ForgeKind::Gitea);
+        assert_eq!(f.detect("https://code.example.org/a/b").kind, ForgeKind::Git);
+
+        let ssh = f.detect("git@git.acme.corp:team/skills.git");
+        assert_eq!((ssh.host.as_str(), ssh.kind), ("git.acme.corp", ForgeKind::Gitlab));
+        assert_eq!(f.detect("ssh://git@plain.example.org/srv/skills.git").kind, ForgeKind::Git);
+    }
+
+    #[test]
+    fn test_parse_and_build_tree_urls() {
+        let f = forges();
+        let gitlab = "https://git.acme.corp/team/skills/-/tree/main/tools/pdf";
+        let (repo, git_ref, sub) = f.detect(gitlab).parse_tree_url(gitlab).unwrap();
+        assert_eq!((repo.as_str(), git_ref.as_str(), sub.as_str()), ("https://git.acme.corp/team/skills", "main", "tools/pdf"));
+        assert_eq!(f.detect(gitlab).tree_url(&repo, &git_ref, &sub), gitlab);
+
+        let gitea = "https://codeberg.org/acme/skills/src/branch/dev/pdf";
+        let (repo, git_ref, sub) = f.detect(gitea).parse_tree_url(gitea).unwrap();
+        assert_eq!((repo.as_str(), git_ref.as_str(), sub.as_str()), ("https://codeberg.org/acme/skills", "dev", "pdf"));
+
+        let github = "https://github.acme.corp/team/skills/tree/v1/pdf/";
+        assert_eq!(f.detect(github).parse_tree_url(github).unwrap().2, "pdf");
+        assert!(f.detect("https://github.com/acme/skills").parse_tree_url("https://github.com/acme/skills").is_none());
+    }
+
+    #[test]
+    fn test_raw_urls_and_auth_headers() {
+        let f = forges();
+        let ghe = f.detect("https://github.acme.corp/team/skills/blob/main/feed.json");
+        assert_eq!(
+            ghe.raw_url("https://github.acme.corp/team/skills/blob/main/feed.json").unwrap(),
+            "https://github.acme.corp/api/v3/repos/team/skills/contents/feed.json?ref=main"
+        );
+        let gitlab = f.detect("https://git.acme.corp/team/skills/-/blob/main/feed.json");
+        assert_eq!(
+            gitlab.raw_url("https://git.acme.corp/team/skills/-/blob/main/feed.json").unwrap(),
+            "https://git.acme.corp/team/skills/-/raw/main/feed.json"
+        );
+        assert_eq!(gitlab.auth_header("t0k"), ("PRIVATE-TOKEN", "t0k".to_string()));
+        let gitea = f.detect("https://codeberg.org/a/b/src/branch/main/f.md");
+        assert_eq!(gitea.raw_url("https://codeberg.org/a/b/src/branch/main/f.md").unwrap(), "https://codeberg.org/a/b/raw/branch/main/f.md");
+        assert_eq!(gitea.auth_header("t0k").1, "token t0k");
+        assert!(f.detect("https://google.com").raw_url("https://google.com").is_none());
+    }
+
+    #[test]
+    fn test_tokens_stay_on_https_and_their_host() {
+        let url = |s: &str| reqwest::Url::parse(s).unwrap();
+        let origin = url("https://git.acme.corp/team/skills/-/raw/main/feed.json");
+        assert!(sends_token(&origin, &url("https://git.acme.corp/other/path")));
+        assert!(sends_token(&origin, &url("https://git.acme.corp:443/other/path")));
+        assert!(!sends_token(&origin, &url("https://cdn.example.com/feed.json")));
+        assert!(!sends_token(&origin, &url("https://git.acme.corp:8443/feed.json")));
+        assert!(!sends_token(&origin, &url("http://git.acme.corp/feed.json")));
+        assert!(!sends_token(&url("http://git.acme.corp/feed.json"), &url("http://git.acme.corp/feed.json")));
+    }
+
+    #[test]
+    fn test_forges_file_overrides_builtin() {
+        let home = TempDir::new().unwrap();
+        save_forge(home.path(), "GitHub.com", ForgeKind::Gitea).unwrap();
+        save_forge(home.path(), "git.acme.corp", ForgeKind::Gitlab).unwrap();
+        let forges = Forges::load_from_home(home.path()).unwrap();
+        assert_eq!(forges.detect("https://github.com/a/b").kind, ForgeKind::Gitea);
+        assert_eq!(forges.detect("https://git.acme.corp/a/b").kind, ForgeKind::Gitlab);
+        assert_eq!(forges.hosts().iter().filter(|h| h.host == "github.com").count(), 1);
+    }
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Typed view of the YAML frontmatter at the top of a `SKILL.md`.
+///
+/// Keys xskill does not know about are kept in `extra` so they survive a
+/// parse / render round trip.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct SkillManifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub disable_model_invocation: bool,
+    #[serde(default, deserialize_with = "string_or_list", skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
+}
+
+/// `allowed-tools` is written both as a YAML list and as a comma separated string.
+fn string_or_list<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrList {
+        String(String),
+        List(Vec<String>),
+        Null(()),
+    }
+
+    Ok(match StringOrList::deserialize(deserializer)? {
+        StringOrList::String(s) => s
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        StringOrList::List(list) => list
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect(),
+        StringOrList::Null(()) => Vec::new(),
+    })
+}
+
+impl SkillManifest {
+    /// Tags from a top-level `tags` key or from `metadata.tags`, as a list or a comma
+    /// separated string.
+    pub fn tags(&self) -> Vec<String> {
+        list_value(self.extra.get("tags").or_else(|| self.extra.get("metadata").and_then(|m| m.get("tags"))))
+    }
+
+    /// File patterns the skill applies to (`globs`), as a list or a comma separated string.
+    pub fn globs(&self) -> Vec<String> {
+        list_value(self.extra.get("globs"))
+    }
+}
+
+fn list_value(value: Option<&serde_yaml::Value>) -> Vec<String> {
+    match value {
+        Some(serde_yaml::Value::String(s)) => s.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+        Some(serde_yaml::Value::Sequence(list)) => list.iter().filter_map(|t| t.as_str()).map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Why the frontmatter of a `SKILL.md` could not be read.
+///
+/// Line numbers are 1-based and relative to the whole file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FrontmatterError {
+    /// An opening `---` without a matching closing `---`.
+    Unterminated { line: usize },
+    /// The frontmatter is not valid YAML.
+    InvalidYaml { message: String, line: Option<usize>, column: Option<usize> },
+    /// Valid YAML, but not a `key: value` mapping.
+    NotAMapping { line: usize },
+    /// A known key holds a value of the wrong type (e.g. a list for `name`).
+    InvalidField { message: String, line: usize },
+}
+
+impl fmt::Display for FrontmatterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrontmatterError::Unterminated { line } => {
+                write!(f, "Frontmatter opened on line {} is never closed with '---'", line)
+            }
+            FrontmatterError::InvalidYaml { message, line: Some(line), column } => {
+                write!(f, "Invalid YAML frontmatter at line {}", line)?;
+                if let Some(col) = column {
+                    write!(f, ", column {}", col)?;
+                }
+                write!(f, ": {}", message)
+            }
+            FrontmatterError::InvalidYaml { message, line: None, .. } => {
+                write!(f, "Invalid YAML frontmatter: {}", message)
+            }
+            FrontmatterError::NotAMapping { line } => {
+                write!(f, "Frontmatter starting on line {} must be a mapping of keys to values", line)
+            }
+            FrontmatterError::InvalidField { message, line } => {
+                write!(f, "Invalid frontmatter starting on line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FrontmatterError {}
+
+/// Location of the frontmatter block inside a document.
+struct Block<'a> {
+    yaml: &'a str,
+    /// 1-based line of the opening `---`.
+    open_line: usize,
+    body: &'a str,
+}
+
+fn delimiter(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r', ' ', '\t'])
+}
+
+fn split_block(raw: &str) -> Result<Option<Block<'_>>, FrontmatterError> {
+    let text = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+    let mut offset = 0;
+    let mut open_line = None;
+    let mut yaml_start = 0;
+
+    for (idx, line) in text.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        match open_line {
+            None => {
+                // Blank lines may precede the opening delimiter
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if delimiter(line) != "---" {
+                    return Ok(None);
+                }
+                open_line = Some(idx + 1);
+                yaml_start = offset;
+            }
+            Some(open) => {
+                if matches!(delimiter(line), "---" | "...") {
+                    return Ok(Some(Block {
+                        yaml: &text[yaml_start..line_start],
+                        open_line: open,
+                        body: text[offset..].trim_start_matches(['\n', '\r']),
+                    }));
+                }
+            }
+        }
+    }
+
+    match open_line {
+        Some(line) => Err(FrontmatterError::Unterminated { line }),
+        None => Ok(None),
+    }
+}
+
+/// Split `raw` into its frontmatter and markdown body.
+///
+/// A document without frontmatter yields an empty manifest and the whole text as body.
+pub fn parse(raw: &str) -> Result<(SkillManifest, String), FrontmatterError> {
+    let block = match split_block(raw)? {
+        Some(b) => b,
+        None => return Ok((SkillManifest::default(), raw.to_string())),
+    };
+
+    if block.yaml.trim().is_empty() {
+        return Ok((SkillManifest::default(), block.body.to_string()));
+    }
+
+    let value: serde_yaml::Value = serde_yaml::from_str(block.yaml).map_err(|e| {
+        let location = e.location();
+        FrontmatterError::InvalidYaml {
+            message: strip_location(&e.to_string()),
+            line: location.as_ref().map(|l| l.line() + block.open_line),
+            column: location.as_ref().map(|l| l.column()),
+        }
+    })?;
+
+    let value = match value {
+        serde_yaml::Value::Mapping(_) => value,
+        serde_yaml::Value::Null => return Ok((SkillManifest::default(), block.body.to_string())),
+        _ => return Err(FrontmatterError::NotAMapping { line: block.open_line }),
+    };
+
+    let manifest: SkillManifest = serde_yaml::from_value(value).map_err(|e| FrontmatterError::InvalidField {
+        message: e.to_string(),
+        line: block.open_line,
+    })?;
+
+    Ok((manifest, block.body.to_string()))
+}
+
+/// serde_yaml appends "at line X column Y" to messages; we report the location separately.
+fn strip_location(message: &str) -> String {
+    match message.find(" at line ") {
+        Some(pos) => message[..pos].to_string(),
+        None => message.to_string(),
+    }
+}
+
+/// Render a manifest and body back into a `SKILL.md` document.
+pub fn render(manifest: &SkillManifest, body: &str) -> Result<String, String> {
+    let yaml = serde_yaml::to_string(manifest).map_err(|e| e.to_string())?;
+    Ok(format!("---\n{}---\n\n{}", yaml, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_block_scalars_lists_and_comments() {
+        let raw = "---\n# a comment\nname: pdf-tools\ndescription: >\n  Extract text from PDFs.\n  Use when the user uploads a PDF.\nallowed-tools:\n  - Read\n  - Bash\nmetadata:\n  owner: docs-team\n  tags: [pdf, text]\n---\n\n# Body\n";
+        let (manifest, body) = parse(raw).unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("pdf-tools"));
+        assert_eq!(
+            manifest.description.as_deref(),
+            Some("Extract text from PDFs. Use when the user uploads a PDF.\n")
+        );
+        assert_eq!(manifest.allowed_tools, vec!["Read", "Bash"]);
+        assert!(manifest.extra.contains_key("metadata"));
+        assert_eq!(manifest.tags(), vec!["pdf", "text"]);
+        assert_eq!(body, "# Body\n");
+    }
+
+    #[test]
+    fn test_parse_comma_separated_tools_and_flags() {
+        let raw = "---\nname: \"quoted\"\nallowed-tools: Read, Grep , Glob\ndisable-model-invocation: true\nglobs: src/**/*.rs, Cargo.toml\n---\nContent";
+        let (manifest, body) = parse(raw).unwrap();
+        assert_eq!(manifest.name.as_deref(), Some("quoted"));
+        assert_eq!(manifest.allowed_tools, vec!["Read", "Grep", "Glob"]);
+        assert!(manifest.disable_model_invocation);
+        assert!(manifest.tags().is_empty());
+        assert_eq!(manifest.globs(), vec!["src/**/*.rs", "Cargo.toml"]);
+        assert_eq!(body, "Content");
+    }
+
+    #[test]
+    fn test_parse_without_frontmatter() {
+        let (manifest, body) = parse("Just markdown").unwrap();
+        assert_eq!(manifest, SkillManifest::default());
+        assert_eq!(body, "Just markdown");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("---\nname: x\n").unwrap_err(), FrontmatterError::Unterminated { line: 1 });
+
+        match parse("---\nname: x\ndescription: [unclosed\n---\n").unwrap_err() {
+            FrontmatterError::InvalidYaml { line, .. } => assert!(line.unwrap() >= 3),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        assert_eq!(parse("---\n- a\n- b\n---\n").unwrap_err(), FrontmatterError::NotAMapping { line: 1 });
+
+        assert!(matches!(
+            parse("---\nname: [a, b]\n---\n").unwrap_err(),
+            FrontmatterError::InvalidField { .. }
+        ));
+    }
+
+    #[test]
+    fn test_render_round_trip_keeps_unknown_keys() {
+        let raw = "---\nname: demo\ndescription: 'Use when: testing'\nlicense: MIT\n---\n\nBody";
+        let (manifest, body) = parse(raw).unwrap();
+        let rendered = render(&manifest, &body).unwrap();
+        let (reparsed, rebody) = parse(&rendered).unwrap();
+        assert_eq!(reparsed, manifest);
+        assert_eq!(rebody, "Body");
+        assert!(rendered.contains("license: MIT"));
+    }
+}
@@ -0,0 +1,519 @@
+use crate::error::XskillError;
+use crate::utils::COPY_IGNORED;
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{
+    AutotagOption, Cred, CredentialType, DiffOptions, FetchOptions, FetchPrune, ObjectType, Oid, ProxyOptions,
+    RemoteCallbacks, Repository, Status, StatusOptions, TreeWalkMode, TreeWalkResult,
+};
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Receives human-readable progress lines ("Receiving objects: 42% ...").
+pub type Progress<'a> = &'a mut dyn FnMut(String);
+
+#[derive(Debug, Clone, Default)]
+pub struct CloneOptions {
+    /// No working tree; branches are mirrored to `refs/heads`.
+    pub bare: bool,
+    /// Shallow clone with this many commits.
+    pub depth: Option<i32>,
+}
+
+/// A file in a commit's tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeFile {
+    /// Path relative to the directory that was listed.
+    pub path: String,
+    /// Blob id, comparable with `GitBackend::hash_file`.
+    pub id: String,
+    pub mode: i32,
+}
+
+impl TreeFile {
+    pub fn is_executable(&self) -> bool {
+        self.mode == 0o100755
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.mode == 0o120000
+    }
+}
+
+/// Everything xskill needs from git. `NativeGit` is the real implementation; tests can
+/// swap in another one with `with_backend`.
+///
+/// Revisions are anything `git rev-parse` understands (branch, tag, commit, `origin/main`).
+pub trait GitBackend: Send + Sync {
+    fn clone_repo(&self, url: &str, dest: &Path, opts: &CloneOptions, progress: Progress) -> Result<(), XskillError>;
+    /// Fetch branches and tags from `origin`, pruning deleted branches.
+    fn fetch(&self, repo: &Path, progress: Progress) -> Result<(), XskillError>;
+    /// Commit that `rev` points to.
+    fn resolve(&self, repo: &Path, rev: &str) -> Result<String, XskillError>;
+    /// Check out `rev` with a detached HEAD. Returns the commit.
+    fn checkout(&self, repo: &Path, rev: &str) -> Result<String, XskillError>;
+    /// Fast-forward the current branch to its upstream. Returns the new commit.
+    fn fast_forward(&self, repo: &Path) -> Result<String, XskillError>;
+    /// Branch HEAD is on, `None` when detached.
+    fn current_branch(&self, repo: &Path) -> Result<Option<String>, XskillError>;
+    fn remote_url(&self, repo: &Path) -> Result<String, XskillError>;
+    /// Commits only in `local` and only in `upstream`.
+    fn ahead_behind(&self, repo: &Path, local: &str, upstream: &str) -> Result<(usize, usize), XskillError>;
+    /// Commits in `from..to`, counting only those that change `subpath` when given.
+    fn count_commits(&self, repo: &Path, from: &str, to: &str, subpath: Option<&str>) -> Result<usize, XskillError>;
+    /// Files changed on `to` since it forked from `from`, relative to `subpath` when given.
+    fn changed_paths(&self, repo: &Path, from: &str, to: &str, subpath: Option<&str>) -> Result<Vec<String>, XskillError>;
+    /// Tracked files whose working copy differs from HEAD.
+    fn modified_paths(&self, repo: &Path) -> Result<Vec<String>, XskillError>;
+    /// Every file under `subpath` (or the whole tree) at `rev`.
+    fn list_files(&self, repo: &Path, rev: &str, subpath: Option<&str>) -> Result<Vec<TreeFile>, XskillError>;
+    fn read_file(&self, repo: &Path, rev: &str, path: &str) -> Result<Vec<u8>, XskillError>;
+    fn read_blob(&self, repo: &Path, id: &str) -> Result<Vec<u8>, XskillError>;
+    /// Raw object of the annotated tag `name` and the commit it points to. `None` for
+    /// lightweight tags.
+    fn read_tag(&self, repo: &Path, name: &str) -> Result<Option<(Vec<u8>, String)>, XskillError>;
+    /// Blob id the file at `path` would have.
+    fn hash_file(&self, path: &Path) -> Result<String, XskillError>;
+
+    /// Write the files under `subpath` at `rev` into `dest`, replacing it. Directories
+    /// that `utils::copy_dir_all` ignores are left out, and so are symlinks: a repository
+    /// could otherwise point one anywhere on the machine.
+    fn export(&self, repo: &Path, rev: &str, subpath: &str, dest: &Path) -> Result<(), XskillError> {
+        let files = self.list_files(repo, rev, Some(subpath))?;
+        if dest.exists() || dest.is_symlink() {
+            fs::remove_dir_all(dest).map_err(|e| XskillError::io(dest, e))?;
+        }
+        fs::create_dir_all(dest).map_err(|e| XskillError::io(dest, e))?;
+        for file in files {
+            if file.path.split('/').any(|c| COPY_IGNORED.contains(&c)) {
+                continue;
+            }
+            if file.is_symlink() {
+                continue;
+            }
+            let target = dest.join(&file.path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+            }
+            let content = self.read_blob(repo, &file.id)?;
+            fs::write(&target, content).map_err(|e| XskillError::io(&target, e))?;
+            #[cfg(unix)]
+            if file.is_executable() {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&target, fs::Permissions::from_mode(0o755)).map_err(|e| XskillError::io(&target, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+thread_local! {
+    static OVERRIDE: RefCell<Option<Arc<dyn GitBackend>>> = const { RefCell::new(None) };
+}
+
+/// The git implementation in use on this thread.
+pub fn backend() -> Arc<dyn GitBackend> {
+    OVERRIDE.with(|o| o.borrow().clone()).unwrap_or_else(|| Arc::new(NativeGit))
+}
+
+/// Run `f` with `git` in place of `NativeGit` on the current thread.
+pub fn with_backend<R>(git: Arc<dyn GitBackend>, f: impl FnOnce() -> R) -> R {
+    struct Reset(Option<Arc<dyn GitBackend>>);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            OVERRIDE.with(|o| *o.borrow_mut() = previous);
+        }
+    }
+    let _reset = Reset(OVERRIDE.with(|o| o.borrow_mut().replace(git)));
+    f()
+}
+
+/// In-process git via libgit2; no `git` binary required.
+pub struct NativeGit;
+
+fn git_error(e: git2::Error) -> XskillError {
+    XskillError::git(e.message())
+}
+
+fn open(path: &Path) -> Result<Repository, XskillError> {
+    Repository::open(path).map_err(|e| XskillError::git(format!("Failed to open repository {}: {}", path.display(), e.message())))
+}
+
+fn peel<'r>(repo: &'r Repository, rev: &str) -> Result<git2::Commit<'r>, XskillError> {
+    // Plain branch names may only exist as remote-tracking branches in a fresh clone
+    repo.revparse_single(&format!("{}^{{commit}}", rev))
+        .or_else(|_| repo.revparse_single(&format!("origin/{}^{{commit}}", rev)))
+        .and_then(|o| o.peel_to_commit())
+        .map_err(|_| XskillError::git(format!("Revision {} not found in repository", rev)))
+}
+
+fn format_bytes(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    }
+}
+
+fn credentials(url: &str, username: Option<&str>, allowed: CredentialType, attempt: usize) -> Result<Cred, git2::Error> {
+    if allowed.contains(CredentialType::SSH_KEY) {
+        let user = username.unwrap_or("git");
+        // The agent first, then the usual key files in turn
+        if attempt == 1 {
+            return Cred::ssh_key_from_agent(user);
+        }
+        let ssh_dir = crate::utils::get_home_dir().unwrap_or_default().join(".ssh");
+        let keys: Vec<_> = ["id_ed25519", "id_ecdsa", "id_rsa"].iter().map(|k| ssh_dir.join(k)).filter(|k| k.exists()).collect();
+        if let Some(key) = keys.get(attempt - 2) {
+            return Cred::ssh_key(user, None, key, None);
+        }
+    }
+    if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+        // A token stored for the forge wins over git's credential helpers, but is never
+        // sent over plain http
+        let forge = crate::forge::detect(url);
+        if attempt == 1 && url.starts_with("https://") {
+            if let Some(token) = forge.token() {
+                return Cred::userpass_plaintext(username.unwrap_or(forge.git_username()), &token);
+            }
+        }
+        if let Ok(config) = git2::Config::open_default() {
+            if let Ok(cred) = Cred::credential_helper(&config, url, username) {
+                return Ok(cred);
+            }
+        }
+    }
+    if allowed.contains(CredentialType::DEFAULT) {
+        return Cred::default();
+    }
+    Err(git2::Error::from_str(&format!("No credentials available for {}", url)))
+}
+
+fn fetch_options<'a>(progress: Progress<'a>) -> FetchOptions<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    let mut last = None;
+    callbacks.transfer_progress(move |stats| {
+        let (phase, done, total) = if stats.received_objects() < stats.total_objects() {
+            ("Receiving objects", stats.received_objects(), stats.total_objects())
+        } else {
+            ("Resolving deltas", stats.indexed_deltas(), stats.total_deltas())
+        };
+        if let Some(percent) = (done * 100).checked_div(total) {
+            if last != Some((phase, percent)) {
+                last = Some((phase, percent));
+                progress(format!("{}: {}% ({}/{}), {}", phase, percent, done, total, format_bytes(stats.received_bytes())));
+            }
+        }
+        true
+    });
+    // libgit2 keeps asking while credentials are rejected; give up after a few tries
+    let mut attempts = 0;
+    callbacks.credentials(move |url, username, allowed| {
+        attempts += 1;
+        if attempts > 4 {
+            return Err(git2::Error::from_str(&format!("Authentication failed for {}", url)));
+        }
+        credentials(url, username, allowed, attempts)
+    });
+
+    let mut proxy = ProxyOptions::new();
+    match crate::utils::get_system_proxy() {
+        Some(url) => proxy.url(&url),
+        None => proxy.auto(),
+    };
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks).proxy_options(proxy).download_tags(AutotagOption::All);
+    options
+}
+
+impl GitBackend for NativeGit {
+    fn clone_repo(&self, url: &str, dest: &Path, opts: &CloneOptions, progress: Progress) -> Result<(), XskillError> {
+        let mut fetch = fetch_options(progress);
+        if let Some(depth) = opts.depth {
+            fetch.depth(depth);
+        }
+        let mut builder = RepoBuilder::new();
+        builder.bare(opts.bare).fetch_options(fetch);
+        if opts.bare {
+            builder.remote_create(|repo, name, url| repo.remote_with_fetch(name, url, "+refs/heads/*:refs/heads/*"));
+        }
+        let repo = builder.clone(url, dest).map_err(|e| XskillError::git(format!("git clone failed: {}", e.message())))?;
+
+        // Remember the default branch as origin/HEAD, like `git clone` does
+        if !opts.bare {
+            if let Some(branch) = repo.head().ok().filter(|h| h.is_branch()).and_then(|h| h.shorthand().map(str::to_string)) {
+                let target = format!("refs/remotes/origin/{}", branch);
+                if repo.find_reference(&target).is_ok() {
+                    let _ = repo.reference_symbolic("refs/remotes/origin/HEAD", &target, true, "clone: set origin/HEAD");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fetch(&self, repo_path: &Path, progress: Progress) -> Result<(), XskillError> {
+        let repo = open(repo_path)?;
+        let mut remote = repo.find_remote("origin").map_err(git_error)?;
+        let refspec = if repo.is_bare() { "+refs/heads/*:refs/heads/*" } else { "+refs/heads/*:refs/remotes/origin/*" };
+        let mut options = fetch_options(progress);
+        options.prune(FetchPrune::On);
+        remote
+            .fetch(&[refspec], Some(&mut options), None)
+            .map_err(|e| XskillError::git(format!("git fetch failed: {}", e.message())))
+    }
+
+    fn resolve(&self, repo_path: &Path, rev: &str) -> Result<String, XskillError> {
+        let repo = open(repo_path)?;
+        let commit = peel(&repo, rev)?;
+        Ok(commit.id().to_string())
+    }
+
+    fn checkout(&self, repo_path: &Path, rev: &str) -> Result<String, XskillError> {
+        let repo = open(repo_path)?;
+        let commit = peel(&repo, rev)?;
+        repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().safe()))
+            .map_err(|e| XskillError::git(format!("git checkout failed: {}", e.message())))?;
+        repo.set_head_detached(commit.id()).map_err(git_error)?;
+        Ok(commit.id().to_string())
+    }
+
+    fn fast_forward(&self, repo_path: &Path) -> Result<String, XskillError> {
+        let repo = open(repo_path)?;
+        let head = repo.head().map_err(git_error)?;
+        if !head.is_branch() {
+            return Err(XskillError::git("HEAD is detached; nothing to fast-forward"));
+        }
+        let branch = git2::Branch::wrap(head);
+        let local = branch.get().peel_to_commit().map_err(git_error)?;
+        let target = branch
+            .upstream()
+            .and_then(|u| u.get().peel_to_commit())
+            .map_err(|e| XskillError::git(format!("No upstream branch to pull from: {}", e.message())))?;
+        if local.id() == target.id() {
+            return Ok(local.id().to_string());
+        }
+        if !repo.graph_descendant_of(target.id(), local.id()).map_err(git_error)? {
+            return Err(XskillError::git("Not possible to fast-forward: the local branch has diverged"));
+        }
+        repo.checkout_tree(target.as_object(), Some(CheckoutBuilder::new().safe()))
+            .map_err(|e| XskillError::git(format!("git checkout failed: {}", e.message())))?;
+        branch
+            .into_reference()
+            .set_target(target.id(), "xskill: fast-forward")
+            .map_err(git_error)?;
+        Ok(target.id().to_string())
+    }
+
+    fn current_branch(&self, repo_path: &Path) -> Result<Option<String>, XskillError> {
+        let repo = open(repo_path)?;
+        let head = repo.head().map_err(git_error)?;
+        Ok(if head.is_branch() { head.shorthand().map(str::to_string) } else { None })
+    }
+
+    fn remote_url(&self, repo_path: &Path) -> Result<String, XskillError> {
+        let repo = open(repo_path)?;
+        let remote = repo.find_remote("origin").map_err(git_error)?;
+        remote.url().map(str::to_string).ok_or_else(|| XskillError::git("origin has no URL"))
+    }
+
+    fn ahead_behind(&self, repo_path: &Path, local: &str, upstream: &str) -> Result<(usize, usize), XskillError> {
+        let repo = open(repo_path)?;
+        let (local, upstream) = (peel(&repo, local)?.id(), peel(&repo, upstream)?.id());
+        repo.graph_ahead_behind(local, upstream).map_err(git_error)
+    }
+
+    fn count_commits(&self, repo_path: &Path, from: &str, to: &str, subpath: Option<&str>) -> Result<usize, XskillError> {
+        let repo = open(repo_path)?;
+        let (from, to) = (peel(&repo, from)?.id(), peel(&repo, to)?.id());
+        let mut walk = repo.revwalk().map_err(git_error)?;
+        walk.push(to).and_then(|_| walk.hide(from)).map_err(git_error)?;
+
+        let entry = |commit: &git2::Commit, path: &str| commit.tree().ok().and_then(|t| t.get_path(Path::new(path)).ok()).map(|e| e.id());
+        let mut count = 0;
+        for oid in walk {
+            let commit = repo.find_commit(oid.map_err(git_error)?).map_err(git_error)?;
+            let touched = match subpath {
+                None => true,
+                // Like `git rev-list -- <path>`: skip commits that leave the path as one parent had it
+                Some(path) => {
+                    let mine = entry(&commit, path);
+                    if commit.parent_count() == 0 {
+                        mine.is_some()
+                    } else {
+                        commit.parents().all(|p| entry(&p, path) != mine)
+                    }
+                }
+            };
+            if touched {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn changed_paths(&self, repo_path: &Path, from: &str, to: &str, subpath: Option<&str>) -> Result<Vec<String>, XskillError> {
+        let repo = open(repo_path)?;
+        let (from, to) = (peel(&repo, from)?, peel(&repo, to)?);
+        let base = repo.merge_base(from.id(), to.id()).map_err(git_error)?;
+        let old_tree = repo.find_commit(base).and_then(|c| c.tree()).map_err(git_error)?;
+        let new_tree = to.tree().map_err(git_error)?;
+        let diff = repo
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut DiffOptions::new()))
+            .map_err(git_error)?;
+
+        let prefix = subpath.map(|p| format!("{}/", p.trim_end_matches('/')));
+        Ok(diff
+            .deltas()
+            .filter_map(|d| d.new_file().path().or(d.old_file().path()).map(|p| p.to_string_lossy().replace('\\', "/")))
+            .filter_map(|p| match &prefix {
+                Some(prefix) => p.strip_prefix(prefix.as_str()).map(str::to_string),
+                None => Some(p),
+            })
+            .collect())
+    }
+
+    fn modified_paths(&self, repo_path: &Path) -> Result<Vec<String>, XskillError> {
+        let repo = open(repo_path)?;
+        let mut options = StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+        let statuses = repo.statuses(Some(&mut options)).map_err(git_error)?;
+        Ok(statuses
+            .iter()
+            .filter(|s| s.status() != Status::CURRENT)
+            .filter_map(|s| s.path().map(str::to_string))
+            .collect())
+    }
+
+    fn list_files(&self, repo_path: &Path, rev: &str, subpath: Option<&str>) -> Result<Vec<TreeFile>, XskillError> {
+        let repo = open(repo_path)?;
+        let mut tree = peel(&repo, rev)?.tree().map_err(git_error)?;
+        if let Some(sub) = subpath.map(|p| p.trim_matches('/')).filter(|p| !p.is_empty()) {
+            let not_found = || XskillError::git(format!("Subdirectory {} not found in repository", sub));
+            let entry = tree.get_path(Path::new(sub)).map_err(|_| not_found())?;
+            tree = repo.find_tree(entry.id()).map_err(|_| not_found())?;
+        }
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                files.push(TreeFile {
+                    path: format!("{}{}", root, entry.name().unwrap_or_default()),
+                    id: entry.id().to_string(),
+                    mode: entry.filemode(),
+                });
+            }
+            TreeWalkResult::Ok
+        })
+        .map_err(git_error)?;
+        Ok(files)
+    }
+
+    fn read_file(&self, repo_path: &Path, rev: &str, path: &str) -> Result<Vec<u8>, XskillError> {
+        let repo = open(repo_path)?;
+        let tree = peel(&repo, rev)?.tree().map_err(git_error)?;
+        let entry = tree.get_path(Path::new(path)).map_err(|_| XskillError::git(format!("{} not found at {}", path, rev)))?;
+        let blob = repo.find_blob(entry.id()).map_err(git_error)?;
+        Ok(blob.content().to_vec())
+    }
+
+    fn read_tag(&self, repo_path: &Path, name: &str) -> Result<Option<(Vec<u8>, String)>, XskillError> {
+        let repo = open(repo_path)?;
+        let Ok(reference) = repo.find_reference(&format!("refs/tags/{}", name)) else {
+            return Ok(None);
+        };
+        let Some(tag) = reference.target().and_then(|oid| repo.find_tag(oid).ok()) else {
+            return Ok(None);
+        };
+        let commit = tag.target().and_then(|t| t.peel_to_commit()).map_err(git_error)?;
+        let odb = repo.odb().map_err(git_error)?;
+        let raw = odb.read(tag.id()).map_err(git_error)?;
+        Ok(Some((raw.data().to_vec(), commit.id().to_string())))
+    }
+
+    fn read_blob(&self, repo_path: &Path, id: &str) -> Result<Vec<u8>, XskillError> {
+        let repo = open(repo_path)?;
+        let oid = Oid::from_str(id).map_err(git_error)?;
+        let blob = repo.find_blob(oid).map_err(git_error)?;
+        Ok(blob.content().to_vec())
+    }
+
+    fn hash_file(&self, path: &Path) -> Result<String, XskillError> {
+        Oid::hash_file(ObjectType::Blob, path)
+            .map(|oid| oid.to_string())
+            .map_err(|e| XskillError::git(format!("Failed to hash {}: {}", path.display(), e.message())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::Signature;
+    use tempfile::TempDir;
+
+    fn commit_all(repo: &Repository, message: &str) -> Oid {
+        let mut index = repo.index().unwrap();
+        index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("xskill", "xskill@example.com").unwrap();
+        let parents: Vec<_> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+        let parents: Vec<_> = parents.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn test_native_clone_count_and_export_without_git_binary() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        let repo = Repository::init(&origin).unwrap();
+        fs::create_dir_all(origin.join("skills/a")).unwrap();
+        fs::write(origin.join("skills/a/SKILL.md"), "a v1").unwrap();
+        fs::write(origin.join("README.md"), "readme").unwrap();
+        let first = commit_all(&repo, "v1");
+        fs::write(origin.join("README.md"), "readme v2").unwrap();
+        commit_all(&repo, "readme");
+        fs::write(origin.join("skills/a/SKILL.md"), "a v2").unwrap();
+        let last = commit_all(&repo, "a v2");
+
+        let git = NativeGit;
+        let cache = temp_dir.path().join("cache.git");
+        let url = origin.to_string_lossy().to_string();
+        git.clone_repo(&url, &cache, &CloneOptions { bare: true, depth: None }, &mut |_| {}).unwrap();
+        assert_eq!(git.resolve(&cache, "HEAD").unwrap(), last.to_string());
+
+        let (from, to) = (first.to_string(), last.to_string());
+        assert_eq!(git.count_commits(&cache, &from, &to, None).unwrap(), 2);
+        assert_eq!(git.count_commits(&cache, &from, &to, Some("skills/a")).unwrap(), 1);
+        assert_eq!(git.changed_paths(&cache, &from, &to, Some("skills/a")).unwrap(), vec!["SKILL.md".to_string()]);
+
+        let dest = temp_dir.path().join("a");
+        git.export(&cache, &from, "skills/a", &dest).unwrap();
+        assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), "a v1");
+        let listed = git.list_files(&cache, &from, Some("skills/a")).unwrap();
+        assert_eq!(listed[0].id, git.hash_file(&dest.join("SKILL.md")).unwrap());
+        assert!(git.export(&cache, &from, "skills/missing", &dest).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_export_leaves_out_symlinks() {
+        let temp_dir = TempDir::new().unwrap();
+        let origin = temp_dir.path().join("origin");
+        let repo = Repository::init(&origin).unwrap();
+        fs::create_dir_all(origin.join("skills/a")).unwrap();
+        fs::write(origin.join("skills/a/SKILL.md"), "a").unwrap();
+        std::os::unix::fs::symlink("../../../../../etc/passwd", origin.join("skills/a/passwd")).unwrap();
+        std::os::unix::fs::symlink("SKILL.md", origin.join("skills/a/alias.md")).unwrap();
+        let head = commit_all(&repo, "links").to_string();
+
+        let git = NativeGit;
+        assert_eq!(git.list_files(&origin, &head, Some("skills/a")).unwrap().iter().filter(|f| f.is_symlink()).count(), 2);
+        let dest = temp_dir.path().join("a");
+        git.export(&origin, &head, "skills/a", &dest).unwrap();
+        assert!(dest.join("SKILL.md").is_file());
+        assert!(!dest.join("passwd").is_symlink() && !dest.join("passwd").exists());
+        assert!(!dest.join("alias.md").is_symlink());
+    }
+}
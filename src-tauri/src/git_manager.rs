@@ -1,196 +1,631 @@
-use std::path::Path;
-use std::process::Command;
-use crate::skill_manager::{CENTRAL_SKILLS_DIR, core_update_skill_metadata};
+use std::path::{Path, PathBuf};
+use crate::error::XskillError;
+use crate::git_backend::{backend, CloneOptions};
+use crate::repo_cache::{self, RepoCache};
+use crate::skill_lock::{LockedSkill, SkillSource, SkillsLock};
+use crate::skill_manager::{read_skill_meta, write_skill_meta};
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
 use tauri::{Emitter, Window};
 
-fn git_cmd() -> Command {
-    let mut cmd = Command::new("git");
-    if let Some(proxy) = crate::utils::get_system_proxy() {
-        cmd.env("http_proxy", &proxy);
-        cmd.env("https_proxy", &proxy);
-        cmd.env("HTTP_PROXY", &proxy);
-        cmd.env("HTTPS_PROXY", &proxy);
-        cmd.env("ALL_PROXY", &proxy);
-    }
-    cmd
+pub fn resolve_commit(dir: &Path) -> Result<String, XskillError> {
+    backend().resolve(dir, "HEAD")
 }
 
-pub fn parse_github_tree_url(url: &str) -> Option<(String, String, String)> {
-    if let Some(pos) = url.find("/tree/") {
-        let repo_url = url[..pos].to_string();
-        let rest = &url[pos + 6..];
-        if let Some(slash_pos) = rest.find('/') {
-            let branch = rest[..slash_pos].to_string();
-            let sub_path = rest[slash_pos + 1..].to_string();
-            return Some((repo_url, branch, sub_path));
+impl Xskill {
+    /// Install a skill from a git repository, a `.zip`/`.tar.gz` URL, a local archive or a
+    /// local directory into the Hub. Returns the installed directory.
+    ///
+    /// `pin` (a tag, branch or commit) is checked out instead of the default branch and
+    /// recorded so that plain updates leave the skill where it is. `sha256` is checked
+    /// against archives before they are unpacked.
+    pub async fn install_skill<F>(&self, repo_url: &str, pin: Option<&str>, sha256: Option<&str>, mut progress: F) -> Result<PathBuf, XskillError>
+    where
+        F: FnMut(String),
+    {
+        progress("Analyzing repository URL...".to_string());
+
+        if let Some(location) = crate::archive::Location::parse(repo_url) {
+            if pin.is_some() {
+                return Err(XskillError::invalid("A pin only applies to git repositories"));
+            }
+            return crate::archive::install_location(self, &location, repo_url, sha256, progress).await;
         }
+        if sha256.is_some() {
+            return Err(XskillError::invalid("A sha256 can only be checked for archives"));
+        }
+
+        let name = repo_url.trim_end_matches('/').split('/').next_back()
+            .ok_or_else(|| XskillError::invalid("Invalid URL"))?
+            .trim_end_matches(".git");
+
+        if name.is_empty() {
+            return Err(XskillError::invalid("Could not determine repo name from URL"));
+        }
+
+        let source = SkillSource::from_url(repo_url, pin);
+        install_source(self, &source, pin, name, progress).await
+    }
+
+    /// Run the security audit on a skill staged outside its Hub directory. Every install and
+    /// update path calls this before moving the skill in, so agents linked to the Hub never
+    /// see a blocked skill.
+    pub(crate) fn audit_staged(&self, staged: &Path, mut progress: impl FnMut(String)) -> Result<(), XskillError> {
+        progress("Auditing skill files...".to_string());
+        crate::audit::gate(staged, self.config().audit.block, &mut progress).map(|_| ())
+    }
+
+    /// Move an installed skill to `rev`, or fast-forward it when `rev` is `None`. The new
+    /// revision is built next to the skill and audited before it replaces it.
+    async fn move_audited(&self, skill_path: &Path, source: &SkillSource, rev: Option<&str>) -> Result<String, XskillError> {
+        let (staged, commit) = stage_update(skill_path, source, rev).await?;
+        let swapped = self.audit_staged(&staged, |_| {}).and_then(|_| swap_in(&staged, skill_path));
+        if swapped.is_err() {
+            let _ = std::fs::remove_dir_all(&staged);
+        }
+        swapped.map(|_| commit)
+    }
+
+    /// Update an installed skill. With `pin`, move it to that tag/branch/commit and keep it
+    /// there; without, fast-forward it unless it is pinned. Returns the installed commit.
+    pub async fn update_skill(&self, skill_path: &Path, pin: Option<&str>) -> Result<String, XskillError> {
+        if !skill_path.exists() {
+            return Err(XskillError::path_not_found(skill_path));
+        }
+
+        let mut source = installed_source(skill_path).unwrap_or_default();
+        if pin.is_none() && source.pinned {
+            return Err(XskillError::invalid(format!(
+                "Skill is pinned to {}; pass a new pin to move it",
+                source.git_ref.as_deref().unwrap_or(&source.commit)
+            )));
+        }
+        if let Some(pin) = pin {
+            source.git_ref = Some(pin.to_string());
+            source.pinned = true;
+        }
+
+        let commit = self.move_audited(skill_path, &source, pin).await?;
+        source.commit = commit.clone();
+        if source.repo.is_empty() {
+            forget_signature(skill_path)?;
+        } else {
+            record_source(skill_path, source)?;
+        }
+        self.refresh_lock_quietly();
+        Ok(commit)
+    }
+
+    /// Check every git-backed skill in the Hub for upstream changes.
+    pub fn check_updates(&self) -> Result<Vec<UpdateCheck>, XskillError> {
+        if !self.hub().exists() {
+            return Ok(Vec::new());
+        }
+        let mut dirs: Vec<PathBuf> = std::fs::read_dir(self.hub())
+            .map_err(|e| XskillError::io(self.hub(), e))?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir() && !p.file_name().map(|n| n.to_string_lossy().starts_with('.')).unwrap_or(true))
+            .collect();
+        dirs.sort();
+        // One cache for the whole pass, so a monorepo is fetched once however many skills it has
+        let mut cache = RepoCache::open()?;
+        Ok(dirs.iter().filter_map(|d| check_with_cache(d, &mut cache)).collect())
+    }
+
+    /// Apply every available update, leaving pinned, diverged and locally edited skills alone.
+    pub async fn update_all(&self) -> Result<Vec<LockAction>, XskillError> {
+        let mut actions = Vec::new();
+        for check in self.check_updates()? {
+            let (action, detail) = if let Some(e) = check.error {
+                ("failed", Some(e))
+            } else if check.pinned && check.update_available {
+                ("skipped", Some(format!("Pinned to {}", check.git_ref.as_deref().unwrap_or("a commit"))))
+            } else if !check.update_available {
+                ("unchanged", None)
+            } else if check.ahead > 0 {
+                ("skipped", Some(format!("{} local commit(s) not upstream; update it by hand", check.ahead)))
+            } else if !check.local_changes.is_empty() {
+                ("skipped", Some(format!("{} locally edited file(s); update it by name to overwrite them", check.local_changes.len())))
+            } else {
+                match self.update_skill(Path::new(&check.path), None).await {
+                    Ok(commit) => (
+                        "updated",
+                        Some(format!(
+                            "{} -> {} ({} commit(s), {} file(s))",
+                            check.current.as_deref().map(short_commit).unwrap_or("unknown"),
+                            short_commit(&commit),
+                            check.behind,
+                            check.changed_files.len()
+                        )),
+                    ),
+                    Err(e) => ("failed", Some(e.to_string())),
+                }
+            };
+            actions.push(LockAction { name: check.name, action: action.to_string(), detail });
+        }
+        Ok(actions)
+    }
+
+    /// Bring the Hub to the exact revisions in `lock`: install missing git skills and move
+    /// existing ones to the locked commit.
+    pub async fn install_from_lock(&self, lock: &SkillsLock) -> Result<Vec<LockAction>, XskillError> {
+        let mut actions = Vec::new();
+        for locked in &lock.skills {
+            let (action, detail) = match apply_locked(self, locked).await {
+                Ok((action, detail)) => (action.to_string(), detail),
+                Err(e) => ("failed".to_string(), Some(e.to_string())),
+            };
+            actions.push(LockAction { name: locked.name.clone(), action, detail });
+        }
+        self.refresh_lock_quietly();
+        Ok(actions)
     }
-    None
 }
 
-pub async fn core_install_skill_from_url<F>(repo_url: &str, mut progress: F) -> Result<String, String>
+/// Install a skill from a repository URL into the Hub. See [`Xskill::install_skill`].
+pub async fn core_install_skill_from_url<F>(repo_url: &str, pin: Option<&str>, progress: F) -> Result<String, XskillError>
 where
     F: FnMut(String),
 {
-    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
-    let hub_path = home.join(CENTRAL_SKILLS_DIR);
-    
-    if !hub_path.exists() {
-        std::fs::create_dir_all(&hub_path).map_err(|e| e.to_string())?;
-    }
-    
-    progress("Analyzing repository URL...".to_string());
+    core_install_skill(repo_url, pin, None, progress).await
+}
 
-    let name = repo_url.trim_end_matches('/').split('/').last()
-        .ok_or("Invalid URL")?
-        .trim_end_matches(".git");
-        
-    if name.is_empty() {
-        return Err("Could not determine repo name from URL".to_string());
+pub async fn core_install_skill<F>(repo_url: &str, pin: Option<&str>, sha256: Option<&str>, progress: F) -> Result<String, XskillError>
+where
+    F: FnMut(String),
+{
+    let target_dir = Xskill::load()?.install_skill(repo_url, pin, sha256, progress).await?;
+    Ok(target_dir.to_string_lossy().to_string())
+}
+
+/// Clone `source` at `rev` into the Hub as `name` and record where it came from.
+/// The clone is staged and audited first; a blocked skill never reaches the Hub.
+pub async fn install_source<F>(xskill: &Xskill, source: &SkillSource, rev: Option<&str>, name: &str, mut progress: F) -> Result<PathBuf, XskillError>
+where
+    F: FnMut(String),
+{
+    crate::scaffold::validate_hub_name(name)?;
+    let hub_path = xskill.hub();
+
+    if !hub_path.exists() {
+        std::fs::create_dir_all(hub_path).map_err(|e| XskillError::io(hub_path, e))?;
     }
 
     let target_dir = hub_path.join(name);
-    let target_dir_str = target_dir.to_string_lossy().to_string();
-    
     if target_dir.exists() {
-        return Err(format!("Skill '{}' already exists in Hub", name));
+        return Err(XskillError::already_exists(name, &target_dir));
     }
-    
-    core_clone_skill(repo_url, &target_dir_str, progress).await?;
-    
-    // Update skill metadata with original URL
-    if let Err(e) = core_update_skill_metadata(&target_dir, Some(repo_url.to_string()), None) {
-        eprintln!("Failed to save skill metadata: {}", e);
+
+    // Staged next to the Hub so the final move is a rename
+    let staging = tempfile::Builder::new()
+        .prefix(".install-")
+        .tempdir_in(xskill.home().join(".xskill"))
+        .map_err(|e| XskillError::io(&xskill.home().join(".xskill"), e))?;
+    let staged = staging.path().join(name);
+    let commit = clone_source(source, rev, &staged, &mut progress).await?;
+    let checked = tag_signature(&staged, source, rev, &commit)?;
+    if let Some(warning) = checked.warning {
+        progress(warning);
     }
-    
-    Ok(target_dir_str)
+    let signature = checked.signature;
+
+    // Record where the skill came from and which commit is installed
+    let meta = crate::skill_manager::SkillMeta {
+        original_url: Some(source.url()),
+        remark: None,
+        source: Some(SkillSource { commit, ..source.clone() }),
+        signature,
+        ..Default::default()
+    };
+    write_skill_meta(&staged, &meta)?;
+    xskill.audit_staged(&staged, &mut progress)?;
+
+    std::fs::rename(&staged, &target_dir).map_err(|e| XskillError::io(&target_dir, e))?;
+    xskill.refresh_lock_quietly();
+    Ok(target_dir)
+}
+
+pub async fn core_clone_skill<F>(repo_url: &str, target_dir: &str, progress: F) -> Result<(), XskillError>
+where
+    F: FnMut(String),
+{
+    clone_source(&SkillSource::from_url(repo_url, None), None, Path::new(target_dir), progress)
+        .await
+        .map(|_| ())
 }
 
-pub async fn core_clone_skill<F>(repo_url: &str, target_dir: &str, mut progress: F) -> Result<(), String>
+/// Clone `source` into `target_path`, checking out `rev` if given (otherwise the source's
+/// branch, or the default branch). Returns the resolved commit.
+pub async fn clone_source<F>(source: &SkillSource, rev: Option<&str>, target_path: &Path, mut progress: F) -> Result<String, XskillError>
 where
     F: FnMut(String),
 {
-    let target_path = Path::new(target_dir);
+    let repo_url = source.repo.as_str();
+    let target_dir = target_path.to_string_lossy().to_string();
 
     progress(format!("Preparing directory: {}...", target_dir));
 
     if let Some(parent) = target_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent directory: {}", e))?;
-    }
-
-    // Check if it's a GitHub subdirectory URL
-    if let Some((base_repo, branch, subpath)) = parse_github_tree_url(repo_url) {
-        progress(format!("Detected subdirectory. Cloning {} (branch: {}, path: {})...", base_repo, branch, subpath));
-        
-        let temp_dir = std::env::temp_dir().join(format!("xskill_clone_{}", uuid::Uuid::new_v4()));
-        
-        // 1. Clone sparse
-        let output = git_cmd()
-            .args(["clone", "-n", "--depth=1", "--filter=tree:0", &base_repo, temp_dir.to_str().unwrap()])
-            .output()
-            .map_err(|e| format!("Failed to execute git clone: {}", e))?;
-
-        if !output.status.success() {
-            // Fallback for older git without --filter=tree:0
-            let output_fallback = git_cmd()
-                .args(["clone", "-n", "--depth=1", &base_repo, temp_dir.to_str().unwrap()])
-                .output()
-                .map_err(|e| format!("Failed to execute git clone fallback: {}", e))?;
-                
-            if !output_fallback.status.success() {
-                return Err(format!("git clone failed: {}", String::from_utf8_lossy(&output_fallback.stderr)));
-            }
-        }
+        std::fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+    }
+
+    // Subdirectory install: export the subdirectory from the shared cached clone
+    if let Some(subpath) = &source.subpath {
+        let checkout = rev.or(source.git_ref.as_deref());
+        progress(format!("Detected subdirectory. Fetching {} (ref: {}, path: {})...", repo_url, checkout.unwrap_or("HEAD"), subpath));
+
+        let cached = RepoCache::open()?.sync(repo_url, &mut progress)?;
+        let commit = repo_cache::resolve(&cached, checkout)?;
+        repo_cache::export(&cached, &commit, subpath, target_path)?;
 
-        // 2. Sparse checkout
-        let output2 = git_cmd()
-            .args(["sparse-checkout", "set", "--no-cone", &subpath])
-            .current_dir(&temp_dir)
-            .output()
-            .map_err(|e| format!("Failed to execute git sparse-checkout: {}", e))?;
-            
-        if !output2.status.success() {
-            let _ = std::fs::remove_dir_all(&temp_dir);
-            return Err(format!("git sparse-checkout failed: {}", String::from_utf8_lossy(&output2.stderr)));
-        }
-
-        // 3. Checkout branch
-        let output3 = git_cmd()
-            .args(["checkout", &branch])
-            .current_dir(&temp_dir)
-            .output()
-            .map_err(|e| format!("Failed to execute git checkout: {}", e))?;
-            
-        if !output3.status.success() {
-            let _ = std::fs::remove_dir_all(&temp_dir);
-            return Err(format!("git checkout failed: {}", String::from_utf8_lossy(&output3.stderr)));
-        }
-
-        // 4. Move contents to target_dir using utils::copy_dir_all
-        let src_dir = temp_dir.join(&subpath);
-        if !src_dir.exists() {
-            let _ = std::fs::remove_dir_all(&temp_dir);
-            return Err(format!("Subdirectory {} not found in repository", subpath));
-        }
-
-        crate::utils::copy_dir_all(&src_dir, &target_path.to_path_buf())?;
-        let _ = std::fs::remove_dir_all(&temp_dir);
-        
         progress("Subdirectory clone successful!".to_string());
-        return Ok(());
+        return Ok(commit);
     }
 
     // Normal clone
     progress(format!("Cloning {}...", repo_url));
-    
-    let output = git_cmd()
-        .args(["clone", repo_url, target_dir])
-        .output()
-        .map_err(|e| format!("Failed to execute git clone: {}", e))?;
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git clone failed: {}", stderr.trim()));
+    let git = backend();
+    git.clone_repo(repo_url, target_path, &CloneOptions::default(), &mut progress)?;
+
+    if let Some(rev) = rev.or(source.git_ref.as_deref()) {
+        progress(format!("Checking out {}...", rev));
+        if let Err(e) = git.checkout(target_path, rev) {
+            let _ = std::fs::remove_dir_all(target_path);
+            return Err(e);
+        }
     }
 
     progress("Clone successful!".to_string());
 
+    resolve_commit(target_path)
+}
+
+/// Source recorded for an installed skill. Older installs only have `original_url`: clones
+/// fall back to their `origin` remote and subdirectory copies to the forge tree URL.
+pub fn installed_source(skill_path: &Path) -> Option<SkillSource> {
+    let meta = read_skill_meta(skill_path).unwrap_or_default();
+    meta.source
+        .or_else(|| {
+            let repo = backend().remote_url(skill_path).ok()?;
+            Some(SkillSource { repo, ..Default::default() })
+        })
+        .or_else(|| {
+            let url = meta.original_url?;
+            crate::forge::parse_tree_url(&url)?;
+            Some(SkillSource::from_url(&url, None))
+        })
+}
+
+/// Branch that `source` follows in `dir`: `<prefix><ref>` when the ref is a branch there,
+/// otherwise `default` (the remote's default branch).
+fn tracked_branch(dir: &Path, source: &SkillSource, prefix: &str, default: &str) -> String {
+    if let Some(r) = &source.git_ref {
+        let branch = format!("{}{}", prefix, r);
+        if backend().resolve(dir, &branch).is_ok() {
+            return branch;
+        }
+    }
+    default.to_string()
+}
+
+/// Build `rev` of an installed skill (or its fast-forward when `rev` is `None`) in a
+/// sibling directory, leaving the installed copy untouched. Returns the staged directory
+/// and its commit; [`swap_in`] puts it in place.
+async fn stage_update(skill_path: &Path, source: &SkillSource, rev: Option<&str>) -> Result<(PathBuf, String), XskillError> {
+    let name = skill_path.file_name().and_then(|n| n.to_str()).ok_or_else(|| XskillError::invalid("Invalid skill path"))?;
+    let staged = skill_path.with_file_name(format!(".{}.xskill-update", name));
+    if staged.exists() {
+        std::fs::remove_dir_all(&staged).map_err(|e| XskillError::io(&staged, e))?;
+    }
+
+    if skill_path.join(".git").exists() {
+        crate::utils::copy_tree(skill_path, &staged)?;
+        let moved = move_checkout(&staged, source, rev);
+        if moved.is_err() {
+            let _ = std::fs::remove_dir_all(&staged);
+        }
+        return moved.map(|commit| (staged, commit));
+    }
+
+    if source.subpath.is_none() {
+        return Err(XskillError::git(format!("{} is not a git checkout; reinstall it to enable updates", skill_path.display())));
+    }
+
+    // Subdirectory installs have no .git: clone the new revision
+    let commit = clone_source(source, rev, &staged, |_| {}).await?;
+    let meta_file = skill_path.join(".xskill-meta.json");
+    if meta_file.exists() {
+        let _ = std::fs::copy(&meta_file, staged.join(".xskill-meta.json"));
+    }
+    Ok((staged, commit))
+}
+
+/// Fetch and check out `rev` (or fast-forward) in the git checkout at `dir`.
+fn move_checkout(dir: &Path, source: &SkillSource, rev: Option<&str>) -> Result<String, XskillError> {
+    let git = backend();
+    git.fetch(dir, &mut |_| {})?;
+    match rev {
+        Some(rev) => {
+            git.checkout(dir, rev)?;
+        }
+        None if git.current_branch(dir)?.is_some() => {
+            git.fast_forward(dir)?;
+        }
+        None => {
+            // Installed at a branch with a detached HEAD: follow that branch
+            let upstream = tracked_branch(dir, source, "origin/", "origin/HEAD");
+            git.checkout(dir, &upstream)?;
+        }
+    }
+    resolve_commit(dir)
+}
+
+/// Replace the installed skill with the directory [`stage_update`] built.
+fn swap_in(staged: &Path, skill_path: &Path) -> Result<(), XskillError> {
+    std::fs::remove_dir_all(skill_path).map_err(|e| XskillError::io(skill_path, e))?;
+    std::fs::rename(staged, skill_path).map_err(|e| XskillError::io(skill_path, e))
+}
+
+/// Signature on the tag `source` follows (or `rev`) when that tag points at `commit`,
+/// sealed with the skill's current content. Clones carry their tags; subdirectory installs
+/// read them from the cached clone.
+fn tag_signature(skill_path: &Path, source: &SkillSource, rev: Option<&str>, commit: &str) -> Result<crate::trust::TagVerification, XskillError> {
+    let Some(tag) = source.git_ref.as_deref().or(rev) else {
+        return Ok(Default::default());
+    };
+    let dir = if skill_path.join(".git").exists() {
+        skill_path.to_path_buf()
+    } else {
+        RepoCache::open()?.path_for(&source.repo)
+    };
+    let mut checked = crate::trust::verify_git_tag(&dir, tag, commit).map_err(XskillError::integrity)?;
+    checked.signature = checked.signature.map(|s| s.seal(skill_path)).transpose().map_err(XskillError::integrity)?;
+    Ok(checked)
+}
+
+/// Record the installed source and re-check its tag signature. Returns a warning about a
+/// signature that could not be checked.
+fn record_source(skill_path: &Path, source: SkillSource) -> Result<Option<String>, XskillError> {
+    let mut meta = read_skill_meta(skill_path).unwrap_or_default();
+    if meta.original_url.is_none() {
+        meta.original_url = Some(source.url());
+    }
+    let checked = tag_signature(skill_path, &source, None, &source.commit)?;
+    meta.signature = checked.signature;
+    meta.source = Some(source);
+    write_skill_meta(skill_path, &meta)?;
+    Ok(checked.warning)
+}
+
+/// Drop a recorded signature that no longer describes the installed files.
+fn forget_signature(skill_path: &Path) -> Result<(), XskillError> {
+    let mut meta = read_skill_meta(skill_path).unwrap_or_default();
+    if meta.signature.take().is_some() {
+        write_skill_meta(skill_path, &meta)?;
+    }
     Ok(())
 }
 
+/// Update an installed skill. See [`Xskill::update_skill`].
+pub async fn core_update_skill(skill_path: &Path, pin: Option<&str>) -> Result<String, XskillError> {
+    Xskill::load()?.update_skill(skill_path, pin).await
+}
+
 #[tauri::command]
-pub async fn install_skill_from_url(window: Window, repo_url: String) -> Result<String, String> {
-    core_install_skill_from_url(&repo_url, |msg| {
+pub async fn install_skill_from_url(window: Window, repo_url: String, pin: Option<String>, sha256: Option<String>) -> Result<String, XskillError> {
+    let target_dir = Xskill::load()?.install_skill(&repo_url, pin.as_deref(), sha256.as_deref(), |msg| {
         let _ = window.emit("import-progress", msg);
-    }).await
+    }).await?;
+    Ok(target_dir.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-pub async fn clone_skill(window: Window, repo_url: String, target_dir: String) -> Result<(), String> {
+pub async fn clone_skill(window: Window, repo_url: String, target_dir: String) -> Result<(), XskillError> {
     core_clone_skill(&repo_url, &target_dir, |msg| {
         let _ = window.emit("import-progress", msg);
     }).await
 }
 
 #[tauri::command]
-pub async fn update_skill(skill_dir: String) -> Result<(), String> {
-    let skill_path = Path::new(&skill_dir);
+pub async fn update_skill(skill_dir: String, pin: Option<String>) -> Result<(), XskillError> {
+    Xskill::load()?.update_skill(Path::new(&skill_dir), pin.as_deref()).await.map(|_| ())
+}
+
+/// Upstream state of one git-backed Hub skill, as reported by `check_updates`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub name: String,
+    pub path: String,
+    pub repo: String,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    pub subpath: Option<String>,
+    pub pinned: bool,
+    /// Installed commit; `None` for older subdirectory installs that never recorded one.
+    pub current: Option<String>,
+    /// Tip of the followed branch upstream.
+    pub latest: Option<String>,
+    /// Local commits that are not upstream (clones only).
+    pub ahead: usize,
+    /// Upstream commits not installed. For subdirectory installs only commits touching the subdirectory count.
+    pub behind: usize,
+    /// Files changed upstream, relative to the skill directory.
+    pub changed_files: Vec<String>,
+    /// Files edited, added or deleted in the installed copy since `current`.
+    pub local_changes: Vec<String>,
+    pub update_available: bool,
+    pub error: Option<String>,
+}
+
+struct Upstream {
+    current: Option<String>,
+    latest: String,
+    ahead: usize,
+    behind: usize,
+    changed_files: Vec<String>,
+    local_changes: Vec<String>,
+}
 
-    if !skill_path.exists() {
-        return Err(format!("Directory does not exist: {}", skill_dir));
+/// Fetch a clone's origin and compare HEAD with the branch it follows.
+fn check_clone(dir: &Path, source: &SkillSource) -> Result<Upstream, XskillError> {
+    let git = backend();
+    git.fetch(dir, &mut |_| {})?;
+    let upstream = tracked_branch(dir, source, "origin/", "origin/HEAD");
+    let current = resolve_commit(dir)?;
+    let latest = git.resolve(dir, &upstream)?;
+    let (ahead, behind) = git.ahead_behind(dir, &current, &latest)?;
+    Ok(Upstream {
+        changed_files: git.changed_paths(dir, &current, &latest, None)?,
+        local_changes: git.modified_paths(dir)?,
+        current: Some(current),
+        latest,
+        ahead,
+        behind,
+    })
+}
+
+/// Subdirectory installs are plain copies: compare the recorded commit and the files on
+/// disk against the shared cached clone, looking only at the subdirectory.
+fn check_subdir(skill_path: &Path, source: &SkillSource, subpath: &str, cache: &mut RepoCache) -> Result<Upstream, XskillError> {
+    let cached = cache.sync(&source.repo, &mut |_| {})?;
+    let branch = tracked_branch(&cached, source, "refs/heads/", "HEAD");
+    let latest = repo_cache::resolve(&cached, Some(&branch))?;
+    if !repo_cache::has_commit(&cached, &source.commit) {
+        return Ok(Upstream { current: None, latest, ahead: 0, behind: 0, changed_files: Vec::new(), local_changes: Vec::new() });
+    }
+    Ok(Upstream {
+        current: Some(source.commit.clone()),
+        ahead: 0,
+        behind: repo_cache::commits_touching(&cached, &source.commit, &latest, subpath)?,
+        changed_files: repo_cache::changed_files(&cached, &source.commit, &latest, subpath)?,
+        local_changes: repo_cache::local_changes(&cached, &source.commit, subpath, skill_path)?,
+        latest,
+    })
+}
+
+/// Check one installed skill against its upstream. `None` for skills that are not git-backed.
+pub fn check_skill_update(skill_path: &Path) -> Option<UpdateCheck> {
+    match RepoCache::open() {
+        Ok(mut cache) => check_with_cache(skill_path, &mut cache),
+        Err(_) => None,
     }
+}
 
-    let output = git_cmd()
-        .args(["pull", "--ff-only"])
-        .current_dir(skill_path)
-        .output()
-        .map_err(|e| format!("Failed to execute git pull: {}", e))?;
+fn check_with_cache(skill_path: &Path, cache: &mut RepoCache) -> Option<UpdateCheck> {
+    let source = installed_source(skill_path).filter(|s| !s.repo.is_empty())?;
+    let is_clone = skill_path.join(".git").exists();
+    let upstream = match (&source.subpath, is_clone) {
+        (_, true) => check_clone(skill_path, &source),
+        (Some(subpath), false) => check_subdir(skill_path, &source, subpath, cache),
+        (None, false) => return None,
+    };
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("git pull failed: {}", stderr.trim()));
+    let mut check = UpdateCheck {
+        name: skill_path.file_name()?.to_string_lossy().to_string(),
+        path: skill_path.to_string_lossy().to_string(),
+        repo: source.repo.clone(),
+        git_ref: source.git_ref.clone(),
+        subpath: source.subpath.clone(),
+        pinned: source.pinned,
+        current: None,
+        latest: None,
+        ahead: 0,
+        behind: 0,
+        changed_files: Vec::new(),
+        local_changes: Vec::new(),
+        update_available: false,
+        error: None,
+    };
+    match upstream {
+        Ok(up) => {
+            // Without a recorded commit, an update is what starts tracking it
+            check.update_available = up.behind > 0 || up.current.is_none();
+            check.current = up.current;
+            check.latest = Some(up.latest);
+            check.ahead = up.ahead;
+            check.behind = up.behind;
+            check.changed_files = up.changed_files;
+            check.local_changes = up.local_changes;
+        }
+        Err(e) => check.error = Some(e.to_string()),
     }
+    Some(check)
+}
 
-    Ok(())
+/// Check every git-backed skill in the Hub for upstream changes.
+pub fn check_updates_all() -> Result<Vec<UpdateCheck>, String> {
+    Ok(Xskill::load()?.check_updates()?)
+}
+
+/// Apply every available update, leaving pinned, diverged and locally edited skills alone.
+pub async fn update_all() -> Result<Vec<LockAction>, String> {
+    Ok(Xskill::load()?.update_all().await?)
+}
+
+pub fn short_commit(commit: &str) -> &str {
+    &commit[..commit.len().min(7)]
+}
+
+#[tauri::command]
+pub async fn check_updates() -> Result<Vec<UpdateCheck>, XskillError> {
+    Xskill::load()?.check_updates()
+}
+
+#[tauri::command]
+pub async fn update_all_skills() -> Result<Vec<LockAction>, XskillError> {
+    Xskill::load()?.update_all().await
+}
+
+/// What a bulk install or update did with one skill.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockAction {
+    pub name: String,
+    /// `"installed"`, `"updated"`, `"unchanged"`, `"skipped"` or `"failed"`.
+    pub action: String,
+    pub detail: Option<String>,
+}
+
+async fn apply_locked(xskill: &Xskill, locked: &LockedSkill) -> Result<(&'static str, Option<String>), XskillError> {
+    // skills.lock is committed to shared repositories: never follow a name out of the Hub
+    crate::scaffold::validate_hub_name(&locked.name)?;
+    let target = xskill.hub().join(&locked.name);
+    let Some(source) = &locked.source else {
+        return Ok(if target.exists() {
+            ("unchanged", None)
+        } else {
+            ("skipped", Some("Local skill; copy it over or restore it from a Hub manifest".to_string()))
+        });
+    };
+
+    if !target.exists() {
+        install_source(xskill, source, Some(&source.commit), &locked.name, |_| {}).await?;
+        return Ok(("installed", Some(source.commit.clone())));
+    }
+
+    let installed = installed_source(&target).unwrap_or_default();
+    let current = installed.commit.clone();
+    if current == source.commit {
+        return Ok(("unchanged", None));
+    }
+    let commit = xskill.move_audited(&target, source, Some(&source.commit)).await?;
+    let warning = record_source(&target, SkillSource { commit: commit.clone(), ..source.clone() })?;
+    let detail = format!("{} -> {}", current, commit);
+    Ok(("updated", Some(warning.map(|w| format!("{}; {}", detail, w)).unwrap_or(detail))))
+}
+
+/// Bring the Hub to the exact revisions in `lock`. See [`Xskill::install_from_lock`].
+pub async fn install_from_lock(lock: &SkillsLock) -> Result<Vec<LockAction>, String> {
+    Ok(Xskill::load()?.install_from_lock(lock).await?)
+}
+
+#[tauri::command]
+pub async fn install_skills_from_lock(lock_path: Option<String>) -> Result<Vec<LockAction>, XskillError> {
+    let path = match lock_path {
+        Some(p) => PathBuf::from(p),
+        None => crate::skill_lock::lock_path()?,
+    };
+    let lock = SkillsLock::load_from(&path)?;
+    Xskill::load()?.install_from_lock(&lock).await
 }
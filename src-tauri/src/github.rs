@@ -1,4 +1,4 @@
-use reqwest::header::USER_AGENT;
+use crate::error::XskillError;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -15,32 +15,14 @@ pub struct GithubContent {
     pub encoding: Option<String>,
 }
 
+/// Raw-content URL for a file link on GitHub or any other known forge.
 pub fn convert_github_url(url: &str) -> Option<String> {
-    if url.contains("raw.githubusercontent.com") {
-        Some(url.to_string())
-    } else if url.contains("github.com") && url.contains("/blob/") {
-        Some(url.replace("github.com", "raw.githubusercontent.com").replace("/blob/", "/"))
-    } else {
-        None
-    }
+    crate::forge::detect(url).raw_url(url)
 }
 
 #[tauri::command]
-pub async fn fetch_github_file(url: String) -> Result<String, String> {
-    let raw_url = convert_github_url(&url).ok_or("Unsupported URL format")?;
-
-    let client = reqwest::Client::builder().timeout(std::time::Duration::from_secs(10)).build().unwrap();
-    let resp = client.get(&raw_url)
-        .header(USER_AGENT, "xskill")
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-        
-    if !resp.status().is_success() {
-        return Err(format!("Failed to fetch: {}", resp.status()));
-    }
-    
-    resp.text().await.map_err(|e| e.to_string())
+pub async fn fetch_github_file(url: String) -> Result<String, XskillError> {
+    crate::forge::fetch_raw(&url).await.map_err(XskillError::network)
 }
 
 #[cfg(test)]
@@ -64,6 +46,6 @@ mod tests {
     async fn test_fetch_github_file_invalid() {
         let result = fetch_github_file("https://google.com".to_string()).await;
         assert!(result.is_err());
-        assert_eq!(result.unwrap_err(), "Unsupported URL format");
+        assert_eq!(result.unwrap_err(), XskillError::network("Unsupported URL format"));
     }
 }
@@ -0,0 +1,366 @@
+use crate::error::XskillError;
+use crate::config_manager::{load_all_configs, save_all_configs, SkillConfig};
+use crate::audit::Severity;
+use crate::git_manager::LockAction;
+use crate::skill_lock::{LockedSkill, SkillSource, SkillsLock};
+use crate::skill_manager::CENTRAL_SKILLS_DIR;
+use crate::store::FeedEntry;
+use crate::suite_manager::{load_suites, save_suites, Suite};
+use crate::sync_txn::Transaction;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Everything needed to rebuild a Hub on another machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HubManifest {
+    pub version: u32,
+    pub exported_at: u64,
+    pub skills: Vec<ManifestSkill>,
+    #[serde(default)]
+    pub suites: Vec<Suite>,
+    #[serde(default)]
+    pub skill_configs: BTreeMap<String, SkillConfig>,
+    #[serde(default)]
+    pub feeds: Vec<FeedEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestSkill {
+    /// Directory name in the Hub.
+    pub name: String,
+    pub fingerprint: String,
+    #[serde(flatten)]
+    pub origin: SkillOrigin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "origin", rename_all = "snake_case")]
+pub enum SkillOrigin {
+    /// Re-cloned at the recorded commit.
+    Git { source: SkillSource },
+    /// Local skill carried inline, keyed by relative path.
+    Embedded { files: BTreeMap<String, EmbeddedFile> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddedFile {
+    /// UTF-8 text, or base64 when `base64` is set.
+    pub content: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub executable: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RestoreReport {
+    pub skills: Vec<LockAction>,
+    pub suites_added: usize,
+    pub suites_updated: usize,
+    pub configs_written: usize,
+    pub feeds_added: usize,
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+fn embed_dir(dir: &Path) -> Result<BTreeMap<String, EmbeddedFile>, String> {
+    let mut files = BTreeMap::new();
+    for (rel, abs) in crate::utils::list_files(dir)? {
+        let bytes = fs::read(&abs).map_err(|e| format!("Failed to read {}: {}", abs.display(), e))?;
+        let (content, base64) = match String::from_utf8(bytes) {
+            Ok(text) => (text, false),
+            Err(e) => (base64::engine::general_purpose::STANDARD.encode(e.into_bytes()), true),
+        };
+        files.insert(
+            rel.to_string_lossy().replace('\\', "/"),
+            EmbeddedFile { content, base64, executable: is_executable(&abs) },
+        );
+    }
+    Ok(files)
+}
+
+fn decode(file: &EmbeddedFile) -> Result<Vec<u8>, String> {
+    if file.base64 {
+        base64::engine::general_purpose::STANDARD.decode(&file.content).map_err(|e| e.to_string())
+    } else {
+        Ok(file.content.clone().into_bytes())
+    }
+}
+
+/// Whether `dir` already holds exactly the embedded files.
+fn matches_embedded(dir: &Path, files: &BTreeMap<String, EmbeddedFile>) -> Result<bool, String> {
+    if !dir.exists() {
+        return Ok(false);
+    }
+    let current = embed_dir(dir)?;
+    Ok(current.len() == files.len()
+        && files.iter().all(|(rel, f)| current.get(rel).map(|c| c.content == f.content && c.base64 == f.base64).unwrap_or(false)))
+}
+
+/// Write an embedded skill to `dest` through a transaction, auditing the staged copy first
+/// so a blocked skill never reaches the Hub.
+fn write_embedded(dest: &Path, files: &BTreeMap<String, EmbeddedFile>, block: Option<Severity>) -> Result<(), XskillError> {
+    let mut tx = Transaction::begin()?;
+    let stage = tx.stage(dest)?;
+    let written = (|| {
+        fs::create_dir_all(&stage).map_err(|e| XskillError::io(&stage, e))?;
+        for (rel, file) in files {
+            if rel.split('/').any(|c| c == ".." || c.is_empty()) {
+                return Err(XskillError::invalid(format!("Refusing to write outside the skill directory: {}", rel)));
+            }
+            let target = stage.join(rel);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+            }
+            fs::write(&target, decode(file)?).map_err(|e| XskillError::io(&target, e))?;
+            #[cfg(unix)]
+            if file.executable {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&target, fs::Permissions::from_mode(0o755)).map_err(|e| XskillError::io(&target, e))?;
+            }
+        }
+        crate::audit::gate(&stage, block, |_| {})?;
+        Ok(())
+    })();
+    match written {
+        Ok(_) => tx.commit(),
+        Err(e) => {
+            let _ = tx.rollback();
+            Err(e)
+        }
+    }
+}
+
+/// Snapshot the Hub, suites, skill configs and `feeds` into a manifest.
+pub fn build_manifest(feeds: Vec<FeedEntry>) -> Result<HubManifest, XskillError> {
+    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+    let hub = home.join(CENTRAL_SKILLS_DIR);
+
+    let mut skills = Vec::new();
+    for locked in SkillsLock::from_hub(&hub)?.skills {
+        let dir = hub.join(&locked.name);
+        let source = locked.source.or_else(|| crate::git_manager::installed_source(&dir)).and_then(|mut s| {
+            if s.commit.is_empty() {
+                s.commit = crate::git_manager::resolve_commit(&dir).ok()?;
+            }
+            Some(s)
+        });
+        let origin = match source {
+            Some(source) => SkillOrigin::Git { source },
+            None => SkillOrigin::Embedded { files: embed_dir(&dir)? },
+        };
+        skills.push(ManifestSkill { name: locked.name, fingerprint: locked.fingerprint, origin });
+    }
+
+    Ok(HubManifest {
+        version: MANIFEST_VERSION,
+        exported_at: crate::sync_ledger::now_secs(),
+        skills,
+        suites: load_suites()?,
+        skill_configs: load_all_configs()?.into_iter().collect(),
+        feeds,
+    })
+}
+
+pub fn write_manifest(manifest: &HubManifest, path: &Path) -> Result<(), XskillError> {
+    let content = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+    fs::write(path, content).map_err(|e| XskillError::io(path, e))
+}
+
+pub fn read_manifest(path: &Path) -> Result<HubManifest, XskillError> {
+    let content = fs::read_to_string(path).map_err(|e| XskillError::io(path, e))?;
+    let manifest: HubManifest = serde_json::from_str(&content)
+        .map_err(|e| XskillError::invalid(format!("Failed to parse {}: {}", path.display(), e)))?;
+    if manifest.version > MANIFEST_VERSION {
+        return Err(XskillError::invalid(format!(
+            "Manifest version {} is newer than this xskill supports ({})",
+            manifest.version, MANIFEST_VERSION
+        )));
+    }
+    Ok(manifest)
+}
+
+/// Snapshot everything into a manifest at `path`, with `feeds` from whichever store holds
+/// them (the app's store in the UI, the store file from the CLI).
+pub fn export_to(path: &Path, feeds: Vec<FeedEntry>) -> Result<HubManifest, XskillError> {
+    let manifest = build_manifest(feeds)?;
+    write_manifest(&manifest, path)?;
+    Ok(manifest)
+}
+
+/// Read the manifest at `path` and [`restore`] it, then add its feeds through `load_feeds`
+/// and `save_feeds`. Feeds are read first, so a broken feed store stops the restore before
+/// anything is written.
+pub async fn restore_from(
+    path: &Path,
+    force: bool,
+    load_feeds: impl FnOnce() -> Result<Vec<FeedEntry>, XskillError>,
+    save_feeds: impl FnOnce(Vec<FeedEntry>) -> Result<(), XskillError>,
+) -> Result<RestoreReport, XskillError> {
+    let manifest = read_manifest(path)?;
+    let current = load_feeds()?;
+    let mut report = restore(&manifest, force).await?;
+    let (feeds, added) = merge_feeds(current, &manifest.feeds);
+    if added > 0 {
+        save_feeds(feeds)?;
+    }
+    report.feeds_added = added;
+    Ok(report)
+}
+
+/// Append feeds not already present (by id or URL). Returns the merged list and how many were added.
+pub fn merge_feeds(mut current: Vec<FeedEntry>, incoming: &[FeedEntry]) -> (Vec<FeedEntry>, usize) {
+    let mut added = 0;
+    for feed in incoming {
+        if !current.iter().any(|f| f.id == feed.id || f.url == feed.url) {
+            current.push(feed.clone());
+            added += 1;
+        }
+    }
+    (current, added)
+}
+
+/// Bring `~/.xskill` in line with `manifest`. Running it twice changes nothing the second time.
+///
+/// Local skills that differ from the manifest are left alone unless `force` is set.
+/// Embedded skills are audited like installs. A manifest with any skill name that is not a
+/// plain Hub directory name is rejected before anything is written.
+/// Feeds are not touched here since they live in the app store; see `restore_from`.
+pub async fn restore(manifest: &HubManifest, force: bool) -> Result<RestoreReport, XskillError> {
+    for skill in &manifest.skills {
+        crate::scaffold::validate_hub_name(&skill.name)?;
+    }
+    let xskill = crate::xskill::Xskill::load()?;
+    let hub = xskill.hub();
+    fs::create_dir_all(hub).map_err(|e| XskillError::io(hub, e))?;
+    let block = xskill.config().audit.block;
+    let mut report = RestoreReport::default();
+
+    let mut git_lock = SkillsLock::default();
+    for skill in &manifest.skills {
+        match &skill.origin {
+            SkillOrigin::Git { source } => git_lock.skills.push(LockedSkill {
+                name: skill.name.clone(),
+                fingerprint: skill.fingerprint.clone(),
+                source: Some(source.clone()),
+            }),
+            SkillOrigin::Embedded { files } => {
+                let dest = hub.join(&skill.name);
+                let (action, detail) = match matches_embedded(&dest, files) {
+                    Ok(true) => ("unchanged", None),
+                    Ok(false) if dest.exists() && !force => {
+                        ("skipped", Some("Differs from the manifest; use --force to overwrite".to_string()))
+                    }
+                    Ok(false) => {
+                        let existed = dest.exists();
+                        match write_embedded(&dest, files, block) {
+                            Ok(_) if existed => ("updated", None),
+                            Ok(_) => ("installed", None),
+                            Err(e) => ("failed", Some(e.to_string())),
+                        }
+                    }
+                    Err(e) => ("failed", Some(e)),
+                };
+                report.skills.push(LockAction { name: skill.name.clone(), action: action.to_string(), detail });
+            }
+        }
+    }
+    report.skills.extend(xskill.install_from_lock(&git_lock).await?);
+
+    // Suites are matched by id; manifest wins for the ones it contains
+    let mut suites = load_suites()?;
+    let mut suites_changed = false;
+    for suite in &manifest.suites {
+        match suites.iter_mut().find(|s| s.id == suite.id) {
+            Some(existing) => {
+                if serde_json::to_value(&*existing).ok() != serde_json::to_value(suite).ok() {
+                    *existing = suite.clone();
+                    report.suites_updated += 1;
+                    suites_changed = true;
+                }
+            }
+            None => {
+                suites.push(suite.clone());
+                report.suites_added += 1;
+                suites_changed = true;
+            }
+        }
+    }
+    if suites_changed {
+        save_suites(suites)?;
+    }
+
+    let mut configs = load_all_configs()?;
+    for (name, config) in &manifest.skill_configs {
+        let same = configs.get(name).map(|c| serde_json::to_value(c).ok() == serde_json::to_value(config).ok()).unwrap_or(false);
+        if !same {
+            configs.insert(name.clone(), config.clone());
+            report.configs_written += 1;
+        }
+    }
+    if report.configs_written > 0 {
+        save_all_configs(&configs)?;
+    }
+
+    crate::skill_lock::refresh_quietly();
+    Ok(report)
+}
+
+#[tauri::command]
+pub fn export_hub_manifest(app: AppHandle, path: String) -> Result<HubManifest, XskillError> {
+    export_to(Path::new(&path), crate::store::load_feeds(app)?)
+}
+
+#[tauri::command]
+pub async fn restore_hub_manifest(app: AppHandle, path: String, force: Option<bool>) -> Result<RestoreReport, XskillError> {
+    let load = || crate::store::load_feeds(app.clone());
+    let save = |feeds| crate::store::save_feeds(app.clone(), feeds);
+    restore_from(Path::new(&path), force.unwrap_or(false), load, save).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_embed_roundtrip_with_binary_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("assets")).unwrap();
+        fs::write(src.join("SKILL.md"), "---\nname: x\n---\n").unwrap();
+        fs::write(src.join("assets/logo.bin"), [0u8, 159, 146, 150]).unwrap();
+
+        let files = embed_dir(&src).unwrap();
+        assert!(files["assets/logo.bin"].base64);
+        assert!(!files["SKILL.md"].base64);
+
+        let dest = temp_dir.path().join("dest");
+        fs::create_dir_all(&dest).unwrap();
+        for (rel, file) in &files {
+            let target = dest.join(rel);
+            fs::create_dir_all(target.parent().unwrap()).unwrap();
+            fs::write(target, decode(file).unwrap()).unwrap();
+        }
+        assert!(matches_embedded(&dest, &files).unwrap());
+        fs::write(dest.join("extra.md"), "x").unwrap();
+        assert!(!matches_embedded(&dest, &files).unwrap());
+    }
+}
@@ -1,61 +1,45 @@
-use crate::config_manager::get_skill_config;
-use crate::skill_manager::CENTRAL_SKILLS_DIR;
+use crate::error::XskillError;
+use crate::plan::{Plan, PlannedOp};
+use crate::render::{self, SkillFormat};
+use crate::skill_manager::LocalSkill;
+use crate::sync_ledger::{self, SyncLedger};
+use crate::sync_merge::{self, ConflictStrategy, Resolution, SyncConflict};
+use crate::sync_txn::{journal_dir_in, Transaction};
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-
-fn tool_skills_dir(tool_key: &str) -> Option<PathBuf> {
-    let home = crate::utils::get_home_dir()?;
-    let subdir = match tool_key {
-        "cursor"         => ".cursor/skills",
-        "vscode"         => ".vscode/skills",
-        "claude_code"    => ".claude/skills",
-        "opencode"       => ".config/opencode/skills",
-        "windsurf"       => ".codeium/windsurf/skills",
-        "trae"           => ".trae/skills",
-        "gemini_cli"     => ".gemini/skills",
-        "github_copilot" => ".copilot/skills",
-        "amp"            => ".config/agents/skills",
-        "goose"          => ".config/goose/skills",
-        "antigravity"    => ".gemini/antigravity/global_skills",
-        "augment"        => ".augment/rules",
-        "codex"          => ".codex/skills",
-        "kimi_cli"       => ".kimi/skills",
-        "openclaw"       => ".openclaw/skills",
-        "cline"          => ".cline/skills",
-        "codebuddy"      => ".codebuddy/skills",
-        "continue_dev"   => ".continue/skills",
-        "crush"          => ".crush/skills",
-        "junie"          => ".junie/skills",
-        "kode"           => ".kode/skills",
-        "roo_code"       => ".roo-code/skills",
-        "kilo_code"      => ".kilocode/skills",
-        _ => return None,
-    };
-    Some(home.join(subdir))
-}
+use std::path::{Path, PathBuf};
 
-fn update_claude_desktop_config(skill_name: &str, _dest_path: &PathBuf) -> Result<(), String> {
+fn update_claude_desktop_config(xskill: &Xskill, skill_name: &str, dest_path: &Path, plan: &mut Plan) -> Result<(), XskillError> {
+    // Get skill config
+    let skill_config = xskill.skill_config(skill_name, Some(dest_path))?;
 
-    // Only works on macOS for now
-    let home = crate::utils::get_home_dir().ok_or("Could not find home dir")?;
-    let config_path = home.join("Library/Application Support/Claude/claude_desktop_config.json");
-    
-    if !config_path.exists() {
-        if let Some(parent) = config_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    // Only update if we have a command configured
+    if let Some(cmd) = skill_config.command {
+        // `~/Library/Application Support/Claude` on macOS, `%APPDATA%\Claude` on Windows
+        let config_path = crate::utils::get_app_data_dir()
+            .ok_or_else(|| XskillError::from("Could not find app data directory"))?
+            .join("Claude/claude_desktop_config.json");
+        plan.record(PlannedOp::PatchClaudeConfig {
+            path: config_path.to_string_lossy().to_string(),
+            server: skill_name.to_string(),
+        });
+        if plan.dry_run {
+            return Ok(());
+        }
+
+        if !config_path.exists() {
+            if let Some(parent) = config_path.parent() {
+                fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+            }
+            fs::write(&config_path, "{}").map_err(|e| XskillError::io(&config_path, e))?;
         }
-        fs::write(&config_path, "{}").map_err(|e| e.to_string())?;
-    }
 
-    let content = fs::read_to_string(&config_path).map_err(|e| e.to_string())?;
-    let mut json: Value = serde_json::from_str(&content).unwrap_or(serde_json::json!({}));
+        let content = fs::read_to_string(&config_path).map_err(|e| XskillError::io(&config_path, e))?;
+        let mut json: Value = serde_json::from_str(&content).unwrap_or(serde_json::json!({}));
 
-    // Get skill config
-    let skill_config = get_skill_config(skill_name.to_string(), Some(_dest_path.to_string_lossy().to_string()))?;
-    
-    // Only update if we have a command configured
-    if let Some(cmd) = skill_config.command {
         let args = skill_config.args.unwrap_or_default();
         let env = skill_config.env.unwrap_or_default();
 
@@ -72,112 +56,468 @@ fn update_claude_desktop_config(skill_name: &str, _dest_path: &PathBuf) -> Resul
         }
 
         let new_content = serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?;
-        fs::write(&config_path, new_content).map_err(|e| e.to_string())?;
+        fs::write(&config_path, new_content).map_err(|e| XskillError::io(&config_path, e))?;
     }
 
     Ok(())
 }
 
-/// Sync a skill directory to one or more target tool skill directories.
+/// How `sync_skill` delivers a skill and what it does with local edits.
+#[derive(Debug, Clone, Default)]
+pub struct SyncOptions {
+    /// Symlink each agent copy to the source instead of copying it.
+    pub link: bool,
+    pub on_conflict: ConflictStrategy,
+    /// Per-file overrides of `on_conflict`, keyed by path relative to the skill directory.
+    pub resolutions: HashMap<String, ConflictStrategy>,
+    pub dry_run: bool,
+}
+
+/// Outcome of `sync_skill` across all requested agents.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// Destination directories that now hold the skill.
+    pub written: Vec<String>,
+    /// Files edited inside agent directories since the last sync, and what was done with them.
+    pub conflicts: Vec<SyncConflict>,
+    /// Failures after the skill landed (e.g. patching the Claude config) that did not undo the sync.
+    pub errors: Vec<String>,
+    /// Filesystem operations performed, or only planned when `dry_run` is set.
+    pub operations: Vec<PlannedOp>,
+    /// Symlinks inside the skill that copies and rendered rules left out, relative to it.
+    pub skipped_links: Vec<String>,
+    pub dry_run: bool,
+}
+
+impl SyncReport {
+    pub fn unresolved(&self) -> impl Iterator<Item = &SyncConflict> {
+        self.conflicts.iter().filter(|c| matches!(c.resolution, Resolution::Unresolved | Resolution::Aborted))
+    }
+}
+
+/// Which Hub skills a bulk sync pushes and which agents receive them.
+#[derive(Debug, Clone, Default)]
+pub struct SyncSelection {
+    /// Skill names or glob patterns (`review-*`), matched against the SKILL.md name and the
+    /// directory name. With `tags` also empty, every Hub skill is selected.
+    pub skills: Vec<String>,
+    /// Select skills carrying any of these tags.
+    pub tags: Vec<String>,
+    /// Names or glob patterns to leave out even when selected.
+    pub exclude: Vec<String>,
+    /// Agent keys; empty means every installed agent.
+    pub agents: Vec<String>,
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<glob::Pattern>, XskillError> {
+    patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).map_err(|e| XskillError::invalid(format!("Invalid pattern '{}': {}", p, e))))
+        .collect()
+}
+
+fn matches_any(patterns: &[glob::Pattern], skill: &LocalSkill) -> bool {
+    let dir_name = Path::new(&skill.path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    patterns.iter().any(|p| p.matches(&skill.name) || p.matches(&dir_name))
+}
+
+/// Stage one agent's new copy in `tx` (or plan it in place in dry-run mode).
 ///
-/// `mode` controls how the skill is delivered:
-/// - `"copy"` (default): copy the directory recursively
-/// - `"link"`: create a symlink pointing back to `skill_dir`
-#[tauri::command]
-pub fn sync_skill(
-    skill_dir: String,
-    target_tool_keys: Vec<String>,
-    mode: Option<String>,
-) -> Result<Vec<String>, String> {
-    let src = PathBuf::from(&skill_dir);
-    if !src.exists() {
-        return Err(format!("Skill directory does not exist: {}", skill_dir));
-    }
-
-    let skill_name = src.file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| format!("Invalid skill directory path: {}", skill_dir))?
-        .to_string();
-
-    let use_link = mode.as_deref() == Some("link");
-
-    let mut written_paths: Vec<String> = Vec::new();
-    let mut errors: Vec<String> = Vec::new();
-
-    for tool_key in &target_tool_keys {
-        match tool_skills_dir(tool_key) {
-            None => errors.push(format!("Unknown tool key: {}", tool_key)),
-            Some(skills_dir) => {
-                let dest = skills_dir.join(&skill_name);
-                if let (Ok(s), Ok(d)) = (fs::canonicalize(&src), fs::canonicalize(&dest)) {
-                    if s == d {
-                        written_paths.push(dest.to_string_lossy().to_string());
-                        continue;
-                    }
-                }
+/// Returns false if the agent was skipped because of local edits.
+#[allow(clippy::too_many_arguments)]
+fn stage_target(
+    tool_key: &str,
+    src: &Path,
+    rendered: Option<(&str, &[u8])>,
+    dest: &Path,
+    base: &Path,
+    mut tx: Option<&mut Transaction>,
+    options: &SyncOptions,
+    plan: &mut Plan,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Result<bool, XskillError> {
+    let (work_dest, work_base) = match tx.as_deref_mut() {
+        Some(tx) => (tx.stage(dest)?, tx.stage(base)?),
+        None => (dest.to_path_buf(), base.to_path_buf()),
+    };
+    let start = plan.ops.len();
+    let applied = write_target(tool_key, src, rendered, dest, base, (&work_dest, &work_base), options, plan, conflicts)?;
+    plan.retarget(start, &work_dest, dest);
+    if !applied {
+        if let Some(tx) = tx {
+            tx.unstage(dest)?;
+            tx.unstage(base)?;
+        }
+    }
+    Ok(applied)
+}
+
+/// Write the new content for one agent into `work_dest` / `work_base`, which are the
+/// transaction's staging directories (or `dest` / `base` themselves in dry-run mode).
+///
+/// `rendered` is the file name and content for agents that take a rendered file instead
+/// of the skill directory; they get it in link mode too.
+#[allow(clippy::too_many_arguments)]
+fn write_target(
+    tool_key: &str,
+    src: &Path,
+    rendered: Option<(&str, &[u8])>,
+    dest: &Path,
+    base: &Path,
+    (work_dest, work_base): (&Path, &Path),
+    options: &SyncOptions,
+    plan: &mut Plan,
+    conflicts: &mut Vec<SyncConflict>,
+) -> Result<bool, XskillError> {
+    let (use_link, strategy, resolutions) = (options.link, options.on_conflict, &options.resolutions);
+    // A previous copy with a recorded base can be merged instead of overwritten
+    let has_base = dest.is_dir() && !dest.is_symlink() && base.exists();
+    // A directory with no base was not written by xskill: like `merge_file`, every file in
+    // it that differs from the Hub counts as a local edit
+    let unmanaged = dest.is_dir() && !dest.is_symlink() && !base.exists();
+    let replaces = dest.exists() || dest.is_symlink();
+
+    if let Some((file, content)) = rendered {
+        if !plan.dry_run {
+            if dest.is_file() {
+                fs::copy(dest, work_dest).map_err(|e| XskillError::io(dest, e))?;
+            }
+            if base.exists() {
+                crate::utils::copy_tree(base, work_base)?;
+            }
+        }
+        let outcome = sync_merge::merge_file(tool_key, file, content, work_dest, work_base, strategy, plan)?;
+        let dest_path = dest.to_string_lossy().to_string();
+        conflicts.extend(outcome.conflicts.into_iter().map(|c| SyncConflict { dest_path: dest_path.clone(), ..c }));
+        return Ok(outcome.applied);
+    }
 
-                let result = if use_link {
-                    crate::utils::symlink_dir(&src, &dest)
+    if use_link {
+        if (has_base || unmanaged) && strategy != ConflictStrategy::KeepOurs {
+            let edited = sync_merge::local_edits(dest, if has_base { base } else { src })?;
+            if !edited.is_empty() {
+                let resolution = if strategy == ConflictStrategy::KeepTheirs {
+                    Resolution::KeptTheirs
                 } else {
-                    crate::utils::copy_dir_all(&src, &dest)
+                    Resolution::Aborted
                 };
-
-                match result {
-                    Ok(_) => {
-                        written_paths.push(dest.to_string_lossy().to_string());
-                        // Try to inject config for known tools
-                        if tool_key == "claude_code" || tool_key == "claude_desktop" {
-                             if let Err(e) = update_claude_desktop_config(&skill_name, &dest) {
-                                 errors.push(format!("Claude Config Error: {}", e));
-                             }
-                        }
-                    },
-                    Err(e) => errors.push(format!("{}: {}", tool_key, e)),
-                }
+                conflicts.extend(edited.into_iter().map(|file| SyncConflict {
+                    agent: tool_key.to_string(),
+                    dest_path: dest.to_string_lossy().to_string(),
+                    file,
+                    hub_changed: false,
+                    resolution,
+                }));
+                return Ok(false);
             }
         }
+        // The base is left unstaged, so the commit removes it along with the old copy
+        plan.record(PlannedOp::CreateSymlink {
+            path: dest.to_string_lossy().to_string(),
+            target: src.to_string_lossy().to_string(),
+            replaces,
+        });
+        if !plan.dry_run {
+            crate::utils::symlink_dir(&src.to_path_buf(), &work_dest.to_path_buf())?;
+        }
+        return Ok(true);
     }
 
-    if !errors.is_empty() && written_paths.is_empty() {
-        return Err(errors.join("; "));
+    if has_base || unmanaged {
+        if !plan.dry_run {
+            crate::utils::copy_tree(dest, work_dest)?;
+            if has_base {
+                crate::utils::copy_tree(base, work_base)?;
+            }
+        }
+        // Without a base the merge runs against nothing, so identical files are adopted
+        // and differing ones become conflicts
+        let outcome = sync_merge::merge_into(tool_key, src, work_dest, work_base, strategy, resolutions, plan)?;
+        // Someone else's directory is only taken over once nothing in it is left unresolved
+        let adopt = has_base || !outcome.conflicts.iter().any(|c| c.resolution == Resolution::Unresolved);
+        let dest_path = dest.to_string_lossy().to_string();
+        conflicts.extend(outcome.conflicts.into_iter().map(|c| SyncConflict { dest_path: dest_path.clone(), ..c }));
+        return Ok(outcome.applied && adopt);
     }
 
-    Ok(written_paths)
+    if replaces && work_dest != dest {
+        plan.record(PlannedOp::RemoveDir { path: dest.to_string_lossy().to_string() });
+    }
+    plan.copy_dir(src, work_dest)?;
+    if !plan.dry_run {
+        sync_merge::snapshot_base(src, work_base)?;
+    }
+    Ok(true)
 }
 
-/// Collect a skill from any Agent/Project-level directory into the Hub (`~/.xskill/skills/`).
-///
-/// The skill directory at `skill_dir` is copied into the Hub. If a skill with the
-/// same name already exists in the Hub, it is overwritten.
-#[tauri::command]
-pub fn skill_collect_to_hub(skill_dir: String) -> Result<String, String> {
-    let src = PathBuf::from(&skill_dir);
-    if !src.exists() || !src.is_dir() {
-        return Err(format!("Skill directory does not exist or is not a directory: {}", skill_dir));
+impl Xskill {
+    /// Hub skills picked by `selection`, sorted by name. Only the Hub is a source: skills
+    /// that exist in just one agent directory are not pushed to the others.
+    ///
+    /// A plain name (no `*`, `?` or `[`) that matches nothing is a `SkillNotFound` error.
+    pub fn select_skills(&self, selection: &SyncSelection) -> Result<Vec<LocalSkill>, XskillError> {
+        let (selected, mut unmatched) = self.match_skills(selection)?;
+        match unmatched.is_empty() {
+            true => Ok(selected),
+            false => Err(XskillError::SkillNotFound { skill: unmatched.remove(0) }),
+        }
+    }
+
+    /// Like [`Xskill::select_skills`], but returns the plain names that matched nothing
+    /// instead of failing on them.
+    pub fn match_skills(&self, selection: &SyncSelection) -> Result<(Vec<LocalSkill>, Vec<String>), XskillError> {
+        let include = compile_patterns(&selection.skills)?;
+        let exclude = compile_patterns(&selection.exclude)?;
+        let select_all = selection.skills.is_empty() && selection.tags.is_empty();
+        let hub_skills = self.hub_skills();
+
+        let unmatched = selection
+            .skills
+            .iter()
+            .zip(&include)
+            .filter(|(raw, pattern)| {
+                !raw.contains(['*', '?', '[']) && !hub_skills.iter().any(|s| matches_any(std::slice::from_ref(pattern), s))
+            })
+            .map(|(raw, _)| raw.clone())
+            .collect();
+
+        let mut selected: Vec<LocalSkill> = hub_skills
+            .into_iter()
+            .filter(|s| select_all || matches_any(&include, s) || s.tags.iter().any(|t| selection.tags.contains(t)))
+            .filter(|s| !matches_any(&exclude, s))
+            .collect();
+        selected.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok((selected, unmatched))
     }
 
-    let skill_name = src.file_name()
-        .and_then(|n| n.to_str())
-        .ok_or_else(|| format!("Invalid skill directory path: {}", skill_dir))?
-        .to_string();
+    /// What `agent` takes a synced skill as (a directory, unless the registry says otherwise).
+    pub fn agent_format(&self, agent: &str) -> SkillFormat {
+        self.registry().get(agent).map(|a| a.format).unwrap_or_default()
+    }
 
-    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
-    let hub_dir = home.join(CENTRAL_SKILLS_DIR).join(&skill_name);
-    if let (Ok(s), Ok(h)) = (fs::canonicalize(&src), fs::canonicalize(&hub_dir)) {
-        if s == h {
-            return Ok(hub_dir.to_string_lossy().to_string());
+    /// Where `sync_skill` puts the Hub skill directory named `skill_dir` for `agent`: a
+    /// directory of the same name, or the file the agent's renderer generates.
+    pub fn sync_dest(&self, agent: &str, skill_dir: &str) -> Result<PathBuf, XskillError> {
+        let skills_dir = self.agent_skills_dir(agent)?;
+        Ok(match self.agent_format(agent).renderer() {
+            Some(renderer) => skills_dir.join(renderer.file_name(skill_dir)),
+            None => skills_dir.join(skill_dir),
+        })
+    }
+
+    /// Agents a bulk sync writes to: the ones named in `selection`, or every installed agent.
+    pub fn select_agents(&self, selection: &SyncSelection) -> Result<Vec<String>, XskillError> {
+        if selection.agents.is_empty() {
+            return Ok(self.tools().into_iter().filter(|t| t.installed).map(|t| t.key).collect());
         }
+        for agent in &selection.agents {
+            self.agent_skills_dir(agent)?;
+        }
+        Ok(selection.agents.clone())
     }
 
-    crate::utils::copy_dir_all(&src, &hub_dir).map_err(|e| e.to_string())?;
+    /// Sync a skill directory to one or more agents' global skills directories.
+    ///
+    /// Agents with a rendered [`SkillFormat`] get a file generated from the skill instead of
+    /// the directory; the Hub copy stays the one that is edited.
+    ///
+    /// When an agent copy was edited since the last sync, `options.on_conflict` decides
+    /// what happens to each edited file.
+    ///
+    /// Every agent is staged next to its destination and swapped in by one journaled
+    /// transaction: if any agent fails, all of them are left as they were.
+    ///
+    /// With `options.dry_run`, nothing is written; `operations` lists what a real sync would do.
+    pub fn sync_skill(&self, src: &Path, agents: &[String], options: &SyncOptions) -> Result<SyncReport, XskillError> {
+        if !src.exists() {
+            return Err(XskillError::path_not_found(src));
+        }
+        self.check_sync_allowed(src)?;
+
+        let skill_name = src.file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| XskillError::invalid(format!("Invalid skill directory path: {}", src.display())))?
+            .to_string();
+
+        // Read before anything is written, so a broken ledger stops the sync instead of
+        // leaving agent copies nobody tracks
+        let mut ledger = SyncLedger::load_from_home(self.home())?;
+        let mut plan = Plan::new(options.dry_run);
+        let mut report = SyncReport { dry_run: plan.dry_run, ..Default::default() };
+        let mut tx = if plan.dry_run { None } else { Some(Transaction::begin_in(&journal_dir_in(self.home()))?) };
+        let mut failures: Vec<XskillError> = Vec::new();
+        let mut landed: Vec<(String, PathBuf, &str)> = Vec::new();
+
+        for tool_key in agents {
+            let dest = match self.sync_dest(tool_key, &skill_name) {
+                Ok(dest) => dest,
+                Err(e) => {
+                    failures.push(e);
+                    continue;
+                }
+            };
+            let rendered = match render::render_skill(self.agent_format(tool_key), src) {
+                Ok(rendered) => rendered,
+                Err(e) => {
+                    failures.push(e.context(tool_key));
+                    continue;
+                }
+            };
+            if let (None, Ok(s), Ok(d)) = (&rendered, fs::canonicalize(src), fs::canonicalize(&dest)) {
+                if s == d {
+                    report.written.push(dest.to_string_lossy().to_string());
+                    continue;
+                }
+            }
+            let mode = match (&rendered, options.link) {
+                (Some(_), _) => "render",
+                (None, true) => "link",
+                (None, false) => "copy",
+            };
+
+            let base = sync_merge::base_dir_for(self.home(), &dest);
+            let rendered = rendered.as_ref().map(|(file, content)| (file.as_str(), content.as_bytes()));
+            let staged = stage_target(tool_key, src, rendered, &dest, &base, tx.as_mut(), options, &mut plan, &mut report.conflicts);
+
+            match staged {
+                Ok(true) => landed.push((tool_key.clone(), dest, mode)),
+                Ok(false) => {}
+                Err(e) => failures.push(e.context(tool_key)),
+            }
+        }
+
+        if let Some(tx) = tx {
+            if !failures.is_empty() {
+                if let Err(e) = tx.rollback() {
+                    failures.push(e.context("Rollback failed"));
+                }
+                return Err(combine_failures(failures, " (no agent was changed)"));
+            }
+            tx.commit()?;
+        } else if !failures.is_empty() {
+            return Err(combine_failures(failures, ""));
+        }
+
+        for (tool_key, dest, mode_str) in &landed {
+            report.written.push(dest.to_string_lossy().to_string());
+            if !plan.dry_run {
+                match sync_ledger::entry_for(&skill_name, tool_key, mode_str, src, dest) {
+                    Ok(entry) => ledger.record(entry),
+                    Err(e) => report.errors.push(format!("Failed to fingerprint {}: {}", dest.display(), e)),
+                }
+            }
+            // Copies an earlier version left where this agent used to keep its skills
+            match self.retire_legacy_copies(tool_key, src, dest, &mut ledger, &mut plan) {
+                Ok(kept) => report.errors.extend(kept.into_iter().map(|old| {
+                    format!("Kept {}: it was edited since an earlier sync; move your changes to {} and delete it", old, dest.display())
+                })),
+                Err(e) => report.errors.push(format!("Failed to clean up the old copy for {}: {}", tool_key, e)),
+            }
+            // Try to inject config for known tools
+            if tool_key == "claude_code" || tool_key == "claude_desktop" {
+                 if let Err(e) = update_claude_desktop_config(self, &skill_name, dest, &mut plan) {
+                     report.errors.push(format!("Claude Config Error: {}", e));
+                 }
+            }
+        }
 
-    // Check if the hub directory is empty (or only contains ignored files/dirs, effectively empty)
-    if let Ok(entries) = fs::read_dir(&hub_dir) {
-        if entries.count() == 0 {
-            let _ = fs::remove_dir_all(&hub_dir);
-            return Err(format!("Imported skill directory is empty. Check if the source directory contains valid content: {:?}", src));
+        // Links keep the whole directory; copies and rendered rules leave symlinks out
+        if landed.iter().any(|(_, _, mode)| *mode != "link") {
+            report.skipped_links = crate::utils::skipped_links(src)?;
+        }
+        report.operations = plan.into_ops();
+        if !report.dry_run && !landed.is_empty() {
+            if let Err(e) = ledger.save_to_home(self.home()) {
+                report.errors.push(format!("Failed to save sync ledger: {}", e));
+            }
         }
+
+        Ok(report)
     }
 
+    /// Copy a skill from any agent or project directory into the Hub, overwriting a Hub
+    /// skill of the same name. Returns the Hub path.
+    pub fn collect_to_hub(&self, src: &Path) -> Result<PathBuf, XskillError> {
+        if !src.exists() || !src.is_dir() {
+            return Err(XskillError::path_not_found(src));
+        }
+
+        let skill_name = src.file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| XskillError::invalid(format!("Invalid skill directory path: {}", src.display())))?;
+
+        let hub_dir = self.hub().join(skill_name);
+        if let (Ok(s), Ok(h)) = (fs::canonicalize(src), fs::canonicalize(&hub_dir)) {
+            if s == h {
+                return Ok(hub_dir);
+            }
+        }
+
+        crate::utils::copy_dir_all(&src.to_path_buf(), &hub_dir)?;
+
+        // Check if the hub directory is empty (or only contains ignored files/dirs, effectively empty)
+        if let Ok(entries) = fs::read_dir(&hub_dir) {
+            if entries.count() == 0 {
+                let _ = fs::remove_dir_all(&hub_dir);
+                return Err(XskillError::invalid(format!("Imported skill directory is empty. Check if the source directory contains valid content: {:?}", src)));
+            }
+        }
+        crate::skill_manager::write_skill_meta(&hub_dir, &crate::skill_manager::SkillMeta::local(&hub_dir))?;
+
+        Ok(hub_dir)
+    }
+}
+
+/// Sync a skill directory to one or more target tool skill directories.
+///
+/// `mode` controls how the skill is delivered:
+/// - `"copy"` (default): copy the directory recursively
+/// - `"link"`: create a symlink pointing back to `skill_dir`
+///
+/// When an agent copy was edited since the last sync, `on_conflict`
+/// (`merge`, `keep-theirs`, `keep-ours` or `abort`; default `merge`) decides what
+/// happens to each edited file. `resolutions` overrides it per relative file path.
+#[tauri::command]
+pub fn sync_skill(
+    skill_dir: String,
+    target_tool_keys: Vec<String>,
+    mode: Option<String>,
+    on_conflict: Option<String>,
+    resolutions: Option<HashMap<String, String>>,
+    dry_run: Option<bool>,
+) -> Result<SyncReport, XskillError> {
+    let options = SyncOptions {
+        link: mode.as_deref() == Some("link"),
+        on_conflict: match on_conflict.as_deref() {
+            Some(s) => s.parse().map_err(XskillError::invalid)?,
+            None => ConflictStrategy::default(),
+        },
+        resolutions: resolutions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(file, s)| s.parse().map(|s| (file, s)))
+            .collect::<Result<_, String>>()
+            .map_err(XskillError::invalid)?,
+        dry_run: dry_run.unwrap_or(false),
+    };
+    Xskill::load()?.sync_skill(Path::new(&skill_dir), &target_tool_keys, &options)
+}
+
+/// A single failure keeps its kind; several are reported together.
+fn combine_failures(mut failures: Vec<XskillError>, suffix: &str) -> XskillError {
+    if failures.len() == 1 {
+        return failures.remove(0);
+    }
+    let messages: Vec<String> = failures.iter().map(|e| e.to_string()).collect();
+    format!("{}{}", messages.join("; "), suffix).into()
+}
+
+/// Collect a skill from any Agent/Project-level directory into the Hub (`~/.xskill/skills/`).
+///
+/// The skill directory at `skill_dir` is copied into the Hub. If a skill with the
+/// same name already exists in the Hub, it is overwritten.
+#[tauri::command]
+pub fn skill_collect_to_hub(skill_dir: String) -> Result<String, XskillError> {
+    let hub_dir = Xskill::load()?.collect_to_hub(Path::new(&skill_dir))?;
     Ok(hub_dir.to_string_lossy().to_string())
 }
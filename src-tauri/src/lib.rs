@@ -1,22 +1,47 @@
 pub mod utils;
+pub mod agent_registry;
+pub mod archive;
+pub mod audit;
 pub mod integration_tests;
 pub mod commands;
 pub mod config;
 pub mod config_manager;
+pub mod error;
 pub mod crawler;
+pub mod credentials;
 pub mod feed_parser;
+pub mod forge;
+#[cfg(test)]
+pub mod fake_git;
+pub mod git_backend;
 pub mod git_manager;
 pub mod ide_sync;
+pub mod linter;
 pub mod scaffold;
+pub mod skill_lock;
 pub mod skill_manager;
 pub mod store;
 pub mod fingerprint;
+pub mod frontmatter;
 pub mod onboarding;
+pub mod pack;
+pub mod plan;
+pub mod profiles;
+pub mod prune;
+pub mod render;
+pub mod repo_cache;
+pub mod repo_discovery;
 pub mod scanner;
 pub mod github;
+pub mod hub_manifest;
 pub mod suite_manager;
 pub mod suite_applier;
+pub mod sync_ledger;
+pub mod sync_merge;
+pub mod sync_txn;
 pub mod test_logger;
+pub mod trust;
+pub mod xskill;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -36,6 +61,22 @@ pub fn run() {
             git_manager::clone_skill,
             git_manager::update_skill,
             git_manager::install_skill_from_url,
+            git_manager::install_skills_from_lock,
+            git_manager::check_updates,
+            git_manager::update_all_skills,
+            repo_discovery::discover_repo_skills,
+            repo_discovery::install_repo_skills,
+            skill_lock::get_skills_lock,
+            hub_manifest::export_hub_manifest,
+            hub_manifest::restore_hub_manifest,
+            pack::pack_hub_skill,
+            pack::verify_package,
+            audit::audit_skills,
+            trust::list_trusted_publishers,
+            trust::add_trusted_publisher,
+            trust::remove_trusted_publisher,
+            trust::get_sync_policy,
+            trust::set_sync_policy,
             store::load_skills,
             store::save_skills,
             store::load_feeds,
@@ -46,15 +87,36 @@ pub fn run() {
             config_manager::save_skill_config,
             scanner::scan_workspace,
             github::fetch_github_file,
+            forge::list_forges,
+            forge::add_forge,
+            credentials::set_forge_token,
+            credentials::delete_forge_token,
+            credentials::list_forge_tokens,
             suite_manager::load_suites,
             suite_manager::save_suites,
             suite_applier::apply_suite,
             suite_applier::apply_suite_to_agent,
+            profiles::load_profiles,
+            profiles::save_profiles,
+            profiles::reconcile_profiles,
+            prune::prune_orphans,
+            prune::delete_skill_everywhere,
             ide_sync::skill_collect_to_hub,
             utils::open_folder,
+            linter::lint_skills,
+            sync_ledger::get_sync_status,
         ])
         .setup(|_app| {
             config::init_config();
+            // Finish or undo syncs interrupted by a crash before anything reads agent dirs
+            match sync_txn::recover(sync_txn::RecoverMode::Auto) {
+                Ok(recoveries) => {
+                    for r in recoveries {
+                        println!("Recovered interrupted sync {}: {}", r.id, r.outcome);
+                    }
+                }
+                Err(e) => eprintln!("Failed to recover interrupted syncs: {}", e),
+            }
             Ok(())
         })
         .run(tauri::generate_context!())
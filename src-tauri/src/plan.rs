@@ -0,0 +1,220 @@
+use crate::error::XskillError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A filesystem change made (or, in dry-run mode, intended) by sync, suite apply or import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PlannedOp {
+    CreateDir { path: String },
+    /// Remove an existing directory, file or symlink.
+    RemoveDir { path: String },
+    CopyFile { from: String, to: String },
+    /// Write generated or merged content.
+    WriteFile { path: String, overwrite: bool },
+    DeleteFile { path: String },
+    CreateSymlink { path: String, target: String, replaces: bool },
+    MoveDir { from: String, to: String },
+    WriteAgentsMd { path: String, overwrite: bool },
+    PatchClaudeConfig { path: String, server: String },
+}
+
+/// Records every filesystem operation and, unless `dry_run` is set, performs it.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub dry_run: bool,
+    pub ops: Vec<PlannedOp>,
+}
+
+fn display(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+impl Plan {
+    pub fn new(dry_run: bool) -> Self {
+        Self { dry_run, ops: Vec::new() }
+    }
+
+    pub fn into_ops(self) -> Vec<PlannedOp> {
+        self.ops
+    }
+
+    pub fn record(&mut self, op: PlannedOp) {
+        self.ops.push(op);
+    }
+
+    /// Report operations recorded since `start` against `to` instead of `from`, so work done
+    /// in a staging directory shows up under the destination it is renamed to.
+    pub fn retarget(&mut self, start: usize, from: &Path, to: &Path) {
+        let (from, to) = (display(from), display(to));
+        for op in self.ops.iter_mut().skip(start) {
+            let path = match op {
+                PlannedOp::CreateDir { path }
+                | PlannedOp::RemoveDir { path }
+                | PlannedOp::WriteFile { path, .. }
+                | PlannedOp::DeleteFile { path }
+                | PlannedOp::CreateSymlink { path, .. }
+                | PlannedOp::WriteAgentsMd { path, .. }
+                | PlannedOp::PatchClaudeConfig { path, .. } => path,
+                PlannedOp::CopyFile { to: dest, .. } | PlannedOp::MoveDir { to: dest, .. } => dest,
+            };
+            if let Some(rest) = path.strip_prefix(&from) {
+                *path = format!("{}{}", to, rest);
+            }
+        }
+    }
+
+    pub fn create_dir(&mut self, path: &Path) -> Result<(), XskillError> {
+        if path.exists() {
+            return Ok(());
+        }
+        self.record(PlannedOp::CreateDir { path: display(path) });
+        if !self.dry_run {
+            fs::create_dir_all(path).map_err(|e| XskillError::io(path, e))?;
+        }
+        Ok(())
+    }
+
+    /// Replace `to` with a copy of `from`, following `utils::copy_dir_all` semantics.
+    pub fn copy_dir(&mut self, from: &Path, to: &Path) -> Result<(), XskillError> {
+        if to.exists() || to.is_symlink() {
+            self.record(PlannedOp::RemoveDir { path: display(to) });
+        }
+        self.record(PlannedOp::CreateDir { path: display(to) });
+        for (rel, file) in crate::utils::list_files(from)? {
+            self.record(PlannedOp::CopyFile { from: display(&file), to: display(&to.join(rel)) });
+        }
+        if !self.dry_run {
+            crate::utils::copy_dir_all(&from.to_path_buf(), &to.to_path_buf())?;
+        }
+        Ok(())
+    }
+
+    pub fn symlink_dir(&mut self, target: &Path, link: &Path) -> Result<(), XskillError> {
+        self.record(PlannedOp::CreateSymlink {
+            path: display(link),
+            target: display(target),
+            replaces: link.exists() || link.is_symlink(),
+        });
+        if !self.dry_run {
+            crate::utils::symlink_dir(&target.to_path_buf(), &link.to_path_buf())?;
+        }
+        Ok(())
+    }
+
+    pub fn write_file(&mut self, path: &Path, content: &[u8]) -> Result<(), XskillError> {
+        self.record(PlannedOp::WriteFile { path: display(path), overwrite: path.exists() });
+        if !self.dry_run {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+            }
+            fs::write(path, content).map_err(|e| XskillError::io(path, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_file(&mut self, path: &Path) -> Result<(), XskillError> {
+        if !path.exists() {
+            return Ok(());
+        }
+        self.record(PlannedOp::DeleteFile { path: display(path) });
+        if !self.dry_run {
+            fs::remove_file(path).map_err(|e| XskillError::io(path, e))?;
+        }
+        Ok(())
+    }
+
+    /// Remove a directory, file or symlink (a symlinked directory's target is left alone).
+    pub fn remove_dir(&mut self, path: &Path) -> Result<(), XskillError> {
+        if !path.exists() && !path.is_symlink() {
+            return Ok(());
+        }
+        self.record(PlannedOp::RemoveDir { path: display(path) });
+        if !self.dry_run {
+            let removed = if path.is_symlink() {
+                // Directory links are removed with remove_dir on Windows
+                fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+            } else if path.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+            removed.map_err(|e| XskillError::io(path, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn move_dir(&mut self, from: &Path, to: &Path) -> Result<(), XskillError> {
+        self.record(PlannedOp::MoveDir { from: display(from), to: display(to) });
+        if !self.dry_run {
+            fs::rename(from, to).map_err(|e| XskillError::io(from, e))?;
+        }
+        Ok(())
+    }
+
+    pub fn write_agents_md(&mut self, path: &Path, content: &str) -> Result<(), XskillError> {
+        self.record(PlannedOp::WriteAgentsMd { path: display(path), overwrite: path.exists() });
+        if !self.dry_run {
+            fs::write(path, content).map_err(|e| XskillError::io(path, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PlannedOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlannedOp::CreateDir { path } => write!(f, "create dir   {}", path),
+            PlannedOp::RemoveDir { path } => write!(f, "remove       {}", path),
+            PlannedOp::CopyFile { to, .. } => write!(f, "copy         {}", to),
+            PlannedOp::WriteFile { path, overwrite } => {
+                write!(f, "{} {}", if *overwrite { "overwrite   " } else { "write       " }, path)
+            }
+            PlannedOp::DeleteFile { path } => write!(f, "delete       {}", path),
+            PlannedOp::CreateSymlink { path, target, replaces } => write!(
+                f,
+                "{} {} -> {}",
+                if *replaces { "replace link" } else { "link        " },
+                path,
+                target
+            ),
+            PlannedOp::MoveDir { from, to } => write!(f, "move         {} -> {}", from, to),
+            PlannedOp::WriteAgentsMd { path, overwrite } => {
+                write!(f, "{} {}", if *overwrite { "overwrite   " } else { "write       " }, path)
+            }
+            PlannedOp::PatchClaudeConfig { path, server } => write!(f, "patch        {} (mcpServers.{})", path, server),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dry_run_records_without_touching_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("scripts")).unwrap();
+        fs::write(src.join("SKILL.md"), "skill").unwrap();
+        fs::write(src.join("scripts/run.sh"), "echo").unwrap();
+        let dest = temp_dir.path().join("dest");
+
+        let mut plan = Plan::new(true);
+        plan.copy_dir(&src, &dest).unwrap();
+        plan.write_agents_md(&temp_dir.path().join("AGENTS.md"), "rules").unwrap();
+
+        assert!(!dest.exists());
+        assert!(!temp_dir.path().join("AGENTS.md").exists());
+        let copies = plan.ops.iter().filter(|op| matches!(op, PlannedOp::CopyFile { .. })).count();
+        assert_eq!(copies, 2);
+        assert!(matches!(plan.ops.last(), Some(PlannedOp::WriteAgentsMd { overwrite: false, .. })));
+
+        let mut real = Plan::new(false);
+        real.copy_dir(&src, &dest).unwrap();
+        assert!(dest.join("scripts/run.sh").exists());
+        assert_eq!(real.ops.len(), plan.ops.len() - 1);
+    }
+}
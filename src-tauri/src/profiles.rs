@@ -0,0 +1,359 @@
+use crate::error::XskillError;
+use crate::ide_sync::{SyncOptions, SyncSelection};
+use crate::plan::{Plan, PlannedOp};
+use crate::skill_manager::LocalSkill;
+use crate::sync_ledger::{self, DriftState, SyncLedger};
+use crate::sync_merge::SyncConflict;
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Which Hub skills each agent should have, kept in `~/.xskill/profiles.toml`.
+pub const PROFILES_FILE: &str = ".xskill/profiles.toml";
+
+/// Every agent gets the `[default]` profile; an `[agents.<key>]` table adds to it, or
+/// replaces it with `inherit = false`.
+///
+/// ```toml
+/// [default]
+/// skills = ["review-*", "deploy"]
+///
+/// [agents.cursor]
+/// skills = ["cursor-rules"]
+/// exclude = ["deploy"]
+///
+/// [agents.codex]
+/// inherit = false
+/// tags = ["cli"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profiles {
+    #[serde(default)]
+    pub default: Profile,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub agents: BTreeMap<String, Profile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    /// Skill names or glob patterns.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<String>,
+    /// Assign every skill carrying one of these tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Names or glob patterns never assigned, even when matched above.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// Start from the default profile.
+    #[serde(default = "inherit_default", skip_serializing_if = "is_inherited")]
+    pub inherit: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self { skills: Vec::new(), tags: Vec::new(), exclude: Vec::new(), inherit: true }
+    }
+}
+
+fn inherit_default() -> bool {
+    true
+}
+
+fn is_inherited(inherit: &bool) -> bool {
+    *inherit
+}
+
+fn profiles_path(home: &Path) -> PathBuf {
+    home.join(PROFILES_FILE)
+}
+
+impl Profiles {
+    pub fn load_from_home(home: &Path) -> Result<Self, XskillError> {
+        let path = profiles_path(home);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| XskillError::io(&path, e))?;
+        toml::from_str(&content).map_err(|e| XskillError::Config { path, message: e.to_string() })
+    }
+
+    pub fn save_to_home(&self, home: &Path) -> Result<(), XskillError> {
+        let path = profiles_path(home);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| XskillError::io(&path, e))
+    }
+
+    /// The profile `agent` ends up with: its own table on top of the default one.
+    pub fn resolve(&self, agent: &str) -> Profile {
+        let Some(own) = self.agents.get(agent) else {
+            return self.default.clone();
+        };
+        if !own.inherit {
+            return own.clone();
+        }
+        let join = |base: &[String], extra: &[String]| base.iter().chain(extra).cloned().collect::<Vec<_>>();
+        Profile {
+            skills: join(&self.default.skills, &own.skills),
+            tags: join(&self.default.tags, &own.tags),
+            exclude: join(&self.default.exclude, &own.exclude),
+            inherit: true,
+        }
+    }
+
+    /// Why unassigned copies in `agent` must not be pruned, if they must not. Only agents
+    /// with their own `[agents.<key>]` table are pruned, and one that assigns nothing only
+    /// when the table says so (`inherit = false` with no skills or tags), so a typo or an
+    /// emptied Hub never wipes an agent.
+    pub fn prune_refusal(&self, agent: &str, assigns_nothing: bool) -> Option<String> {
+        let Some(own) = self.agents.get(agent) else {
+            return Some(format!("no [agents.{}] profile, so nothing was pruned", agent));
+        };
+        let declared_empty = !own.inherit && own.skills.is_empty() && own.tags.is_empty();
+        if assigns_nothing && !declared_empty {
+            return Some("the profile matches no Hub skill, so nothing was pruned".to_string());
+        }
+        None
+    }
+
+    /// Profile table for `agent`, or `[default]` for `"default"`, created if missing.
+    pub fn entry(&mut self, agent: &str) -> &mut Profile {
+        if agent == "default" {
+            return &mut self.default;
+        }
+        self.agents.entry(agent.to_string()).or_default()
+    }
+}
+
+/// What reconciling one agent against its profile did (or, in dry-run mode, would do).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    pub agent: String,
+    /// Hub skills the profile assigns, all of which were synced.
+    pub assigned: Vec<String>,
+    /// Assigned skills the agent did not have before.
+    pub added: Vec<String>,
+    /// Synced copies removed because the profile no longer assigns them or the Hub no
+    /// longer has them.
+    pub pruned: Vec<String>,
+    /// Synced copies no longer assigned (or no longer in the Hub) that were left in place,
+    /// because pruning was not requested or they were edited in the agent directory.
+    pub unassigned: Vec<String>,
+    /// Plain names in the profile that match no Hub skill.
+    pub unknown: Vec<String>,
+    /// Why pruning was requested but not done for this agent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prune_skipped: Option<String>,
+    pub conflicts: Vec<SyncConflict>,
+    pub errors: Vec<String>,
+    pub operations: Vec<PlannedOp>,
+}
+
+impl Xskill {
+    pub fn load_profiles(&self) -> Result<Profiles, XskillError> {
+        Profiles::load_from_home(self.home())
+    }
+
+    pub fn save_profiles(&self, profiles: &Profiles) -> Result<(), XskillError> {
+        profiles.save_to_home(self.home())
+    }
+
+    /// Hub skills assigned to `agent`, and the plain names in its profile that match none.
+    /// A profile without skills or tags assigns nothing.
+    pub fn assigned_skills(&self, profiles: &Profiles, agent: &str) -> Result<(Vec<LocalSkill>, Vec<String>), XskillError> {
+        let profile = profiles.resolve(agent);
+        if profile.skills.is_empty() && profile.tags.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        let selection = SyncSelection { skills: profile.skills, tags: profile.tags, exclude: profile.exclude, agents: Vec::new() };
+        self.match_skills(&selection)
+    }
+
+    /// Bring each agent in line with its profile: sync every assigned Hub skill and, with
+    /// `prune`, remove copies xskill synced earlier whose skill is no longer assigned or was
+    /// deleted from the Hub.
+    ///
+    /// Only copies recorded in the sync ledger are ever pruned, and not when they were
+    /// edited in the agent directory or the agent's profile does not allow it (see
+    /// [`Profiles::prune_refusal`]). Agents default to every installed agent plus every
+    /// agent with its own profile. Fails if no profiles file exists, so that pruning never
+    /// runs against an empty allowlist.
+    pub fn reconcile_profiles(&self, agents: &[String], prune: bool, options: &SyncOptions) -> Result<Vec<ReconcileReport>, XskillError> {
+        let path = profiles_path(self.home());
+        if !path.exists() {
+            return Err(XskillError::path_not_found(&path));
+        }
+        let profiles = self.load_profiles()?;
+        let agents: Vec<String> = if agents.is_empty() {
+            let mut all: Vec<String> = self.tools().into_iter().filter(|t| t.installed).map(|t| t.key).collect();
+            all.extend(profiles.agents.keys().filter(|k| !all.contains(k)).cloned().collect::<Vec<_>>());
+            all
+        } else {
+            agents.to_vec()
+        };
+
+        let mut reports = Vec::new();
+        for agent in &agents {
+            let skills_dir = self.agent_skills_dir(agent)?;
+            let (assigned, unknown) = self.assigned_skills(&profiles, agent)?;
+            let mut report = ReconcileReport { agent: agent.clone(), unknown, ..Default::default() };
+
+            let mut assigned_dirs = HashSet::new();
+            for skill in &assigned {
+                let src = Path::new(&skill.path);
+                let dir_name = src.file_name().unwrap_or_default().to_string_lossy().to_string();
+                let dest = self.sync_dest(agent, &dir_name)?;
+                let is_new = !dest.exists() && !dest.is_symlink();
+                match self.sync_skill(src, std::slice::from_ref(agent), options) {
+                    Ok(sync) => {
+                        report.conflicts.extend(sync.conflicts);
+                        report.errors.extend(sync.errors);
+                        report.operations.extend(sync.operations);
+                        if is_new {
+                            report.added.push(skill.name.clone());
+                        }
+                    }
+                    Err(e) => report.errors.push(format!("{}: {}", skill.name, e)),
+                }
+                report.assigned.push(skill.name.clone());
+                assigned_dirs.insert(dir_name);
+            }
+
+            let prune = prune && {
+                report.prune_skipped = profiles.prune_refusal(agent, assigned.is_empty());
+                report.prune_skipped.is_none()
+            };
+            self.prune_unassigned(&skills_dir, &assigned_dirs, prune, options.dry_run, &mut report)?;
+            if prune {
+                let orphans = self.prune_orphans(std::slice::from_ref(agent), options.dry_run)?;
+                let name = |path: &String| Path::new(path).file_name().unwrap_or_default().to_string_lossy().to_string();
+                report.pruned.extend(orphans.removed.iter().map(name));
+                report.unassigned.extend(orphans.kept.iter().map(name));
+                report.operations.extend(orphans.operations);
+            }
+            reports.push(report);
+        }
+        Ok(reports)
+    }
+
+    /// Ledger entries for `skills_dir` whose Hub skill still exists but is not in
+    /// `assigned_dirs`: removed with `prune`, otherwise listed as unassigned.
+    fn prune_unassigned(&self, skills_dir: &Path, assigned_dirs: &HashSet<String>, prune: bool, dry_run: bool, report: &mut ReconcileReport) -> Result<(), XskillError> {
+        let mut ledger = SyncLedger::load_from_home(self.home())?;
+        let candidates: Vec<_> = ledger
+            .entries
+            .iter()
+            .filter(|e| e.agent == report.agent && Path::new(&e.dest_path).parent() == Some(skills_dir))
+            .filter(|e| Path::new(&e.source_path).starts_with(self.hub()) && Path::new(&e.source_path).exists())
+            .filter(|e| !assigned_dirs.contains(&e.skill))
+            .cloned()
+            .collect();
+        if candidates.is_empty() {
+            return Ok(());
+        }
+
+        let mut plan = Plan::new(dry_run);
+        for entry in candidates {
+            let state = sync_ledger::classify(&entry);
+            let edited = matches!(state, DriftState::LocallyModified | DriftState::Diverged);
+            if !prune || edited {
+                if state != DriftState::Missing {
+                    report.unassigned.push(entry.skill);
+                }
+                continue;
+            }
+            self.remove_synced_copy(&entry, &mut ledger, &mut plan)?;
+            if state != DriftState::Missing {
+                report.pruned.push(entry.skill);
+            }
+        }
+        report.operations.extend(plan.into_ops());
+        if !dry_run {
+            ledger.save_to_home(self.home())?;
+        }
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn load_profiles() -> Result<Profiles, XskillError> {
+    Xskill::load()?.load_profiles()
+}
+
+#[tauri::command]
+pub fn save_profiles(profiles: Profiles) -> Result<(), XskillError> {
+    Xskill::load()?.save_profiles(&profiles)
+}
+
+/// Sync every agent (or just `agents`) to its profile, pruning unassigned copies with `prune`.
+#[tauri::command]
+pub fn reconcile_profiles(
+    agents: Option<Vec<String>>,
+    prune: Option<bool>,
+    mode: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<ReconcileReport>, XskillError> {
+    let options = SyncOptions {
+        link: mode.as_deref() == Some("link"),
+        dry_run: dry_run.unwrap_or(false),
+        ..Default::default()
+    };
+    Xskill::load()?.reconcile_profiles(&agents.unwrap_or_default(), prune.unwrap_or(false), &options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_inherits_the_default_profile() {
+        let profiles: Profiles = toml::from_str(
+            r#"
+[default]
+skills = ["review-*"]
+exclude = ["review-legacy"]
+
+[agents.cursor]
+skills = ["cursor-rules"]
+
+[agents.codex]
+inherit = false
+tags = ["cli"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(profiles.resolve("claude_code"), profiles.default);
+        let cursor = profiles.resolve("cursor");
+        assert_eq!(cursor.skills, vec!["review-*", "cursor-rules"]);
+        assert_eq!(cursor.exclude, vec!["review-legacy"]);
+        let codex = profiles.resolve("codex");
+        assert!(codex.skills.is_empty());
+        assert_eq!(codex.tags, vec!["cli"]);
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path();
+        assert_eq!(Profiles::load_from_home(home).unwrap(), Profiles::default());
+
+        let mut profiles = Profiles::default();
+        profiles.entry("default").skills.push("deploy".to_string());
+        profiles.entry("cursor").inherit = false;
+        profiles.save_to_home(home).unwrap();
+
+        let content = fs::read_to_string(profiles_path(home)).unwrap();
+        assert!(content.contains("inherit = false"));
+        assert_eq!(Profiles::load_from_home(home).unwrap(), profiles);
+
+        fs::write(profiles_path(home), "[default]\nskills = 3\n").unwrap();
+        assert_eq!(Profiles::load_from_home(home).unwrap_err().code(), "config");
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::XskillError;
+use crate::fingerprint::calculate_dir_hash;
+use crate::plan::{Plan, PlannedOp};
+use crate::sync_ledger::{LedgerEntry, SyncLedger};
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Agent copies removed (or, in dry-run mode, to be removed) because their Hub skill is gone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PruneReport {
+    /// Agent paths of the removed copies and links.
+    pub removed: Vec<String>,
+    /// Copies edited in the agent directory since they were synced, left in place.
+    pub kept: Vec<String>,
+    pub operations: Vec<PlannedOp>,
+    pub dry_run: bool,
+}
+
+/// Whether the agent copy was changed after xskill wrote it, so removing it would lose work.
+fn edited_since_sync(entry: &LedgerEntry) -> bool {
+    let dest = Path::new(&entry.dest_path);
+    if entry.mode == "link" {
+        return !dest.is_symlink() || fs::read_link(dest).map(|t| t != Path::new(&entry.source_path)).unwrap_or(true);
+    }
+    calculate_dir_hash(dest).map(|h| h != entry.dest_fingerprint).unwrap_or(true)
+}
+
+impl Xskill {
+    /// Remove a copy xskill synced: the agent directory (or link), its merge base and its
+    /// ledger entry. The caller saves the ledger.
+    pub fn remove_synced_copy(&self, entry: &LedgerEntry, ledger: &mut SyncLedger, plan: &mut Plan) -> Result<(), XskillError> {
+        let dest = Path::new(&entry.dest_path);
+        plan.remove_dir(dest)?;
+        if !plan.dry_run {
+            let _ = fs::remove_dir_all(crate::sync_merge::base_dir_for(self.home(), dest));
+            ledger.remove(&entry.dest_path);
+        }
+        Ok(())
+    }
+
+    /// Remove the copies of `src` that earlier versions synced into `agent`'s legacy
+    /// directories (see [`crate::agent_registry::AgentDef::legacy_subdirs`]), now that `dest`
+    /// holds the skill. Returns the copies kept because they were edited there.
+    pub fn retire_legacy_copies(
+        &self,
+        agent: &str,
+        src: &Path,
+        dest: &Path,
+        ledger: &mut SyncLedger,
+        plan: &mut Plan,
+    ) -> Result<Vec<String>, XskillError> {
+        let Some(name) = src.file_name() else {
+            return Ok(Vec::new());
+        };
+        let mut kept = Vec::new();
+        for old in self.registry().legacy_dirs(agent, self.home()).into_iter().map(|d| d.join(name)) {
+            if old == dest {
+                continue;
+            }
+            let old_path = old.to_string_lossy().to_string();
+            match ledger.find(&old_path).filter(|e| e.agent == agent).cloned() {
+                // Already gone; the ledger is only saved when this is not a dry run
+                Some(_) if !old.exists() && !old.is_symlink() => {
+                    ledger.remove(&old_path);
+                }
+                Some(entry) if edited_since_sync(&entry) => kept.push(old_path),
+                Some(entry) => self.remove_synced_copy(&entry, ledger, plan)?,
+                // A link made before the ledger existed
+                None if old.is_symlink() && fs::read_link(&old).is_ok_and(|t| t == src) => plan.remove_dir(&old)?,
+                None => {}
+            }
+        }
+        Ok(kept)
+    }
+
+    /// Remove every agent copy of a Hub skill that no longer exists, in `agents` (all of
+    /// them when empty).
+    ///
+    /// Only xskill-managed entries are touched: copies recorded in the sync ledger, and links
+    /// into the Hub. Skills authored in an agent directory are never removed, and synced
+    /// copies edited there are kept and reported.
+    pub fn prune_orphans(&self, agents: &[String], dry_run: bool) -> Result<PruneReport, XskillError> {
+        self.prune_where(agents, dry_run, |src| src.starts_with(self.hub()) && !src.exists())
+    }
+
+    /// Delete a Hub skill along with every copy xskill synced from it.
+    ///
+    /// The agent copies go first, so a ledger that cannot be read leaves the Hub skill in
+    /// place rather than copies with no source.
+    pub fn delete_skill_everywhere(&self, path: &Path) -> Result<PruneReport, XskillError> {
+        // Only a direct child of the Hub, so `..` cannot reach outside it
+        if path.parent() != Some(self.hub()) || path.file_name().is_none() {
+            return Err(XskillError::invalid(format!("{} is not a Hub skill", path.display())));
+        }
+        let report = self.prune_where(&[], false, |src| src == path)?;
+        self.delete_skill(path)?;
+        Ok(report)
+    }
+
+    fn prune_where(&self, agents: &[String], dry_run: bool, gone: impl Fn(&Path) -> bool) -> Result<PruneReport, XskillError> {
+        let agents: Vec<String> = if agents.is_empty() {
+            self.registry().agents().iter().map(|a| a.key.clone()).collect()
+        } else {
+            agents.to_vec()
+        };
+        let mut ledger = SyncLedger::load_from_home(self.home())?;
+        let mut plan = Plan::new(dry_run);
+        let mut report = PruneReport { dry_run, ..Default::default() };
+
+        let orphans: Vec<LedgerEntry> = ledger
+            .entries
+            .iter()
+            .filter(|e| agents.contains(&e.agent) && gone(Path::new(&e.source_path)))
+            .cloned()
+            .collect();
+        for entry in &orphans {
+            let dest = Path::new(&entry.dest_path);
+            if !dest.exists() && !dest.is_symlink() {
+                // Already gone from the agent; just forget it
+                if !dry_run {
+                    ledger.remove(&entry.dest_path);
+                }
+            } else if edited_since_sync(entry) {
+                report.kept.push(entry.dest_path.clone());
+            } else {
+                self.remove_synced_copy(entry, &mut ledger, &mut plan)?;
+                report.removed.push(entry.dest_path.clone());
+            }
+        }
+
+        // Links into the Hub that the ledger does not know about, e.g. made before it existed
+        for agent in &agents {
+            let mut dirs = vec![self.agent_skills_dir(agent)?];
+            dirs.extend(self.registry().legacy_dirs(agent, self.home()));
+            for path in dirs.iter().filter_map(|d| fs::read_dir(d).ok()).flat_map(|e| e.flatten()).map(|e| e.path()) {
+                let dest = path.to_string_lossy().to_string();
+                if !path.is_symlink() || ledger.find(&dest).is_some() || orphans.iter().any(|e| e.dest_path == dest) {
+                    continue;
+                }
+                if fs::read_link(&path).is_ok_and(|target| gone(&target)) {
+                    plan.remove_dir(&path)?;
+                    report.removed.push(dest);
+                }
+            }
+        }
+
+        report.operations = plan.into_ops();
+        if !dry_run {
+            ledger.save_to_home(self.home())?;
+        }
+        Ok(report)
+    }
+}
+
+/// Remove agent copies of skills deleted from the Hub. See [`Xskill::prune_orphans`].
+#[tauri::command]
+pub fn prune_orphans(agents: Option<Vec<String>>, dry_run: Option<bool>) -> Result<PruneReport, XskillError> {
+    Xskill::load()?.prune_orphans(&agents.unwrap_or_default(), dry_run.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn delete_skill_everywhere(path: String) -> Result<PruneReport, XskillError> {
+    Xskill::load()?.delete_skill_everywhere(Path::new(&path))
+}
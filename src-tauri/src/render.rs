@@ -0,0 +1,407 @@
+use crate::error::XskillError;
+use crate::frontmatter::{self, SkillManifest};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// How an agent expects to receive a skill. Set per agent with `format` in
+/// `~/.xskill/agents.toml`; the Hub always keeps the `SKILL.md` directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillFormat {
+    /// The Hub's `SKILL.md` directory, copied or linked as is.
+    #[default]
+    SkillDir,
+    /// Cursor rule: `<name>.mdc` with `description`, `globs` and `alwaysApply`.
+    CursorMdc,
+    /// Windsurf rule: `<name>.md` with a `trigger`.
+    WindsurfRule,
+    /// Cline rule: `<name>.md`, limited to `paths` when the skill has globs.
+    ClineRule,
+    /// GitHub Copilot instructions: `<name>.instructions.md` with `applyTo`.
+    CopilotInstructions,
+    /// Augment rule: `<name>.md` with a `type`.
+    AugmentRule,
+}
+
+impl FromStr for SkillFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.replace('-', "_").as_str() {
+            "skill_dir" => Ok(Self::SkillDir),
+            "cursor_mdc" => Ok(Self::CursorMdc),
+            "windsurf_rule" => Ok(Self::WindsurfRule),
+            "cline_rule" => Ok(Self::ClineRule),
+            "copilot_instructions" => Ok(Self::CopilotInstructions),
+            "augment_rule" => Ok(Self::AugmentRule),
+            _ => Err(format!(
+                "Unknown skill format '{}' (expected skill_dir, cursor_mdc, windsurf_rule, cline_rule, copilot_instructions or augment_rule)",
+                s
+            )),
+        }
+    }
+}
+
+impl SkillFormat {
+    /// Renderer for the format, or `None` when the skill directory is synced as is.
+    pub fn renderer(self) -> Option<&'static dyn SkillRenderer> {
+        match self {
+            SkillFormat::SkillDir => None,
+            SkillFormat::CursorMdc => Some(&CursorRenderer),
+            SkillFormat::WindsurfRule => Some(&WindsurfRenderer),
+            SkillFormat::ClineRule => Some(&ClineRenderer),
+            SkillFormat::CopilotInstructions => Some(&CopilotRenderer),
+            SkillFormat::AugmentRule => Some(&AugmentRenderer),
+        }
+    }
+}
+
+/// A Hub skill as a renderer sees it.
+#[derive(Debug, Clone)]
+pub struct RenderSource {
+    /// Directory name in the Hub.
+    pub name: String,
+    pub manifest: SkillManifest,
+    pub body: String,
+    pub hub_path: PathBuf,
+    /// The skill's other files (`scripts/`, `references/`, ...), relative to `hub_path`.
+    pub files: Vec<String>,
+}
+
+impl RenderSource {
+    pub fn load(dir: &Path) -> Result<Self, XskillError> {
+        let skill_md = dir.join("SKILL.md");
+        let raw = fs::read_to_string(&skill_md).map_err(|e| XskillError::io(&skill_md, e))?;
+        let (manifest, body) = frontmatter::parse(&raw).map_err(|e| XskillError::invalid(format!("{}: {}", skill_md.display(), e)))?;
+        let name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let files = crate::utils::list_files(dir)?
+            .into_keys()
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .filter(|p| p != "SKILL.md")
+            .collect();
+        Ok(Self { name, manifest, body, hub_path: dir.to_path_buf(), files })
+    }
+
+    fn description(&self) -> String {
+        self.manifest.description.as_deref().unwrap_or_default().trim().to_string()
+    }
+}
+
+/// Turns a Hub skill into the single file an agent reads natively.
+///
+/// Renderers map what the agent's format can express (description, globs, manual-only
+/// invocation) into its frontmatter and spell out the rest as notes above the body. The
+/// skill's other files stay in the Hub: the rule lists them and links to them there.
+pub trait SkillRenderer: Sync {
+    /// Name of the generated file for a skill whose Hub directory is `name`.
+    fn file_name(&self, name: &str) -> String;
+
+    /// Frontmatter lines, without the `---` fences; empty for none.
+    fn frontmatter(&self, skill: &RenderSource) -> Vec<String>;
+
+    /// Instructions for what the format has no field for.
+    fn notes(&self, skill: &RenderSource) -> Vec<String>;
+
+    fn render(&self, skill: &RenderSource) -> String {
+        let mut out = String::new();
+        let frontmatter = self.frontmatter(skill);
+        if !frontmatter.is_empty() {
+            out.push_str("---\n");
+            for line in frontmatter {
+                out.push_str(&line);
+                out.push('\n');
+            }
+            out.push_str("---\n");
+        }
+        out.push_str(&format!(
+            "<!-- Generated by xskill from {}. Edit the Hub skill and sync again instead of this file. -->\n\n",
+            skill.hub_path.display()
+        ));
+        for note in self.notes(skill).into_iter().chain(files_note(skill)) {
+            out.push_str(&format!("> {}\n", note));
+        }
+        if !out.ends_with("\n\n") {
+            out.push('\n');
+        }
+        out.push_str(&links_to_hub(skill.body.trim_start(), skill));
+        out
+    }
+}
+
+/// Render the Hub skill in `dir` for `format`: the file name and its content, or `None`
+/// for formats that take the skill directory as is.
+pub fn render_skill(format: SkillFormat, dir: &Path) -> Result<Option<(String, String)>, XskillError> {
+    let Some(renderer) = format.renderer() else {
+        return Ok(None);
+    };
+    let skill = RenderSource::load(dir)?;
+    Ok(Some((renderer.file_name(&skill.name), renderer.render(&skill))))
+}
+
+/// A YAML scalar, quoted only when a plain one would be misread.
+fn yaml_str(s: &str) -> String {
+    let plain = !s.is_empty()
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.contains('\n')
+        && !s.starts_with(|c: char| "!&*-:?,[]{}#|>@`\"'%".contains(c) || c.is_whitespace())
+        && !s.ends_with(|c: char| c == ':' || c.is_whitespace());
+    if plain {
+        s.to_string()
+    } else {
+        serde_json::to_string(s).unwrap_or_default()
+    }
+}
+
+fn yaml_list(items: &[String]) -> String {
+    serde_json::to_string(items).unwrap_or_default()
+}
+
+fn files_note(skill: &RenderSource) -> Option<String> {
+    (!skill.files.is_empty()).then(|| format!(
+        "This skill's files are in `{}` ({}); resolve the paths below against that directory.",
+        skill.hub_path.display(),
+        skill.files.iter().map(|f| format!("`{}`", f)).collect::<Vec<_>>().join(", ")
+    ))
+}
+
+/// Point Markdown links to the skill's own files at their Hub copies, since the rule is
+/// written to another directory.
+fn links_to_hub(body: &str, skill: &RenderSource) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("](") {
+        let (head, tail) = rest.split_at(start + 2);
+        out.push_str(head);
+        let end = tail.find(')').unwrap_or(tail.len());
+        let target = tail[..end].trim_start_matches("./");
+        let path = target.split('#').next().unwrap_or_default().trim_end_matches('/');
+        let own_file = !path.is_empty() && skill.files.iter().any(|f| f == path || f.starts_with(&format!("{}/", path)));
+        match own_file {
+            true => out.push_str(&skill.hub_path.join(target).to_string_lossy()),
+            false => out.push_str(&tail[..end]),
+        }
+        rest = &tail[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn tools_note(skill: &RenderSource) -> Option<String> {
+    let tools = &skill.manifest.allowed_tools;
+    (!tools.is_empty()).then(|| format!("Tools these instructions may use without asking: {}.", tools.join(", ")))
+}
+
+fn manual_note(skill: &RenderSource) -> Option<String> {
+    skill.manifest.disable_model_invocation.then(|| "Only follow these instructions when the user asks for them.".to_string())
+}
+
+fn globs_note(skill: &RenderSource) -> Option<String> {
+    let globs = skill.manifest.globs();
+    (!globs.is_empty()).then(|| format!("Applies to files matching {}.", globs.iter().map(|g| format!("`{}`", g)).collect::<Vec<_>>().join(", ")))
+}
+
+pub struct CursorRenderer;
+
+impl SkillRenderer for CursorRenderer {
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.mdc", name)
+    }
+
+    fn frontmatter(&self, skill: &RenderSource) -> Vec<String> {
+        // A rule without description or globs is only applied when @-mentioned. Cursor
+        // itself writes globs comma separated and unquoted.
+        let (description, globs) = match skill.manifest.disable_model_invocation {
+            true => (String::new(), String::new()),
+            false => (yaml_str(&skill.description()), skill.manifest.globs().join(",")),
+        };
+        vec![
+            format!("description: {}", description).trim_end().to_string(),
+            format!("globs: {}", globs).trim_end().to_string(),
+            "alwaysApply: false".to_string(),
+        ]
+    }
+
+    fn notes(&self, skill: &RenderSource) -> Vec<String> {
+        tools_note(skill).into_iter().collect()
+    }
+}
+
+pub struct WindsurfRenderer;
+
+impl SkillRenderer for WindsurfRenderer {
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.md", name)
+    }
+
+    fn frontmatter(&self, skill: &RenderSource) -> Vec<String> {
+        let globs = skill.manifest.globs();
+        if skill.manifest.disable_model_invocation {
+            vec!["trigger: manual".to_string()]
+        } else if !globs.is_empty() {
+            vec!["trigger: glob".to_string(), format!("globs: {}", yaml_str(&globs.join(",")))]
+        } else {
+            vec!["trigger: model_decision".to_string(), format!("description: {}", yaml_str(&skill.description()))]
+        }
+    }
+
+    fn notes(&self, skill: &RenderSource) -> Vec<String> {
+        tools_note(skill).into_iter().collect()
+    }
+}
+
+pub struct ClineRenderer;
+
+impl SkillRenderer for ClineRenderer {
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.md", name)
+    }
+
+    fn frontmatter(&self, skill: &RenderSource) -> Vec<String> {
+        let globs = skill.manifest.globs();
+        match globs.is_empty() {
+            true => Vec::new(),
+            false => vec![format!("paths: {}", yaml_list(&globs))],
+        }
+    }
+
+    fn notes(&self, skill: &RenderSource) -> Vec<String> {
+        // Cline rules are always active, so the description becomes the opening line
+        let description = skill.description();
+        let description = (!description.is_empty()).then(|| format!("Use when: {}", description.replace('\n', " ")));
+        [description, manual_note(skill), tools_note(skill)].into_iter().flatten().collect()
+    }
+}
+
+pub struct CopilotRenderer;
+
+impl SkillRenderer for CopilotRenderer {
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.instructions.md", name)
+    }
+
+    fn frontmatter(&self, skill: &RenderSource) -> Vec<String> {
+        let mut lines = vec![format!("description: {}", yaml_str(&skill.description()))];
+        // Without applyTo the instructions are only attached by hand
+        let globs = skill.manifest.globs();
+        if !skill.manifest.disable_model_invocation && !globs.is_empty() {
+            lines.push(format!("applyTo: {}", yaml_str(&globs.join(","))));
+        }
+        lines
+    }
+
+    fn notes(&self, skill: &RenderSource) -> Vec<String> {
+        tools_note(skill).into_iter().collect()
+    }
+}
+
+pub struct AugmentRenderer;
+
+impl SkillRenderer for AugmentRenderer {
+    fn file_name(&self, name: &str) -> String {
+        format!("{}.md", name)
+    }
+
+    fn frontmatter(&self, skill: &RenderSource) -> Vec<String> {
+        let kind = if skill.manifest.disable_model_invocation { "manual" } else { "agent_requested" };
+        vec![format!("type: {}", kind), format!("description: {}", yaml_str(&skill.description()))]
+    }
+
+    fn notes(&self, skill: &RenderSource) -> Vec<String> {
+        [globs_note(skill), tools_note(skill)].into_iter().flatten().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(frontmatter: &str) -> RenderSource {
+        let raw = format!("---\n{}\n---\n\n# Review\n\nCheck the diff.\n", frontmatter);
+        let (manifest, body) = frontmatter::parse(&raw).unwrap();
+        RenderSource { name: "review".to_string(), manifest, body, hub_path: PathBuf::from("/hub/review"), files: Vec::new() }
+    }
+
+    fn frontmatter_of(rendered: &str) -> serde_yaml::Mapping {
+        let yaml = rendered.strip_prefix("---\n").and_then(|r| r.split("\n---\n").next()).unwrap();
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_cursor_maps_description_globs_and_manual_rules() {
+        let skill = source("name: review\ndescription: 'Review code: style and bugs'\nglobs: [src/**/*.rs, Cargo.toml]\nallowed-tools: Read, Grep");
+        let rendered = CursorRenderer.render(&skill);
+        assert_eq!(CursorRenderer.file_name("review"), "review.mdc");
+        assert!(rendered.starts_with("---\ndescription: \"Review code: style and bugs\"\nglobs: src/**/*.rs,Cargo.toml\nalwaysApply: false\n---\n"));
+        assert!(rendered.contains("Generated by xskill from /hub/review"));
+        assert!(rendered.contains("> Tools these instructions may use without asking: Read, Grep.\n"));
+        assert!(rendered.ends_with("# Review\n\nCheck the diff.\n"));
+
+        let manual = CursorRenderer.render(&source("description: Review\nglobs: '*.rs'\ndisable-model-invocation: true"));
+        assert!(manual.starts_with("---\ndescription:\nglobs:\nalwaysApply: false\n---\n"));
+    }
+
+    #[test]
+    fn test_rule_formats_pick_a_trigger() {
+        let model = source("description: Review code");
+        let globbed = source("description: Review code\nglobs: '*.rs'");
+        let manual = source("description: Review code\ndisable-model-invocation: true");
+
+        let trigger = |s: &RenderSource| frontmatter_of(&WindsurfRenderer.render(s))["trigger"].as_str().unwrap().to_string();
+        assert_eq!(trigger(&model), "model_decision");
+        assert_eq!(trigger(&globbed), "glob");
+        assert_eq!(trigger(&manual), "manual");
+
+        let kind = |s: &RenderSource| frontmatter_of(&AugmentRenderer.render(s))["type"].as_str().unwrap().to_string();
+        assert_eq!(kind(&model), "agent_requested");
+        assert_eq!(kind(&manual), "manual");
+        assert!(AugmentRenderer.render(&globbed).contains("> Applies to files matching `*.rs`.\n"));
+
+        let copilot = frontmatter_of(&CopilotRenderer.render(&globbed));
+        assert_eq!(copilot["applyTo"].as_str(), Some("*.rs"));
+        assert!(frontmatter_of(&CopilotRenderer.render(&manual)).get("applyTo").is_none());
+        assert_eq!(CopilotRenderer.file_name("review"), "review.instructions.md");
+
+        let cline = ClineRenderer.render(&globbed);
+        assert_eq!(frontmatter_of(&cline)["paths"][0].as_str(), Some("*.rs"));
+        assert!(cline.contains("> Use when: Review code\n"));
+        assert!(ClineRenderer.render(&manual).contains("> Only follow these instructions when the user asks for them.\n"));
+        assert!(ClineRenderer.render(&model).starts_with("<!-- Generated by xskill"));
+    }
+
+    #[test]
+    fn test_supporting_files_are_listed_and_linked_in_the_hub() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("review");
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::create_dir_all(dir.join("references")).unwrap();
+        fs::write(dir.join("scripts/check.sh"), "cargo clippy\n").unwrap();
+        fs::write(dir.join("references/style.md"), "# Style\n").unwrap();
+        fs::write(
+            dir.join("SKILL.md"),
+            "---\ndescription: Review\n---\nRun [the check](scripts/check.sh), follow [the guide](./references/style.md#naming) and [upstream](https://example.com/x.md).\n",
+        )
+        .unwrap();
+
+        let (file, content) = render_skill(SkillFormat::CursorMdc, &dir).unwrap().unwrap();
+        assert_eq!(file, "review.mdc");
+        assert!(content.contains(&format!(
+            "> This skill's files are in `{}` (`references/style.md`, `scripts/check.sh`)",
+            dir.display()
+        )));
+        assert!(content.contains(&format!("[the check]({})", dir.join("scripts/check.sh").display())));
+        assert!(content.contains(&format!("[the guide]({})", dir.join("references/style.md#naming").display())));
+        assert!(content.contains("[upstream](https://example.com/x.md)"));
+        assert!(!CursorRenderer.render(&source("description: Review")).contains("This skill's files"));
+    }
+
+    #[test]
+    fn test_format_parses_from_config_names() {
+        assert_eq!("cursor-mdc".parse::<SkillFormat>().unwrap(), SkillFormat::CursorMdc);
+        assert_eq!("skill_dir".parse::<SkillFormat>().unwrap(), SkillFormat::SkillDir);
+        assert!("rtf".parse::<SkillFormat>().is_err());
+        assert!(SkillFormat::SkillDir.renderer().is_none());
+    }
+}
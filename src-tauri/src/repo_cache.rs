@@ -0,0 +1,137 @@
+use crate::error::XskillError;
+use crate::git_backend::{backend, CloneOptions, Progress};
+use crate::utils::COPY_IGNORED;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CACHE_DIR: &str = ".xskill/cache/repos";
+
+/// Bare clones shared by every subdirectory install from the same repository,
+/// so a monorepo with many skills is cloned once and afterwards only fetched.
+pub struct RepoCache {
+    root: PathBuf,
+    fetched: HashSet<String>,
+}
+
+impl RepoCache {
+    pub fn open() -> Result<Self, XskillError> {
+        let home = crate::utils::get_home_dir().ok_or_else(|| XskillError::from("Could not find home directory"))?;
+        Ok(Self::new(home.join(CACHE_DIR)))
+    }
+
+    pub fn new(root: PathBuf) -> Self {
+        Self { root, fetched: HashSet::new() }
+    }
+
+    /// Cached clone for `repo`, named after the repository plus a hash of its URL.
+    pub fn path_for(&self, repo: &str) -> PathBuf {
+        let trimmed = repo.trim_end_matches('/');
+        let name = trimmed.rsplit(['/', ':']).next().unwrap_or("repo").trim_end_matches(".git");
+        let hash: String = Sha256::digest(trimmed.as_bytes()).iter().take(6).map(|b| format!("{:02x}", b)).collect();
+        self.root.join(format!("{}-{}.git", name, hash))
+    }
+
+    /// Clone `repo` into the cache, or fetch it when it is already there.
+    /// Each repository is fetched at most once per `RepoCache`.
+    pub fn sync(&mut self, repo: &str, progress: Progress) -> Result<PathBuf, XskillError> {
+        let dir = self.path_for(repo);
+        if self.fetched.contains(repo) {
+            return Ok(dir);
+        }
+
+        let git = backend();
+        if dir.exists() {
+            git.fetch(&dir, progress)?;
+        } else {
+            fs::create_dir_all(&self.root).map_err(|e| XskillError::io(&self.root, e))?;
+            // Clone beside the final path and rename, so nobody sees a half-written clone
+            let temp = self.root.join(format!(".tmp-{}", uuid::Uuid::new_v4()));
+            if let Err(e) = git.clone_repo(repo, &temp, &CloneOptions { bare: true, depth: None }, progress) {
+                let _ = fs::remove_dir_all(&temp);
+                return Err(e);
+            }
+            if let Err(e) = fs::rename(&temp, &dir) {
+                let _ = fs::remove_dir_all(&temp);
+                if !dir.exists() {
+                    return Err(XskillError::io(&dir, e));
+                }
+            }
+        }
+
+        self.fetched.insert(repo.to_string());
+        Ok(dir)
+    }
+}
+
+/// Commit that `rev` (branch, tag or commit; `None` for the default branch) points to.
+pub fn resolve(dir: &Path, rev: Option<&str>) -> Result<String, XskillError> {
+    backend().resolve(dir, rev.unwrap_or("HEAD"))
+}
+
+/// Whether `commit` exists in the cached clone.
+pub fn has_commit(dir: &Path, commit: &str) -> bool {
+    !commit.is_empty() && backend().resolve(dir, commit).is_ok()
+}
+
+fn is_ignored(relative: &str) -> bool {
+    relative.split('/').any(|c| COPY_IGNORED.contains(&c))
+}
+
+/// Write the files under `subpath` at `commit` into `dest`, replacing it.
+pub fn export(dir: &Path, commit: &str, subpath: &str, dest: &Path) -> Result<(), XskillError> {
+    backend().export(dir, commit, subpath, dest)
+}
+
+/// Commits between `from` and `to` that touch `subpath`.
+pub fn commits_touching(dir: &Path, from: &str, to: &str, subpath: &str) -> Result<usize, XskillError> {
+    backend().count_commits(dir, from, to, Some(subpath))
+}
+
+/// Files under `subpath` that changed between `from` and `to`, relative to `subpath`.
+pub fn changed_files(dir: &Path, from: &str, to: &str, subpath: &str) -> Result<Vec<String>, XskillError> {
+    backend().changed_paths(dir, from, to, Some(subpath))
+}
+
+/// Files of an installed copy that differ from `subpath` at `commit`: edited, added or
+/// deleted locally. Relative to the skill directory and sorted.
+pub fn local_changes(dir: &Path, commit: &str, subpath: &str, skill_dir: &Path) -> Result<Vec<String>, XskillError> {
+    let git = backend();
+    let upstream: BTreeMap<String, String> = git
+        .list_files(dir, commit, Some(subpath))?
+        .into_iter()
+        .filter(|f| !f.is_symlink() && !is_ignored(&f.path))
+        .map(|f| (f.path, f.id))
+        .collect();
+
+    let mut changed = BTreeSet::new();
+    let mut present = HashSet::new();
+    for (rel, abs) in crate::utils::list_files(skill_dir)? {
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        if rel == ".xskill-meta.json" {
+            continue;
+        }
+        if upstream.get(&rel) != Some(&git.hash_file(&abs)?) {
+            changed.insert(rel.clone());
+        }
+        present.insert(rel);
+    }
+    changed.extend(upstream.into_keys().filter(|rel| !present.contains(rel)));
+    Ok(changed.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_for_is_stable_per_repo() {
+        let cache = RepoCache::new(PathBuf::from("/cache"));
+        let a = cache.path_for("https://github.com/acme/skills");
+        assert_eq!(a, cache.path_for("https://github.com/acme/skills/"));
+        assert!(a.file_name().unwrap().to_string_lossy().starts_with("skills-"));
+        assert_ne!(a, cache.path_for("https://gitlab.com/acme/skills"));
+        assert!(cache.path_for("git@github.com:acme/tools.git").file_name().unwrap().to_string_lossy().starts_with("tools-"));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::XskillError;
+use crate::git_backend::backend;
+use crate::git_manager::{install_source, LockAction};
+use crate::repo_cache::{self, RepoCache};
+use crate::skill_lock::SkillSource;
+use crate::skill_manager::CENTRAL_SKILLS_DIR;
+use crate::utils::COPY_IGNORED;
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Window};
+
+/// One `SKILL.md` found in a repository.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredSkill {
+    /// Name it gets in the Hub: the frontmatter `name` when valid, otherwise the directory name.
+    pub name: String,
+    pub description: String,
+    /// Directory of the skill inside the repository; empty for a skill at the root.
+    pub subpath: String,
+    /// A Hub skill with this name already exists.
+    pub installed: bool,
+    pub parse_error: Option<String>,
+}
+
+/// Every skill in a repository at one commit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoDiscovery {
+    /// Origin shared by all the skills: repository, ref and the commit that was scanned.
+    pub source: SkillSource,
+    pub skills: Vec<DiscoveredSkill>,
+}
+
+fn repo_name(repo: &str) -> String {
+    repo.trim_end_matches('/').rsplit(['/', ':']).next().unwrap_or("skill").trim_end_matches(".git").to_string()
+}
+
+/// List every `SKILL.md` in `repo_url` at `pin` (or the default branch) with its parsed name
+/// and description. A `/tree/<branch>/<path>` URL limits the search to that directory.
+pub fn discover(repo_url: &str, pin: Option<&str>) -> Result<RepoDiscovery, XskillError> {
+    let mut source = SkillSource::from_url(repo_url, pin);
+    let prefix = source.subpath.take().map(|p| format!("{}/", p.trim_matches('/')));
+    let cached = RepoCache::open()?.sync(&source.repo, &mut |_| {})?;
+    source.commit = repo_cache::resolve(&cached, source.git_ref.as_deref())?;
+    let skills = scan(&cached, &source, prefix.as_deref())?;
+    Ok(RepoDiscovery { source, skills })
+}
+
+/// Every `SKILL.md` in the cached clone at `source.commit`, under `prefix` when given.
+fn scan(cached: &std::path::Path, source: &SkillSource, prefix: Option<&str>) -> Result<Vec<DiscoveredSkill>, XskillError> {
+    let home = crate::utils::get_home_dir().ok_or_else(|| XskillError::from("Could not find home directory"))?;
+    let hub = home.join(CENTRAL_SKILLS_DIR);
+
+    let mut skills = Vec::new();
+    let git = backend();
+    for file in git.list_files(cached, &source.commit, None)? {
+        let path = file.path.as_str();
+        let Some(dir) = path.strip_suffix("SKILL.md") else { continue };
+        if !(dir.is_empty() || dir.ends_with('/')) {
+            continue;
+        }
+        if prefix.is_some_and(|p| !path.starts_with(p)) {
+            continue;
+        }
+        let subpath = dir.trim_end_matches('/');
+        if subpath.split('/').any(|c| COPY_IGNORED.contains(&c)) {
+            continue;
+        }
+
+        let dir_name = match subpath.rsplit('/').next() {
+            Some(n) if !n.is_empty() => n.to_string(),
+            _ => repo_name(&source.repo),
+        };
+        let raw = String::from_utf8_lossy(&git.read_file(cached, &source.commit, path)?).to_string();
+        let (name, description, parse_error) = match crate::frontmatter::parse(&raw) {
+            Ok((manifest, _)) => (
+                manifest.name.filter(|n| crate::scaffold::validate_skill_name(n).is_ok()).unwrap_or(dir_name),
+                manifest.description.unwrap_or_default(),
+                None,
+            ),
+            Err(e) => (dir_name, String::new(), Some(e.to_string())),
+        };
+        skills.push(DiscoveredSkill {
+            installed: hub.join(&name).exists(),
+            name,
+            description,
+            subpath: subpath.to_string(),
+            parse_error,
+        });
+    }
+    skills.sort_by(|a, b| a.subpath.cmp(&b.subpath));
+    Ok(skills)
+}
+
+/// Re-read the skills at `subpaths` from the repository itself, so that names and paths
+/// chosen in the UI are never trusted as they arrive.
+pub fn rediscover(source: &SkillSource, subpaths: &[String]) -> Result<Vec<DiscoveredSkill>, XskillError> {
+    if source.commit.is_empty() {
+        return Err(XskillError::invalid("Discovery has no commit; discover the repository again"));
+    }
+    let cached = RepoCache::open()?.sync(&source.repo, &mut |_| {})?;
+    let skills = scan(&cached, source, None)?;
+    subpaths
+        .iter()
+        .map(|p| skills.iter().find(|s| &s.subpath == p).cloned().ok_or_else(|| XskillError::invalid(format!("No skill at '{}' in {}", p, source.repo))))
+        .collect()
+}
+
+/// Refuse a discovered skill whose name or subpath would reach outside the Hub or the repository.
+fn check_discovered(skill: &DiscoveredSkill) -> Result<(), XskillError> {
+    crate::scaffold::validate_hub_name(&skill.name)?;
+    if skill.subpath.starts_with('/') || (!skill.subpath.is_empty() && skill.subpath.split('/').any(|c| c.is_empty() || c == "." || c == "..")) {
+        return Err(XskillError::invalid(format!("Invalid skill path '{}'", skill.subpath)));
+    }
+    Ok(())
+}
+
+impl Xskill {
+    /// Install the chosen `skills` from a discovery as separate Hub skills. They all record
+    /// `source` (same repository, ref and commit) with their own subpath.
+    pub async fn install_discovered<F>(&self, source: &SkillSource, skills: &[DiscoveredSkill], mut progress: F) -> Vec<LockAction>
+    where
+        F: FnMut(String),
+    {
+        let mut actions = Vec::new();
+        for skill in skills {
+            let (action, detail) = if let Err(e) = check_discovered(skill) {
+                ("failed", Some(e.to_string()))
+            } else if self.hub().join(&skill.name).exists() {
+                ("skipped", Some(format!("Skill '{}' already exists in Hub", skill.name)))
+            } else {
+                let skill_source = SkillSource {
+                    subpath: (!skill.subpath.is_empty()).then(|| skill.subpath.clone()),
+                    ..source.clone()
+                };
+                progress(format!("Installing {}...", skill.name));
+                match install_source(self, &skill_source, Some(&source.commit), &skill.name, &mut progress).await {
+                    Ok(_) => ("installed", Some(skill.subpath.clone()).filter(|s| !s.is_empty())),
+                    Err(e) => ("failed", Some(e.to_string())),
+                }
+            };
+            actions.push(LockAction { name: skill.name.clone(), action: action.to_string(), detail });
+        }
+        actions
+    }
+}
+
+#[tauri::command]
+pub async fn discover_repo_skills(repo_url: String, pin: Option<String>) -> Result<RepoDiscovery, XskillError> {
+    discover(&repo_url, pin.as_deref())
+}
+
+#[tauri::command]
+pub async fn install_repo_skills(window: Window, source: SkillSource, skills: Vec<DiscoveredSkill>) -> Result<Vec<LockAction>, XskillError> {
+    let subpaths: Vec<String> = skills.into_iter().map(|s| s.subpath).collect();
+    let skills = rediscover(&source, &subpaths)?;
+    Ok(Xskill::load()?.install_discovered(&source, &skills, |msg| {
+        let _ = window.emit("import-progress", msg);
+    }).await)
+}
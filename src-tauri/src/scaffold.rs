@@ -1,37 +1,20 @@
-use crate::skill_manager::CENTRAL_SKILLS_DIR;
+use crate::error::XskillError;
+use crate::frontmatter::SkillManifest;
+use crate::skill_manager::{write_skill_meta, SkillMeta};
+use crate::xskill::Xskill;
 use std::fs;
-use std::path::PathBuf;
-
-fn tool_skills_dir(tool_key: &str) -> Option<PathBuf> {
-    let home = crate::utils::get_home_dir()?;
-    if tool_key == "xskill" || tool_key == "local" {
-        return Some(home.join(CENTRAL_SKILLS_DIR));
-    }
-    let subdir = match tool_key {
-        "cursor" => ".cursor/skills",
-        "claude_code" => ".claude/skills",
-        "opencode" => ".config/opencode/skills",
-        "windsurf" => ".codeium/windsurf/skills",
-        "gemini_cli" => ".gemini/skills",
-        "github_copilot" => ".copilot/skills",
-        "amp" => ".config/agents/skills",
-        "goose" => ".config/goose/skills",
-        "antigravity" => ".gemini/antigravity/global_skills",
-        "augment" => ".augment/rules",
-        "codex" => ".codex/skills",
-        "kimi_cli" => ".kimi/skills",
-        "openclaw" => ".openclaw/skills",
-        "cline" => ".cline/skills",
-        "codebuddy" => ".codebuddy/skills",
-        "continue_dev" => ".continue/skills",
-        "crush" => ".crush/skills",
-        "junie" => ".junie/skills",
-        "kode" => ".kode/skills",
-        "roo_code" => ".roo-code/skills",
-        "kilo_code" => ".kilocode/skills",
-        _ => return None,
-    };
-    Some(home.join(subdir))
+use std::path::{Path, PathBuf};
+
+/// Contents of a skill created by [`Xskill::create_skill`].
+#[derive(Debug, Clone, Default)]
+pub struct NewSkill {
+    pub name: String,
+    pub description: String,
+    /// Overview paragraph; its first line also fills in "When to Use". Empty uses the
+    /// description.
+    pub content: String,
+    pub negative_triggers: String,
+    pub allowed_tools: Vec<String>,
 }
 
 /// Generate a professional SKILL.md following best practices
@@ -42,16 +25,22 @@ fn generate_skill_md(
     negative_triggers: &str,
     allowed_tools: &[String],
 ) -> String {
-    let allowed_tools_str = if allowed_tools.is_empty() {
-        String::new()
-    } else {
-        format!("allowed-tools: [{}]\n", allowed_tools.join(", "))
+    // Serialize the frontmatter so descriptions containing `:` or `#` stay valid YAML
+    let manifest = SkillManifest {
+        name: Some(name.to_string()),
+        description: Some(description.to_string()),
+        allowed_tools: allowed_tools.to_vec(),
+        ..Default::default()
     };
+    let frontmatter = crate::frontmatter::render(&manifest, "").unwrap_or_else(|_| {
+        format!("---\nname: {}\ndescription: {}\n---\n\n", name, description)
+    });
 
-    format!("---\nname: {}\ndescription: {}\n{}\n---\n\n## Overview\n{}\n\n## When to Use\n- Use this skill when the user wants to {}\n\n## When NOT to Use\n{}\n\n## Procedures\n\n### Step 1: Understand the Request\n{}\n\n### Step 2: Execute the Task\n{}\n\n### Step 3: Verify the Result\n{}\n\n## Error Handling\n\nIf errors occur, provide clear feedback to the user and suggest fixes.\n\n## References\n\nSee the following files for detailed information:\n- `references/` - Additional documentation\n- `assets/` - Templates and examples\n",
-        name,
-        description,
-        allowed_tools_str,
+    // Without an overview of its own, the description is the best summary there is
+    let content = if content.trim().is_empty() { description } else { content };
+
+    format!("{}## Overview\n{}\n\n## When to Use\n- Use this skill when the user wants to {}\n\n## When NOT to Use\n{}\n\n## Procedures\n\n### Step 1: Understand the Request\n{}\n\n### Step 2: Execute the Task\n{}\n\n### Step 3: Verify the Result\n{}\n\n## Error Handling\n\nIf errors occur, provide clear feedback to the user and suggest fixes.\n\n## References\n\nSee the following files for detailed information:\n- `references/` - Additional documentation\n- `assets/` - Templates and examples\n",
+        frontmatter,
         content,
         content.lines().next().unwrap_or("perform the task"),
         if negative_triggers.is_empty() {
@@ -66,10 +55,8 @@ fn generate_skill_md(
 }
 
 /// Copy skill directory to hub
-fn collect_skill_to_hub(skill_dir: &PathBuf) -> Result<PathBuf, String> {
-    let home =
-        crate::utils::get_home_dir().ok_or_else(|| "Could not find home directory".to_string())?;
-    let hub_dir = home.join(CENTRAL_SKILLS_DIR).join("hub");
+fn collect_skill_to_hub(hub: &Path, skill_dir: &PathBuf) -> Result<PathBuf, String> {
+    let hub_dir = hub.join("hub");
 
     fs::create_dir_all(&hub_dir).map_err(|e| format!("Failed to create hub directory: {}", e))?;
 
@@ -112,75 +99,110 @@ fn copy_dir_all(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
-pub fn create_skill(
-    name: String,
-    description: String,
-    tool_key: String,
-    content: String,
-    negative_triggers: Option<String>,
-    allowed_tools: Option<Vec<String>>,
-    collect_to_hub: Option<bool>,
-) -> Result<String, String> {
-    // Validate name: lowercase, numbers, hyphens only, 1-64 chars
-    let name_lower = name.to_lowercase();
-    if name_lower.len() > 64 {
+/// Validate a skill name: lowercase letters, numbers and hyphens only, 1-64 chars.
+pub fn validate_skill_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    if name.len() > 64 {
         return Err("Name must be 64 characters or less".to_string());
     }
-    if !name_lower
+    if !name
         .chars()
         .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
     {
         return Err("Name must contain only lowercase letters, numbers, and hyphens".to_string());
     }
-    if name_lower.contains("--") {
+    if name.contains("--") {
         return Err("Name cannot contain consecutive hyphens".to_string());
     }
+    Ok(())
+}
+
+/// Validate a name that becomes a Hub directory, such as one read from `skills.lock` or a
+/// Hub manifest. Git installs are named after their repository, so this only insists on a
+/// single plain directory name rather than the [`validate_skill_name`] rules.
+pub fn validate_hub_name(name: &str) -> Result<(), XskillError> {
+    let reason = if name.is_empty() {
+        "Name cannot be empty"
+    } else if name.starts_with('.') {
+        "Name cannot start with a dot"
+    } else if name.contains(['/', '\\', ':', '\0']) {
+        "Name cannot contain path separators"
+    } else {
+        return Ok(());
+    };
+    Err(XskillError::InvalidName { name: name.to_string(), reason: reason.to_string() })
+}
+
+impl Xskill {
+    /// Scaffold a skill (SKILL.md plus `scripts/`, `references/` and `assets/`) in `agent`'s
+    /// skills directory, or in the Hub for `"xskill"` / `"local"`. Returns its directory.
+    pub fn create_skill(&self, agent: &str, skill: &NewSkill, collect_to_hub: bool) -> Result<PathBuf, XskillError> {
+        let name_lower = skill.name.to_lowercase();
+        validate_skill_name(&name_lower).map_err(|reason| XskillError::InvalidName { name: name_lower.clone(), reason })?;
+
+        let skills_dir = if agent == "xskill" || agent == "local" {
+            self.hub().to_path_buf()
+        } else {
+            self.agent_skills_dir(agent)?
+        };
+
+        let skill_dir = skills_dir.join(&name_lower);
+        fs::create_dir_all(&skill_dir).map_err(|e| XskillError::io(&skill_dir, e))?;
+
+        // Create subdirectories following best practices
+        let scripts_dir = skill_dir.join("scripts");
+        let references_dir = skill_dir.join("references");
+        let assets_dir = skill_dir.join("assets");
+
+        fs::create_dir_all(&scripts_dir).map_err(|e| XskillError::io(&scripts_dir, e))?;
+        fs::create_dir_all(&references_dir).map_err(|e| XskillError::io(&references_dir, e))?;
+        fs::create_dir_all(&assets_dir).map_err(|e| XskillError::io(&assets_dir, e))?;
+
+        // Generate professional SKILL.md
+        let skill_md = generate_skill_md(&name_lower, &skill.description, &skill.content, &skill.negative_triggers, &skill.allowed_tools);
+
+        let skill_md_path = skill_dir.join("SKILL.md");
+        fs::write(&skill_md_path, skill_md).map_err(|e| XskillError::io(&skill_md_path, e))?;
+
+        // Create placeholder files to guide users
+        let readme_content = "# References\n\nAdd supplementary documentation here.\n- API docs\n- Cheatsheets\n- Domain logic\n";
+        fs::write(references_dir.join("README.md"), readme_content).map_err(|e| XskillError::io(&references_dir, e))?;
 
-    let skills_dir =
-        tool_skills_dir(&tool_key).ok_or_else(|| format!("Unknown tool key: {}", tool_key))?;
-
-    let skill_dir = skills_dir.join(&name_lower);
-    fs::create_dir_all(&skill_dir)
-        .map_err(|e| format!("Failed to create skill directory: {}", e))?;
-
-    // Create subdirectories following best practices
-    let scripts_dir = skill_dir.join("scripts");
-    let references_dir = skill_dir.join("references");
-    let assets_dir = skill_dir.join("assets");
-
-    fs::create_dir_all(&scripts_dir)
-        .map_err(|e| format!("Failed to create scripts directory: {}", e))?;
-    fs::create_dir_all(&references_dir)
-        .map_err(|e| format!("Failed to create references directory: {}", e))?;
-    fs::create_dir_all(&assets_dir)
-        .map_err(|e| format!("Failed to create assets directory: {}", e))?;
-
-    // Generate professional SKILL.md
-    let neg_triggers = negative_triggers.unwrap_or_default();
-    let tools = allowed_tools.unwrap_or_default();
-    let skill_md = generate_skill_md(&name_lower, &description, &content, &neg_triggers, &tools);
-
-    let skill_md_path = skill_dir.join("SKILL.md");
-    fs::write(&skill_md_path, skill_md).map_err(|e| format!("Failed to write SKILL.md: {}", e))?;
-
-    // Create placeholder files to guide users
-    let readme_content = "# References\n\nAdd supplementary documentation here.\n- API docs\n- Cheatsheets\n- Domain logic\n";
-    fs::write(references_dir.join("README.md"), readme_content)
-        .map_err(|e| format!("Failed to create references README: {}", e))?;
-
-    let assets_readme = "# Assets\n\nAdd templates and output examples here.\n- JSON schemas\n- Output templates\n- Configuration examples\n";
-    fs::write(assets_dir.join("README.md"), assets_readme)
-        .map_err(|e| format!("Failed to create assets README: {}", e))?;
-
-    let scripts_readme = "# Scripts\n\nAdd executable scripts here (Python, Bash, Node).\nScripts should be tiny, single-purpose CLIs.\n";
-    fs::write(scripts_dir.join("README.md"), scripts_readme)
-        .map_err(|e| format!("Failed to create scripts README: {}", e))?;
-
-    // Collect to hub if requested
-    if collect_to_hub.unwrap_or(true) {
-        collect_skill_to_hub(&skill_dir)?;
+        let assets_readme = "# Assets\n\nAdd templates and output examples here.\n- JSON schemas\n- Output templates\n- Configuration examples\n";
+        fs::write(assets_dir.join("README.md"), assets_readme).map_err(|e| XskillError::io(&assets_dir, e))?;
+
+        let scripts_readme = "# Scripts\n\nAdd executable scripts here (Python, Bash, Node).\nScripts should be tiny, single-purpose CLIs.\n";
+        fs::write(scripts_dir.join("README.md"), scripts_readme).map_err(|e| XskillError::io(&scripts_dir, e))?;
+        write_skill_meta(&skill_dir, &SkillMeta::local(&skill_dir))?;
+
+        // Collect to hub if requested
+        if collect_to_hub {
+            collect_skill_to_hub(self.hub(), &skill_dir)?;
+        }
+
+        Ok(skill_dir)
     }
+}
 
+#[tauri::command]
+pub fn create_skill(
+    name: String,
+    description: String,
+    tool_key: String,
+    content: String,
+    negative_triggers: Option<String>,
+    allowed_tools: Option<Vec<String>>,
+    collect_to_hub: Option<bool>,
+) -> Result<String, XskillError> {
+    let skill = NewSkill {
+        name,
+        description,
+        content,
+        negative_triggers: negative_triggers.unwrap_or_default(),
+        allowed_tools: allowed_tools.unwrap_or_default(),
+    };
+    let skill_dir = Xskill::load()?.create_skill(&tool_key, &skill, collect_to_hub.unwrap_or(true))?;
     Ok(skill_dir.to_string_lossy().to_string())
 }
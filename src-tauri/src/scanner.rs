@@ -1,3 +1,5 @@
+use crate::error::XskillError;
+use crate::xskill::Xskill;
 use std::path::{Path, PathBuf};
 use walkdir::{DirEntry, WalkDir};
 use serde::{Serialize, Deserialize};
@@ -91,26 +93,22 @@ fn check_for_mcp(path: &Path) -> bool {
     false
 }
 
-#[tauri::command]
-pub fn scan_workspace(extra_roots: Option<Vec<String>>) -> Result<Vec<Project>, String> {
-    let mut roots = Vec::new();
-    
-    // Add default roots
-    if let Some(home) = crate::utils::get_home_dir() {
-        roots.push(home.join("workspace"));
-        roots.push(home.join("projects"));
-        roots.push(home.join("codes"));
-        roots.push(home.join("dev"));
-    }
-    
-    // Add extra roots if provided
-    if let Some(extras) = extra_roots {
-        for r in extras {
-            roots.push(PathBuf::from(r));
-        }
+impl Xskill {
+    /// Git projects under `~/workspace`, `~/projects`, `~/codes`, `~/dev` and `extra_roots`.
+    pub fn scan_projects(&self, extra_roots: &[PathBuf]) -> Vec<Project> {
+        let mut roots: Vec<PathBuf> = ["workspace", "projects", "codes", "dev"]
+            .iter()
+            .map(|dir| self.home().join(dir))
+            .collect();
+        roots.extend_from_slice(extra_roots);
+        scan_roots(roots)
     }
+}
 
-    Ok(scan_roots(roots))
+#[tauri::command]
+pub fn scan_workspace(extra_roots: Option<Vec<String>>) -> Result<Vec<Project>, XskillError> {
+    let extra_roots: Vec<PathBuf> = extra_roots.unwrap_or_default().into_iter().map(PathBuf::from).collect();
+    Ok(Xskill::load()?.scan_projects(&extra_roots))
 }
 
 #[cfg(test)]
@@ -0,0 +1,221 @@
+use crate::error::XskillError;
+use crate::fingerprint::calculate_dir_hash;
+use crate::skill_manager::{read_skill_meta, CENTRAL_SKILLS_DIR};
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LOCK_FILE: &str = "skills.lock";
+const LOCK_VERSION: u32 = 1;
+
+/// Where a git-installed skill came from and which revision is checked out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillSource {
+    /// Repository URL, without any `/tree/<branch>/<path>` (or other forge's) suffix.
+    pub repo: String,
+    /// Branch, tag or commit that was asked for; `None` means the default branch.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Directory inside the repository for subdirectory installs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+    /// Resolved commit that is installed.
+    #[serde(default)]
+    pub commit: String,
+    /// Whether `git_ref` is a pin that plain updates must not move.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl SkillSource {
+    /// Build a source from an install URL (plain repo or a forge tree URL) and an optional pin.
+    pub fn from_url(url: &str, pin: Option<&str>) -> Self {
+        let (repo, branch, subpath) = match crate::forge::parse_tree_url(url) {
+            Some((repo, branch, subpath)) => (repo, Some(branch), Some(subpath)),
+            None => (url.trim_end_matches('/').to_string(), None, None),
+        };
+        Self {
+            repo,
+            git_ref: pin.map(str::to_string).or(branch),
+            subpath,
+            commit: String::new(),
+            pinned: pin.is_some(),
+        }
+    }
+
+    /// URL that `from_url` would turn back into this source (minus the pin).
+    pub fn url(&self) -> String {
+        match &self.subpath {
+            Some(sub) => crate::forge::detect(&self.repo).tree_url(&self.repo, self.git_ref.as_deref().unwrap_or("HEAD"), sub),
+            None => self.repo.clone(),
+        }
+    }
+}
+
+/// One Hub skill in `~/.xskill/skills.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedSkill {
+    /// Directory name in the Hub.
+    pub name: String,
+    pub fingerprint: String,
+    /// `None` for skills that were created or imported locally.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SkillSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillsLock {
+    pub version: u32,
+    #[serde(rename = "skill", default)]
+    pub skills: Vec<LockedSkill>,
+}
+
+impl Default for SkillsLock {
+    fn default() -> Self {
+        Self { version: LOCK_VERSION, skills: Vec::new() }
+    }
+}
+
+pub fn lock_path() -> Result<PathBuf, String> {
+    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+    Ok(lock_path_in(&home))
+}
+
+pub fn lock_path_in(home: &Path) -> PathBuf {
+    home.join(".xskill").join(LOCK_FILE)
+}
+
+impl SkillsLock {
+    pub fn load_from(path: &Path) -> Result<Self, XskillError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| XskillError::io(path, e))?;
+        toml::from_str(&content).map_err(|e| XskillError::Config { path: path.to_path_buf(), message: e.to_string() })
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), XskillError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+        }
+        let content = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let header = "# Generated by xskill. Reproduce this Hub with `xskill lock --install`.\n";
+        fs::write(path, format!("{}{}", header, content)).map_err(|e| XskillError::io(path, e))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedSkill> {
+        self.skills.iter().find(|s| s.name == name)
+    }
+
+    /// Describe every skill directory currently in `hub`.
+    pub fn from_hub(hub: &Path) -> Result<Self, String> {
+        let mut lock = Self::default();
+        if !hub.exists() {
+            return Ok(lock);
+        }
+        for entry in fs::read_dir(hub).map_err(|e| e.to_string())?.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Skip staging dirs and other hidden entries
+            if !path.is_dir() || name.starts_with('.') {
+                continue;
+            }
+            lock.skills.push(LockedSkill {
+                name,
+                fingerprint: calculate_dir_hash(&path)?,
+                source: read_skill_meta(&path).and_then(|m| m.source),
+            });
+        }
+        lock.skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(lock)
+    }
+}
+
+/// Rewrite `~/.xskill/skills.lock` from the current Hub.
+pub fn refresh() -> Result<SkillsLock, String> {
+    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+    refresh_in(&home)
+}
+
+pub fn refresh_in(home: &Path) -> Result<SkillsLock, String> {
+    let lock = SkillsLock::from_hub(&home.join(CENTRAL_SKILLS_DIR))?;
+    lock.save_to(&lock_path_in(home))?;
+    Ok(lock)
+}
+
+/// Like `refresh`, for callers where a stale lockfile should not fail the operation.
+pub fn refresh_quietly() {
+    if let Err(e) = refresh() {
+        eprintln!("Failed to update skills.lock: {}", e);
+    }
+}
+
+impl Xskill {
+    /// Rewrite `skills.lock` from the Hub.
+    pub fn refresh_lock(&self) -> Result<SkillsLock, XskillError> {
+        Ok(refresh_in(self.home())?)
+    }
+
+    pub fn refresh_lock_quietly(&self) {
+        if let Err(e) = self.refresh_lock() {
+            eprintln!("Failed to update skills.lock: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_skills_lock() -> Result<SkillsLock, XskillError> {
+    Xskill::load()?.refresh_lock()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_source_from_url_and_back() {
+        let source = SkillSource::from_url("https://github.com/acme/skills/tree/main/pdf", None);
+        assert_eq!(source.repo, "https://github.com/acme/skills");
+        assert_eq!(source.git_ref.as_deref(), Some("main"));
+        assert_eq!(source.subpath.as_deref(), Some("pdf"));
+        assert!(!source.pinned);
+        assert_eq!(source.url(), "https://github.com/acme/skills/tree/main/pdf");
+
+        let pinned = SkillSource::from_url("https://github.com/acme/skill/", Some("v1.2.0"));
+        assert_eq!(pinned.repo, "https://github.com/acme/skill");
+        assert_eq!(pinned.git_ref.as_deref(), Some("v1.2.0"));
+        assert!(pinned.pinned);
+    }
+
+    #[test]
+    fn test_lock_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("skills.lock");
+        let lock = SkillsLock {
+            version: LOCK_VERSION,
+            skills: vec![
+                LockedSkill { name: "local".to_string(), fingerprint: "abc".to_string(), source: None },
+                LockedSkill {
+                    name: "remote".to_string(),
+                    fingerprint: "def".to_string(),
+                    source: Some(SkillSource {
+                        repo: "https://github.com/acme/remote".to_string(),
+                        git_ref: Some("v1".to_string()),
+                        subpath: None,
+                        commit: "0123456789abcdef".to_string(),
+                        pinned: true,
+                    }),
+                },
+            ],
+        };
+        lock.save_to(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("[[skill]]"));
+        assert!(content.contains("ref = \"v1\""));
+
+        let loaded = SkillsLock::load_from(&path).unwrap();
+        assert_eq!(loaded.skills, lock.skills);
+    }
+}
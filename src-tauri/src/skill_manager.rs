@@ -1,6 +1,9 @@
+use crate::error::XskillError;
+use crate::frontmatter::{FrontmatterError, SkillManifest};
+use crate::xskill::Xskill;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Tool {
@@ -18,58 +21,63 @@ pub struct LocalSkill {
     pub tool_key: String,
     pub disable_model_invocation: bool,
     pub allowed_tools: Vec<String>,
+    /// From `tags` or `metadata.tags` in the frontmatter.
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub content: String,
     pub original_url: Option<String>,
     pub remark: Option<String>,
+    /// Set when the SKILL.md frontmatter could not be parsed; the other fields then fall back to defaults.
+    #[serde(default)]
+    pub parse_error: Option<FrontmatterError>,
+    /// Whether a trusted publisher signed the installed copy.
+    #[serde(default)]
+    pub trust: crate::trust::TrustStatus,
 }
 
-#[derive(Debug, Clone)]
-pub struct ToolDef {
-    pub key: &'static str,
-    pub display_name: &'static str,
-    pub skills_subdir: &'static str,
-    pub detect_subdir: &'static str,
-}
-
-pub fn tool_definitions() -> Vec<ToolDef> {
-    vec![
-        ToolDef { key: "cursor",         display_name: "Cursor",          skills_subdir: ".cursor/skills",                       detect_subdir: ".cursor" },
-        ToolDef { key: "vscode",         display_name: "VS Code",         skills_subdir: ".vscode/skills",                       detect_subdir: ".vscode" },
-        ToolDef { key: "claude_code",    display_name: "Claude Code",     skills_subdir: ".claude/skills",                       detect_subdir: ".claude" },
-        ToolDef { key: "opencode",       display_name: "OpenCode",        skills_subdir: ".config/opencode/skills",              detect_subdir: ".config/opencode" },
-        ToolDef { key: "windsurf",       display_name: "Windsurf",        skills_subdir: ".codeium/windsurf/skills",             detect_subdir: ".codeium/windsurf" },
-        ToolDef { key: "trae",           display_name: "Trae",            skills_subdir: ".trae/skills",                         detect_subdir: ".trae" },
-        ToolDef { key: "gemini_cli",     display_name: "Gemini CLI",      skills_subdir: ".gemini/skills",                       detect_subdir: ".gemini" },
-        ToolDef { key: "antigravity",    display_name: "Antigravity",     skills_subdir: ".gemini/antigravity/skills",           detect_subdir: ".gemini/antigravity" },
-        ToolDef { key: "github_copilot", display_name: "GitHub Copilot",  skills_subdir: ".copilot/skills",                      detect_subdir: ".copilot" },
-        ToolDef { key: "amp",            display_name: "Amp",             skills_subdir: ".config/agents/skills",                detect_subdir: ".config/agents" },
-        ToolDef { key: "goose",          display_name: "Goose",           skills_subdir: ".config/goose/skills",                 detect_subdir: ".config/goose" },
-        ToolDef { key: "codex",          display_name: "Codex",           skills_subdir: ".codex/skills",                        detect_subdir: ".codex" },
-        ToolDef { key: "kode",           display_name: "Kode",            skills_subdir: ".kode/skills",                         detect_subdir: ".kode" },
-        ToolDef { key: "roo_code",       display_name: "Roo Code",        skills_subdir: ".roo/skills",                          detect_subdir: ".roo" },
-        ToolDef { key: "kilo_code",      display_name: "Kilo Code",       skills_subdir: ".kilocode/skills",                     detect_subdir: ".kilocode" },
-        ToolDef { key: "clawdbot",       display_name: "Clawdbot",        skills_subdir: ".clawdbot/skills",                     detect_subdir: ".clawdbot" },
-        ToolDef { key: "droid",          display_name: "Droid",           skills_subdir: ".factory/skills",                      detect_subdir: ".factory" },
-        ToolDef { key: "qoder",          display_name: "Qoder",           skills_subdir: ".qoder/skills",                        detect_subdir: ".qoder" },
-    ]
-}
+pub use crate::agent_registry::AgentDef as ToolDef;
 
 pub fn home_dir() -> Result<PathBuf, String> {
     crate::utils::get_home_dir().ok_or_else(|| "Could not find home directory".to_string())
 }
 
-pub fn skills_dir_for_tool(def: &ToolDef) -> Result<PathBuf, String> {
-    let home = home_dir()?;
-    Ok(home.join(def.skills_subdir))
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SkillMeta {
     pub original_url: Option<String>,
     pub remark: Option<String>,
+    /// Repository, ref, subpath and commit for git-installed skills.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<crate::skill_lock::SkillSource>,
+    /// SHA-256 of the archive the skill was installed from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Signer verified at install time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<crate::trust::SignatureRecord>,
+    /// Set when xskill created or imported the skill on this machine. Skills with neither an
+    /// origin nor a source are treated as unsigned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<SkillOrigin>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkillOrigin {
+    /// Created with `xskill create` or imported from an agent directory.
+    Local,
+    /// Anything this version does not know; never trusted.
+    #[serde(other)]
+    Unknown,
 }
 
-pub fn read_skill_meta(path: &PathBuf) -> Option<SkillMeta> {
+impl SkillMeta {
+    /// The metadata of the skill at `path` (if any), marked as created on this machine.
+    pub fn local(path: &Path) -> Self {
+        Self { origin: Some(SkillOrigin::Local), ..read_skill_meta(path).unwrap_or_default() }
+    }
+}
+
+pub fn read_skill_meta(path: &Path) -> Option<SkillMeta> {
     let meta_path = if path.is_file() {
         path.parent()?.join(".xskill-meta.json")
     } else {
@@ -84,7 +92,7 @@ pub fn read_skill_meta(path: &PathBuf) -> Option<SkillMeta> {
     None
 }
 
-pub fn write_skill_meta(path: &PathBuf, meta: &SkillMeta) -> Result<(), String> {
+pub fn write_skill_meta(path: &Path, meta: &SkillMeta) -> Result<(), String> {
     let meta_path = if path.is_file() {
         path.parent().ok_or("Invalid path")?.join(".xskill-meta.json")
     } else {
@@ -96,61 +104,57 @@ pub fn write_skill_meta(path: &PathBuf, meta: &SkillMeta) -> Result<(), String>
     Ok(())
 }
 
-fn parse_skill_md(raw: &str, fallback_name: &str, skill_path: &PathBuf) -> (String, String, bool, Vec<String>, String, Option<String>, Option<String>) {
-    let mut name = fallback_name.to_string();
-    let mut description = String::new();
-    let mut disable_model_invocation = false;
-    let mut allowed_tools: Vec<String> = Vec::new();
-    let mut original_url: Option<String> = None;
-    let mut remark: Option<String> = None;
-    
-    // First try to load from .xskill-meta.json
-    if let Some(meta) = read_skill_meta(skill_path) {
-        original_url = meta.original_url;
-        remark = meta.remark;
-    }
+/// Result of reading a SKILL.md (or single-file skill) together with its `.xskill-meta.json`.
+#[derive(Debug, Clone)]
+pub struct ParsedSkill {
+    pub manifest: SkillManifest,
+    pub name: String,
+    pub description: String,
+    pub content: String,
+    pub original_url: Option<String>,
+    pub remark: Option<String>,
+    pub parse_error: Option<FrontmatterError>,
+}
 
-    let content;
-    let trimmed = raw.trim();
-    if let Some(after_open) = trimmed.strip_prefix("---") {
-        if let Some(close_pos) = after_open.find("\n---") {
-            let frontmatter = &after_open[..close_pos];
-            let after_close = &after_open[close_pos + 4..];
-            content = after_close.trim_start_matches(['\n', '\r']).to_string();
-            for line in frontmatter.lines() {
-                let line = line.trim();
-                if let Some(val) = line.strip_prefix("name:") {
-                    name = val.trim().trim_matches('"').trim_matches('\'').to_string();
-                } else if let Some(val) = line.strip_prefix("description:") {
-                    description = val.trim().trim_matches('"').trim_matches('\'').to_string();
-                } else if let Some(val) = line.strip_prefix("disable-model-invocation:") {
-                    disable_model_invocation = val.trim() == "true";
-                } else if let Some(val) = line.strip_prefix("allowed-tools:") {
-                    let list_str = val.trim().trim_matches('[').trim_matches(']');
-                    allowed_tools = list_str
-                        .split(',')
-                        .map(|s| s.trim().to_string())
-                        .filter(|s| !s.is_empty())
-                        .collect();
-                } else if let Some(val) = line.strip_prefix("original-url:") {
-                    // Only use frontmatter if meta file didn't provide it
-                    if original_url.is_none() {
-                        original_url = Some(val.trim().to_string());
-                    }
-                } else if let Some(val) = line.strip_prefix("remark:") {
-                    // Only use frontmatter if meta file didn't provide it
-                    if remark.is_none() {
-                        remark = Some(val.trim().to_string());
-                    }
-                }
-            }
-        } else {
-            content = raw.to_string();
+impl ParsedSkill {
+    fn into_local_skill(self, path: String, tool_key: &str) -> LocalSkill {
+        LocalSkill {
+            name: self.name,
+            description: self.description,
+            tool_key: tool_key.to_string(),
+            disable_model_invocation: self.manifest.disable_model_invocation,
+            tags: self.manifest.tags(),
+            allowed_tools: self.manifest.allowed_tools,
+            content: self.content,
+            original_url: self.original_url,
+            remark: self.remark,
+            parse_error: self.parse_error,
+            trust: crate::trust::status_of(Path::new(&path)),
+            path,
         }
-    } else {
-        content = raw.to_string();
     }
-    (name, description, disable_model_invocation, allowed_tools, content, original_url, remark)
+}
+
+pub fn parse_skill_md(raw: &str, fallback_name: &str, skill_path: &Path) -> ParsedSkill {
+    let (manifest, content, parse_error) = match crate::frontmatter::parse(raw) {
+        Ok((manifest, body)) => (manifest, body, None),
+        Err(e) => (SkillManifest::default(), raw.to_string(), Some(e)),
+    };
+
+    // .xskill-meta.json takes precedence over the frontmatter
+    let meta = read_skill_meta(skill_path);
+    let original_url = meta.as_ref().and_then(|m| m.original_url.clone()).or_else(|| manifest.original_url.clone());
+    let remark = meta.as_ref().and_then(|m| m.remark.clone()).or_else(|| manifest.remark.clone());
+
+    ParsedSkill {
+        name: manifest.name.clone().unwrap_or_else(|| fallback_name.to_string()),
+        description: manifest.description.clone().map(|d| d.trim().to_string()).unwrap_or_default(),
+        content,
+        original_url,
+        remark,
+        parse_error,
+        manifest,
+    }
 }
 
 fn scan_project_skills_recursively(dir: &PathBuf, skills: &mut Vec<LocalSkill>, depth: usize) {
@@ -192,20 +196,8 @@ fn scan_project_skills_recursively(dir: &PathBuf, skills: &mut Vec<LocalSkill>,
             if let Some(md_path) = found_md {
                 // Found a skill!
                 if let Ok(raw) = fs::read_to_string(&md_path) {
-                    let (name, description, disable_model_invocation, allowed_tools, content, original_url, remark) =
-                        parse_skill_md(&raw, dir_name, &path);
-                    
-                    skills.push(LocalSkill {
-                        name,
-                        description,
-                        path: path.to_string_lossy().to_string(),
-                        tool_key: "project_local".to_string(),
-                        disable_model_invocation,
-                        allowed_tools,
-                        content,
-                        original_url,
-                        remark,
-                    });
+                    let parsed = parse_skill_md(&raw, dir_name, &path);
+                    skills.push(parsed.into_local_skill(path.to_string_lossy().to_string(), "project_local"));
                 }
                 // Don't recurse into a skill directory
                 continue; 
@@ -227,6 +219,10 @@ pub fn read_skills_from_dir(skills_dir: &PathBuf, tool_key: &str) -> Vec<LocalSk
 
     for entry in entries.flatten() {
         let path = entry.path();
+        if path.is_symlink() && !path.exists() {
+            // A link to a skill deleted from the Hub; `xskill sync --prune` removes it
+            continue;
+        }
         if path.is_dir() {
             // Check for SKILL.md
             let skill_md_path = path.join("SKILL.md");
@@ -236,19 +232,8 @@ pub fn read_skills_from_dir(skills_dir: &PathBuf, tool_key: &str) -> Vec<LocalSk
                         .and_then(|n| n.to_str())
                         .unwrap_or("unknown")
                         .to_string();
-                    let (name, description, disable_model_invocation, allowed_tools, content, original_url, remark) =
-                        parse_skill_md(&raw, &dir_name, &path);
-                    skills.push(LocalSkill {
-                        name,
-                        description,
-                        path: path.to_string_lossy().to_string(),
-                        tool_key: tool_key.to_string(),
-                        disable_model_invocation,
-                        allowed_tools,
-                        content,
-                        original_url,
-                        remark,
-                    });
+                    let parsed = parse_skill_md(&raw, &dir_name, &path);
+                    skills.push(parsed.into_local_skill(path.to_string_lossy().to_string(), tool_key));
                 }
             } else {
                 let dir_name = path.file_name()
@@ -264,37 +249,29 @@ pub fn read_skills_from_dir(skills_dir: &PathBuf, tool_key: &str) -> Vec<LocalSk
                         tool_key: tool_key.to_string(),
                         disable_model_invocation: false,
                         allowed_tools: vec![],
+                        tags: vec![],
                         content: String::new(),
                         original_url: None,
                         remark: None,
+                        parse_error: None,
+                        trust: Default::default(),
                     });
                 }
             }
         } else if path.is_file() {
             let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
-            if ext == "md" || path.to_string_lossy().ends_with(".prompt") {
+            if ext == "md" || ext == "mdc" || path.to_string_lossy().ends_with(".prompt") {
                 if let Ok(raw) = fs::read_to_string(&path) {
                     let file_stem = path.file_stem()
                         .and_then(|n| n.to_str())
                         .unwrap_or("unknown")
                         .to_string();
-                    let (name, description, disable_model_invocation, allowed_tools, content, original_url, remark) =
-                        parse_skill_md(&raw, &file_stem, &path);
+                    let parsed = parse_skill_md(&raw, &file_stem, &path);
 
                     let skill_path = path.parent()
                         .map(|p| p.to_string_lossy().to_string())
                         .unwrap_or_else(|| skills_dir.to_string_lossy().to_string());
-                    skills.push(LocalSkill {
-                        name,
-                        description,
-                        path: skill_path,
-                        tool_key: tool_key.to_string(),
-                        disable_model_invocation,
-                        allowed_tools,
-                        content,
-                        original_url,
-                        remark,
-                    });
+                    skills.push(parsed.into_local_skill(skill_path, tool_key));
                 }
             }
         }
@@ -303,120 +280,133 @@ pub fn read_skills_from_dir(skills_dir: &PathBuf, tool_key: &str) -> Vec<LocalSk
     skills
 }
 
-#[tauri::command]
-pub fn get_installed_tools() -> Result<Vec<Tool>, String> {
-    let home = home_dir()?;
-    let defs = tool_definitions();
-    let mut tools = Vec::new();
-
-    for def in &defs {
-        let detect_path = home.join(def.detect_subdir);
-        let skills_path = home.join(def.skills_subdir);
-        tools.push(Tool {
-            key: def.key.to_string(),
-            display_name: def.display_name.to_string(),
-            skills_dir: skills_path.to_string_lossy().to_string(),
-            installed: detect_path.exists(),
-        });
+pub const CENTRAL_SKILLS_DIR: &str = ".xskill/skills";
+
+impl Xskill {
+    /// Every known agent, with its global skills directory and whether it is installed.
+    pub fn tools(&self) -> Vec<Tool> {
+        self.registry()
+            .agents()
+            .iter()
+            .map(|def| Tool {
+                key: def.key.clone(),
+                display_name: def.display_name.clone(),
+                skills_dir: self.home().join(&def.skills_subdir).to_string_lossy().to_string(),
+                installed: self.home().join(&def.detect_subdir).exists(),
+            })
+            .collect()
     }
 
-    Ok(tools)
-}
+    pub fn skills_for_agent(&self, agent: &str) -> Result<Vec<LocalSkill>, XskillError> {
+        Ok(read_skills_from_dir(&self.agent_skills_dir(agent)?, agent))
+    }
 
-#[tauri::command]
-pub fn get_skills_for_tool(tool_key: String) -> Result<Vec<LocalSkill>, String> {
-    let defs = tool_definitions();
-    let def = defs
-        .iter()
-        .find(|d| d.key == tool_key)
-        .ok_or_else(|| format!("Unknown tool key: {}", tool_key))?;
-
-    let skills_dir = skills_dir_for_tool(def)?;
-    Ok(read_skills_from_dir(&skills_dir, &tool_key))
-}
+    pub fn hub_skills(&self) -> Vec<LocalSkill> {
+        read_skills_from_dir(&self.hub().to_path_buf(), "xskill")
+    }
 
-pub const CENTRAL_SKILLS_DIR: &str = ".xskill/skills";
+    /// Skills in the Hub and in every agent's global skills directory.
+    pub fn all_local_skills(&self) -> Vec<LocalSkill> {
+        let mut all_skills = self.hub_skills();
+        for def in self.registry().agents() {
+            let path = self.home().join(&def.skills_subdir);
+            if path.exists() {
+                all_skills.extend(read_skills_from_dir(&path, &def.key));
+            }
+        }
 
-#[tauri::command]
-pub fn delete_skill(path: String) -> Result<(), String> {
-    let path = PathBuf::from(path);
-    if !path.exists() {
-        return Err("Path does not exist".to_string());
-    }
-    if path.is_file() {
-        fs::remove_file(path).map_err(|e| e.to_string())?;
-    } else {
-        fs::remove_dir_all(path).map_err(|e| e.to_string())?;
+        // Deduplicate by path
+        all_skills.sort_by(|a, b| a.path.cmp(&b.path));
+        all_skills.dedup_by(|a, b| a.path == b.path);
+        all_skills
     }
-    Ok(())
-}
 
-#[tauri::command]
-pub fn get_project_skills(project_path: String) -> Result<Vec<LocalSkill>, String> {
-    let project_path_buf = PathBuf::from(&project_path);
-    if !project_path_buf.exists() {
-        return Err("Project path does not exist".to_string());
+    /// Skills in a project's agent directories plus any folder holding an AGENT.md or SKILL.md.
+    pub fn project_skills(&self, project: &Path) -> Result<Vec<LocalSkill>, XskillError> {
+        if !project.exists() {
+            return Err(XskillError::path_not_found(project));
+        }
+
+        let mut skills = Vec::new();
+        for def in self.registry().agents() {
+            // Construct path: project_path / tool_specific_subdir
+            let tool_skills_path = project.join(&def.skills_subdir);
+            if tool_skills_path.exists() {
+                skills.extend(read_skills_from_dir(&tool_skills_path, &def.key));
+            }
+        }
+
+        // 2. New logic: Recursively scan for AGENT.md / SKILL.md in the project
+        // This covers the user's requirement: "只要这个项目下某一个目录存在 AGENT.md，我就能读到"
+        scan_project_skills_recursively(&project.to_path_buf(), &mut skills, 0);
+
+        // Deduplicate by path
+        skills.sort_by(|a, b| a.path.cmp(&b.path));
+        skills.dedup_by(|a, b| a.path == b.path);
+        Ok(skills)
     }
 
-    let mut skills = Vec::new();
-    let defs = tool_definitions();
-
-    for def in defs {
-        // Construct path: project_path / tool_specific_subdir
-        let tool_skills_path = project_path_buf.join(def.skills_subdir);
-        if tool_skills_path.exists() {
-            let tool_skills = read_skills_from_dir(&tool_skills_path, def.key);
-            skills.extend(tool_skills);
+    /// Remove a skill directory (or single-file skill) from the Hub, an agent or a project.
+    pub fn delete_skill(&self, path: &Path) -> Result<(), XskillError> {
+        if !path.exists() && !path.is_symlink() {
+            return Err(XskillError::path_not_found(path));
         }
+        if path.is_symlink() {
+            // Directory links are removed with remove_dir on Windows
+            fs::remove_file(path).or_else(|_| fs::remove_dir(path)).map_err(|e| XskillError::io(path, e))?;
+        } else if path.is_file() {
+            fs::remove_file(path).map_err(|e| XskillError::io(path, e))?;
+        } else {
+            fs::remove_dir_all(path).map_err(|e| XskillError::io(path, e))?;
+        }
+        if path.starts_with(self.hub()) {
+            self.refresh_lock_quietly();
+        }
+        Ok(())
     }
-    
-    // 2. New logic: Recursively scan for AGENT.md / SKILL.md in the project
-    // This covers the user's requirement: "只要这个项目下某一个目录存在 AGENT.md，我就能读到"
-    scan_project_skills_recursively(&project_path_buf, &mut skills, 0);
 
-    // Deduplicate by path
-    skills.sort_by(|a, b| a.path.cmp(&b.path));
-    skills.dedup_by(|a, b| a.path == b.path);
-    
-    Ok(skills)
+    /// The skill whose directory name or SKILL.md `name` is `name`, in the Hub or in
+    /// `agent`'s global skills directory.
+    pub fn find_skill(&self, name: &str, agent: Option<&str>) -> Result<LocalSkill, XskillError> {
+        let skills = match agent {
+            None | Some("xskill") => self.hub_skills(),
+            Some(agent) => self.skills_for_agent(agent)?,
+        };
+        skills
+            .into_iter()
+            .find(|s| s.name == name || Path::new(&s.path).file_name().is_some_and(|n| n == name))
+            .ok_or_else(|| XskillError::SkillNotFound { skill: name.to_string() })
+    }
 }
 
 #[tauri::command]
-pub fn get_all_local_skills() -> Result<Vec<LocalSkill>, String> {
-    let mut all_skills = Vec::new();
-    let home = home_dir()?;
-
-    // 1. Read central skills
-    let central_path = home.join(CENTRAL_SKILLS_DIR);
-    if central_path.exists() {
-        all_skills.extend(read_skills_from_dir(&central_path, "xskill"));
-    }
+pub fn get_installed_tools() -> Result<Vec<Tool>, XskillError> {
+    Ok(Xskill::load()?.tools())
+}
 
-    // 2. Read skills from all other tools
-    let defs = tool_definitions();
-    for def in defs {
-        if let Ok(path) = skills_dir_for_tool(&def) {
-            if path.exists() {
-                // Read skills from tool directory
-                let tool_skills = read_skills_from_dir(&path, def.key);
-                all_skills.extend(tool_skills);
-            }
-        }
-    }
+#[tauri::command]
+pub fn get_skills_for_tool(tool_key: String) -> Result<Vec<LocalSkill>, XskillError> {
+    Xskill::load()?.skills_for_agent(&tool_key)
+}
+
+#[tauri::command]
+pub fn delete_skill(path: String) -> Result<(), XskillError> {
+    Xskill::load()?.delete_skill(Path::new(&path))
+}
 
-    // Deduplicate by path
-    all_skills.sort_by(|a, b| a.path.cmp(&b.path));
-    all_skills.dedup_by(|a, b| a.path == b.path);
+#[tauri::command]
+pub fn get_project_skills(project_path: String) -> Result<Vec<LocalSkill>, XskillError> {
+    Xskill::load()?.project_skills(Path::new(&project_path))
+}
 
-    Ok(all_skills)
+#[tauri::command]
+pub fn get_all_local_skills() -> Result<Vec<LocalSkill>, XskillError> {
+    Ok(Xskill::load()?.all_local_skills())
 }
 
-pub fn core_update_skill_metadata(path: &PathBuf, original_url: Option<String>, remark: Option<String>) -> Result<(), String> {
+pub fn core_update_skill_metadata(path: &Path, original_url: Option<String>, remark: Option<String>) -> Result<(), String> {
     // 1. Try to read existing meta
-    let mut meta = read_skill_meta(path).unwrap_or(SkillMeta {
-        original_url: None,
-        remark: None,
-    });
+    let mut meta = read_skill_meta(path).unwrap_or_default();
     
     // 2. Update fields if provided
     if original_url.is_some() {
@@ -433,6 +423,6 @@ pub fn core_update_skill_metadata(path: &PathBuf, original_url: Option<String>,
 }
 
 #[tauri::command]
-pub fn update_skill_metadata(path: String, original_url: Option<String>, remark: Option<String>) -> Result<(), String> {
-    core_update_skill_metadata(&PathBuf::from(path), original_url, remark)
+pub fn update_skill_metadata(path: String, original_url: Option<String>, remark: Option<String>) -> Result<(), XskillError> {
+    Ok(core_update_skill_metadata(&PathBuf::from(path), original_url, remark)?)
 }
@@ -1,10 +1,15 @@
+use crate::error::XskillError;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
 use tauri::AppHandle;
 use tauri_plugin_store::StoreExt;
 
 const STORE_PATH: &str = "xskill.json";
 const KEY_SKILLS: &str = "skills";
 const KEY_FEEDS: &str = "feeds";
+/// Must match `identifier` in tauri.conf.json; the store plugin keeps its file under it.
+const APP_IDENTIFIER: &str = "com.xskill.app";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Skill {
@@ -25,7 +30,7 @@ pub struct FeedEntry {
 }
 
 #[tauri::command]
-pub fn load_skills(app: AppHandle) -> Result<Vec<Skill>, String> {
+pub fn load_skills(app: AppHandle) -> Result<Vec<Skill>, XskillError> {
     let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
     let skills: Vec<Skill> = store
         .get(KEY_SKILLS)
@@ -35,14 +40,14 @@ pub fn load_skills(app: AppHandle) -> Result<Vec<Skill>, String> {
 }
 
 #[tauri::command]
-pub fn save_skills(app: AppHandle, skills: Vec<Skill>) -> Result<(), String> {
+pub fn save_skills(app: AppHandle, skills: Vec<Skill>) -> Result<(), XskillError> {
     let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
     store.set(KEY_SKILLS, serde_json::to_value(&skills).map_err(|e| e.to_string())?);
-    store.save().map_err(|e| e.to_string())
+    Ok(store.save().map_err(|e| e.to_string())?)
 }
 
 #[tauri::command]
-pub fn load_feeds(app: AppHandle) -> Result<Vec<FeedEntry>, String> {
+pub fn load_feeds(app: AppHandle) -> Result<Vec<FeedEntry>, XskillError> {
     let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
     let feeds: Vec<FeedEntry> = store
         .get(KEY_FEEDS)
@@ -52,8 +57,42 @@ pub fn load_feeds(app: AppHandle) -> Result<Vec<FeedEntry>, String> {
 }
 
 #[tauri::command]
-pub fn save_feeds(app: AppHandle, feeds: Vec<FeedEntry>) -> Result<(), String> {
+pub fn save_feeds(app: AppHandle, feeds: Vec<FeedEntry>) -> Result<(), XskillError> {
     let store = app.store(STORE_PATH).map_err(|e| e.to_string())?;
     store.set(KEY_FEEDS, serde_json::to_value(&feeds).map_err(|e| e.to_string())?);
-    store.save().map_err(|e| e.to_string())
+    Ok(store.save().map_err(|e| e.to_string())?)
+}
+
+/// Location of the plugin store file, for callers without an `AppHandle` such as the CLI.
+pub fn store_file_path() -> Result<PathBuf, String> {
+    let data_dir = crate::utils::get_app_data_dir().ok_or("Could not find app data directory")?;
+    Ok(data_dir.join(APP_IDENTIFIER).join(STORE_PATH))
+}
+
+fn read_store_file() -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let path = store_file_path()?;
+    if !path.exists() {
+        return Ok(serde_json::Map::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Feed list read straight from the store file (the app must not be running to rely on this).
+pub fn read_feeds_file() -> Result<Vec<FeedEntry>, String> {
+    Ok(read_store_file()?
+        .remove(KEY_FEEDS)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default())
+}
+
+pub fn write_feeds_file(feeds: &[FeedEntry]) -> Result<(), String> {
+    let path = store_file_path()?;
+    let mut store = read_store_file()?;
+    store.insert(KEY_FEEDS.to_string(), serde_json::to_value(feeds).map_err(|e| e.to_string())?);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(&store).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
 }
@@ -1,120 +1,149 @@
+use crate::error::XskillError;
+use crate::ide_sync::SyncOptions;
+use crate::plan::{Plan, PlannedOp};
 use crate::suite_manager::Suite;
-use crate::skill_manager::{CENTRAL_SKILLS_DIR, tool_definitions};
+use crate::xskill::Xskill;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[tauri::command]
-pub fn apply_suite(project_path: String, suite: Suite, agent: Option<String>, mode: Option<String>) -> Result<(), String> {
-    let proj_dir = PathBuf::from(&project_path);
-    if !proj_dir.exists() || !proj_dir.is_dir() {
-        return Err(format!("Invalid project directory: {}", project_path));
-    }
+impl Xskill {
+    /// Write the suite's AGENTS.md and loadout skills into a project, placing the skills in
+    /// `agent`'s project skills directory (Cursor's by default).
+    ///
+    /// Returns the filesystem operations performed; with `dry_run` they are only planned.
+    pub fn apply_suite(&self, project: &Path, suite: &Suite, agent: Option<&str>, link: bool, dry_run: bool) -> Result<Vec<PlannedOp>, XskillError> {
+        let mut plan = Plan::new(dry_run);
+        if !project.exists() || !project.is_dir() {
+            return Err(XskillError::path_not_found(project));
+        }
+        // The sync policy covers project copies too; check every skill before writing anything
+        for src_skill_dir in suite.loadout_skills.iter().filter_map(|id| self.resolve_hub_skill(id)) {
+            self.check_sync_allowed(&src_skill_dir)?;
+        }
 
-    // Write AGENTS.md
-    if !suite.policy_rules.trim().is_empty() {
-        let agents_md_path = proj_dir.join("AGENTS.md");
-        fs::write(&agents_md_path, &suite.policy_rules)
-            .map_err(|e| format!("Failed to write AGENTS.md: {}", e))?;
-    }
+        // Write AGENTS.md
+        if !suite.policy_rules.trim().is_empty() {
+            let agents_md_path = project.join("AGENTS.md");
+            plan.write_agents_md(&agents_md_path, &suite.policy_rules)?;
+        }
 
-    // Sync skills to agent-specific skills directory
-    if !suite.loadout_skills.is_empty() {
-        let agent_key = agent.unwrap_or_else(|| "cursor".to_string());
-        
-        let defs = tool_definitions();
-        let target_subdir = defs.iter()
-            .find(|d| d.key == agent_key)
-            .map(|d| d.skills_subdir)
-            .unwrap_or(".cursor/skills"); // Default fallback
+        // Sync skills to agent-specific skills directory
+        if !suite.loadout_skills.is_empty() {
+            let agent_key = agent.unwrap_or("cursor");
 
-        let target_skills_dir = proj_dir.join(target_subdir);
-        
-        // Only create the parent directory of target_skills_dir if we are actually copying skills
-        // But here we might want to create the skills dir itself.
-        // Wait, if target_subdir is ".claude/skills", we want project/.claude/skills to exist.
-        if !target_skills_dir.exists() {
-            fs::create_dir_all(&target_skills_dir)
+            let target_skills_dir = self.registry().skills_dir(agent_key, project)
+                .unwrap_or_else(|| project.join(".cursor/skills")); // Default fallback
+
+            // Only create the parent directory of target_skills_dir if we are actually copying skills
+            // But here we might want to create the skills dir itself.
+            // Wait, if target_subdir is ".claude/skills", we want project/.claude/skills to exist.
+            plan.create_dir(&target_skills_dir)
                 .map_err(|e| format!("Failed to create skills directory {}: {}", target_skills_dir.display(), e))?;
-        }
 
-        let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
-        let central_skills_dir = home.join(CENTRAL_SKILLS_DIR);
+            for skill_id in &suite.loadout_skills {
+                let Some(src_skill_dir) = self.resolve_hub_skill(skill_id) else {
+                    eprintln!("Source skill not found: {} (checked {:?})", skill_id, self.hub().join(skill_id));
+                    continue;
+                };
+                let dir_name = src_skill_dir.file_name().unwrap_or_default();
+                let dest_skill_dir = target_skills_dir.join(dir_name);
 
-        for skill_id in suite.loadout_skills {
-            // skill_id might be "My Skill", but the directory is "my-skill". 
-            // We need to resolve the correct directory name from the skill ID/Name.
-            // Since suite.loadout_skills currently stores names, we should try to find the directory.
-            
-            // Try direct match first
-            let mut src_skill_dir = central_skills_dir.join(&skill_id);
-            
-            if !src_skill_dir.exists() {
-                // Try finding by SKILL.md name content if directory doesn't match
-                // Or maybe the skill_id passed from frontend IS the directory name?
-                // In SuitesPage.tsx: const skillId = skill.name; -> skill.name from get_all_local_skills
-                // In skill_manager.rs: LocalSkill.name comes from SKILL.md "name" field.
-                // But the directory name might be different.
-                
-                // We need to scan Hub to find the directory that contains the skill with this name.
-                if let Ok(entries) = fs::read_dir(&central_skills_dir) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_dir() {
-                            // Check SKILL.md
-                            if let Ok(content) = fs::read_to_string(path.join("SKILL.md")) {
-                                if content.contains(&format!("name: {}", skill_id)) || 
-                                   content.contains(&format!("name: \"{}\"", skill_id)) {
-                                    src_skill_dir = path;
-                                    break;
-                                }
-                            }
+                // Agents with their own rule format get a rendered file, linked or not
+                match crate::render::render_skill(self.agent_format(agent_key), &src_skill_dir) {
+                    Ok(Some((file, content))) => {
+                        if let Err(e) = plan.write_file(&target_skills_dir.join(file), content.as_bytes()) {
+                            eprintln!("Failed to write skill {}: {}", skill_id, e);
                         }
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("Failed to render skill {}: {}", skill_id, e);
+                        continue;
                     }
                 }
-            }
-
-            if src_skill_dir.exists() && src_skill_dir.is_dir() {
-                let dir_name = src_skill_dir.file_name().unwrap_or_default();
-                let dest_skill_dir = target_skills_dir.join(dir_name);
-                
-                println!("Applying skill: {:?} -> {:?}", src_skill_dir, dest_skill_dir);
 
-                if mode.as_deref() == Some("link") {
-                    if let Err(e) = crate::utils::symlink_dir(&src_skill_dir, &dest_skill_dir) {
+                if link {
+                    if let Err(e) = plan.symlink_dir(&src_skill_dir, &dest_skill_dir) {
                         eprintln!("Failed to link skill {}: {}", skill_id, e);
                     }
-                } else {
-                    if let Err(e) = crate::utils::copy_dir_all(&src_skill_dir, &dest_skill_dir) {
-                        eprintln!("Failed to copy skill {}: {}", skill_id, e);
-                        // Cleanup on failure if we created an empty directory
-                        if dest_skill_dir.exists() {
-                            let _ = fs::remove_dir_all(&dest_skill_dir);
-                        }
+                } else if let Err(e) = plan.copy_dir(&src_skill_dir, &dest_skill_dir) {
+                    eprintln!("Failed to copy skill {}: {}", skill_id, e);
+                    // Cleanup on failure if we created an empty directory
+                    if dest_skill_dir.exists() {
+                        let _ = fs::remove_dir_all(&dest_skill_dir);
                     }
                 }
-            } else {
-                eprintln!("Source skill not found: {} (checked {:?})", skill_id, src_skill_dir);
             }
         }
+
+        Ok(plan.into_ops())
     }
 
-    Ok(())
-}
+    /// Sync the suite's loadout skills from the Hub to `agent`'s global skills directory.
+    pub fn apply_suite_to_agent(&self, suite: &Suite, agent: &str, options: &SyncOptions) -> Result<Vec<PlannedOp>, XskillError> {
+        let mut ops = Vec::new();
+        for skill_id in &suite.loadout_skills {
+            let src_skill_dir = self.hub().join(skill_id);
+            if src_skill_dir.exists() {
+                let report = self.sync_skill(&src_skill_dir, &[agent.to_string()], options)?;
+                ops.extend(report.operations);
+            }
+        }
+        Ok(ops)
+    }
 
-#[tauri::command]
-pub fn apply_suite_to_agent(suite: Suite, agent: String, mode: Option<String>) -> Result<(), String> {
-    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
-    let central_skills_dir = home.join(CENTRAL_SKILLS_DIR);
-    
-    for skill_id in suite.loadout_skills {
-        let src_skill_dir = central_skills_dir.join(&skill_id);
-        if src_skill_dir.exists() {
-             crate::ide_sync::sync_skill(
-                 src_skill_dir.to_string_lossy().to_string(),
-                 vec![agent.clone()],
-                 mode.clone()
-             ).map_err(|e| format!("Failed to sync skill {}: {}", skill_id, e))?;
+    /// Hub directory of a loadout entry, which is a directory name or a SKILL.md `name`.
+    fn resolve_hub_skill(&self, skill_id: &str) -> Option<PathBuf> {
+        // Try direct match first
+        let direct = self.hub().join(skill_id);
+        if direct.is_dir() {
+            return Some(direct);
         }
+
+        // In SuitesPage.tsx: const skillId = skill.name; -> skill.name from get_all_local_skills
+        // In skill_manager.rs: LocalSkill.name comes from SKILL.md "name" field.
+        // But the directory name might be different, so scan the Hub for a matching SKILL.md.
+        fs::read_dir(self.hub()).ok()?.flatten().map(|entry| entry.path()).find(|path| {
+            path.is_dir()
+                && fs::read_to_string(path.join("SKILL.md")).is_ok_and(|content| {
+                    content.contains(&format!("name: {}", skill_id)) || content.contains(&format!("name: \"{}\"", skill_id))
+                })
+        })
     }
-    Ok(())
+}
+
+/// Write the suite's AGENTS.md and loadout skills into a project.
+///
+/// Returns the filesystem operations performed; with `dry_run` they are only planned.
+#[tauri::command]
+pub fn apply_suite(
+    project_path: String,
+    suite: Suite,
+    agent: Option<String>,
+    mode: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<PlannedOp>, XskillError> {
+    Xskill::load()?.apply_suite(
+        Path::new(&project_path),
+        &suite,
+        agent.as_deref(),
+        mode.as_deref() == Some("link"),
+        dry_run.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub fn apply_suite_to_agent(
+    suite: Suite,
+    agent: String,
+    mode: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<Vec<PlannedOp>, XskillError> {
+    let options = SyncOptions {
+        link: mode.as_deref() == Some("link"),
+        dry_run: dry_run.unwrap_or(false),
+        ..Default::default()
+    };
+    Xskill::load()?.apply_suite_to_agent(&suite, &agent, &options)
 }
@@ -1,6 +1,8 @@
+use crate::error::XskillError;
+use crate::xskill::Xskill;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 const SUITES_FILE: &str = "suites.json";
 
@@ -13,30 +15,39 @@ pub struct Suite {
     pub loadout_skills: Vec<String>, // List of skill IDs or names
 }
 
-fn get_suites_path() -> Result<PathBuf, String> {
-    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+fn suites_path_in(home: &Path) -> Result<PathBuf, XskillError> {
     let config_dir = home.join(".xskill");
     if !config_dir.exists() {
-        fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+        fs::create_dir_all(&config_dir).map_err(|e| XskillError::io(&config_dir, e))?;
     }
     Ok(config_dir.join(SUITES_FILE))
 }
 
-#[tauri::command]
-pub fn load_suites() -> Result<Vec<Suite>, String> {
-    let path = get_suites_path()?;
-    if !path.exists() {
-        return Ok(Vec::new());
+impl Xskill {
+    pub fn load_suites(&self) -> Result<Vec<Suite>, XskillError> {
+        let path = suites_path_in(self.home())?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&path).map_err(|e| XskillError::io(&path, e))?;
+        serde_json::from_str(&content).map_err(|e| XskillError::Config { path, message: e.to_string() })
+    }
+
+    pub fn save_suites(&self, suites: &[Suite]) -> Result<(), XskillError> {
+        let path = suites_path_in(self.home())?;
+        let content = serde_json::to_string_pretty(suites).map_err(|e| e.to_string())?;
+        fs::write(&path, content).map_err(|e| XskillError::io(&path, e))
     }
-    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    serde_json::from_str(&content).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn save_suites(suites: Vec<Suite>) -> Result<(), String> {
-    let path = get_suites_path()?;
-    let content = serde_json::to_string_pretty(&suites).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())
+pub fn load_suites() -> Result<Vec<Suite>, XskillError> {
+    Xskill::load()?.load_suites()
+}
+
+#[tauri::command]
+pub fn save_suites(suites: Vec<Suite>) -> Result<(), XskillError> {
+    Xskill::load()?.save_suites(&suites)
 }
 
 #[cfg(test)]
@@ -0,0 +1,249 @@
+use crate::error::XskillError;
+use crate::fingerprint::calculate_dir_hash;
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LEDGER_FILE: &str = "sync-ledger.json";
+const LEDGER_VERSION: u32 = 1;
+
+/// One skill delivered to one agent directory by `sync_skill`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub skill: String,
+    pub agent: String,
+    /// `"copy"`, `"link"` or `"render"` (a file generated for the agent's format)
+    pub mode: String,
+    pub source_path: String,
+    pub dest_path: String,
+    /// Hub fingerprint at the time of the sync.
+    pub source_fingerprint: String,
+    /// Fingerprint of the agent copy right after the sync (equal to the source for links).
+    pub dest_fingerprint: String,
+    /// Unix timestamp (seconds).
+    pub synced_at: u64,
+}
+
+/// Persisted record of what was synced where, stored in `~/.xskill/sync-ledger.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncLedger {
+    pub version: u32,
+    pub entries: Vec<LedgerEntry>,
+}
+
+impl Default for SyncLedger {
+    fn default() -> Self {
+        Self { version: LEDGER_VERSION, entries: Vec::new() }
+    }
+}
+
+fn get_ledger_path() -> Result<PathBuf, XskillError> {
+    let home = crate::utils::get_home_dir().ok_or_else(|| XskillError::from("Could not find home directory"))?;
+    ledger_path_in(&home)
+}
+
+fn ledger_path_in(home: &Path) -> Result<PathBuf, XskillError> {
+    let config_dir = home.join(".xskill");
+    if !config_dir.exists() {
+        fs::create_dir_all(&config_dir).map_err(|e| XskillError::io(&config_dir, e))?;
+    }
+    Ok(config_dir.join(LEDGER_FILE))
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+impl SyncLedger {
+    pub fn load() -> Result<Self, XskillError> {
+        Self::load_from(&get_ledger_path()?)
+    }
+
+    pub fn load_from_home(home: &Path) -> Result<Self, XskillError> {
+        Self::load_from(&ledger_path_in(home)?)
+    }
+
+    /// A ledger that cannot be parsed is an integrity error, never silently replaced: it is
+    /// the only record of which agent copies xskill owns.
+    fn load_from(path: &Path) -> Result<Self, XskillError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).map_err(|e| XskillError::io(path, e))?;
+        serde_json::from_str(&content).map_err(|e| XskillError::integrity(format!(
+            "Sync ledger {} is corrupt ({}). Fix it or move it aside to start a new one", path.display(), e
+        )))
+    }
+
+    pub fn save(&self) -> Result<(), XskillError> {
+        self.save_to(&get_ledger_path()?)
+    }
+
+    pub fn save_to_home(&self, home: &Path) -> Result<(), XskillError> {
+        self.save_to(&ledger_path_in(home)?)
+    }
+
+    /// Written atomically: a ledger cut short by a crash would stop every later sync.
+    fn save_to(&self, path: &Path) -> Result<(), XskillError> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        crate::utils::write_atomic(path, content.as_bytes())
+    }
+
+    /// Insert or replace the entry for `entry.dest_path`.
+    pub fn record(&mut self, entry: LedgerEntry) {
+        self.entries.retain(|e| e.dest_path != entry.dest_path);
+        self.entries.push(entry);
+    }
+
+    pub fn remove(&mut self, dest_path: &str) -> Option<LedgerEntry> {
+        let pos = self.entries.iter().position(|e| e.dest_path == dest_path)?;
+        Some(self.entries.remove(pos))
+    }
+
+    pub fn find(&self, dest_path: &str) -> Option<&LedgerEntry> {
+        self.entries.iter().find(|e| e.dest_path == dest_path)
+    }
+}
+
+/// Build a ledger entry for a destination that was just written.
+pub fn entry_for(skill: &str, agent: &str, mode: &str, src: &Path, dest: &Path) -> Result<LedgerEntry, XskillError> {
+    let source_fingerprint = calculate_dir_hash(src)?;
+    let dest_fingerprint = if mode == "link" {
+        source_fingerprint.clone()
+    } else {
+        calculate_dir_hash(dest)?
+    };
+    Ok(LedgerEntry {
+        skill: skill.to_string(),
+        agent: agent.to_string(),
+        mode: mode.to_string(),
+        source_path: src.to_string_lossy().to_string(),
+        dest_path: dest.to_string_lossy().to_string(),
+        source_fingerprint,
+        dest_fingerprint,
+        synced_at: now_secs(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftState {
+    /// Agent copy matches what was synced and the Hub has not changed.
+    InSync,
+    /// The Hub skill changed since the last sync.
+    Stale,
+    /// The agent copy was edited in place.
+    LocallyModified,
+    /// Both the Hub skill and the agent copy changed.
+    Diverged,
+    /// The Hub skill no longer exists but the agent copy does.
+    Orphaned,
+    /// The agent copy was removed.
+    Missing,
+    /// An agent holds a copy named like a Hub skill that xskill never synced.
+    Untracked,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncStatus {
+    #[serde(flatten)]
+    pub entry: LedgerEntry,
+    pub state: DriftState,
+}
+
+/// Compare a ledger entry against what is on disk now.
+pub fn classify(entry: &LedgerEntry) -> DriftState {
+    let src = Path::new(&entry.source_path);
+    let dest = Path::new(&entry.dest_path);
+
+    if !dest.exists() && !dest.is_symlink() {
+        return DriftState::Missing;
+    }
+    if !src.exists() {
+        return DriftState::Orphaned;
+    }
+
+    if entry.mode == "link" {
+        // A link always reflects the Hub; it only drifts if someone replaced it
+        let points_to_src = match (fs::canonicalize(src), fs::canonicalize(dest)) {
+            (Ok(s), Ok(d)) => s == d,
+            _ => false,
+        };
+        return if dest.is_symlink() && points_to_src {
+            DriftState::InSync
+        } else {
+            DriftState::LocallyModified
+        };
+    }
+
+    let hub_changed = calculate_dir_hash(src).map(|h| h != entry.source_fingerprint).unwrap_or(true);
+    let local_changed = calculate_dir_hash(dest).map(|h| h != entry.dest_fingerprint).unwrap_or(true);
+    match (hub_changed, local_changed) {
+        (false, false) => DriftState::InSync,
+        (true, false) => DriftState::Stale,
+        (false, true) => DriftState::LocallyModified,
+        (true, true) => DriftState::Diverged,
+    }
+}
+
+pub fn compute_status() -> Result<Vec<SyncStatus>, XskillError> {
+    Xskill::load()?.sync_status()
+}
+
+impl Xskill {
+    /// Drift report for every skill xskill has synced to an agent, plus copies of Hub skills
+    /// in agent directories that the ledger does not know about.
+    pub fn sync_status(&self) -> Result<Vec<SyncStatus>, XskillError> {
+        let ledger = SyncLedger::load_from_home(self.home())?;
+        let mut statuses = self.untracked_copies(&ledger);
+        statuses.extend(ledger.entries.into_iter().map(|entry| {
+            let state = classify(&entry);
+            SyncStatus { entry, state }
+        }));
+        statuses.sort_by(|a, b| (&a.entry.agent, &a.entry.skill).cmp(&(&b.entry.agent, &b.entry.skill)));
+        Ok(statuses)
+    }
+
+    /// Agent copies where `sync_skill` would put a Hub skill, with no ledger entry.
+    fn untracked_copies(&self, ledger: &SyncLedger) -> Vec<SyncStatus> {
+        let mut statuses = Vec::new();
+        for skill in self.hub_skills() {
+            let src = Path::new(&skill.path);
+            let Some(dir_name) = src.file_name().map(|n| n.to_string_lossy().to_string()) else { continue };
+            for agent in self.registry().agents() {
+                let Ok(dest) = self.sync_dest(&agent.key, &dir_name) else { continue };
+                let dest_path = dest.to_string_lossy().to_string();
+                if !(dest.exists() || dest.is_symlink()) || ledger.find(&dest_path).is_some() {
+                    continue;
+                }
+                let mode = if dest.is_symlink() {
+                    "link"
+                } else if self.agent_format(&agent.key).renderer().is_some() {
+                    "render"
+                } else {
+                    "copy"
+                };
+                let entry = LedgerEntry {
+                    skill: dir_name.clone(),
+                    agent: agent.key.clone(),
+                    mode: mode.to_string(),
+                    source_path: skill.path.clone(),
+                    dest_path,
+                    source_fingerprint: calculate_dir_hash(src).unwrap_or_default(),
+                    dest_fingerprint: calculate_dir_hash(&dest).unwrap_or_default(),
+                    synced_at: 0,
+                };
+                statuses.push(SyncStatus { entry, state: DriftState::Untracked });
+            }
+        }
+        statuses
+    }
+}
+
+/// Drift report for every skill xskill has synced to an agent.
+#[tauri::command]
+pub fn get_sync_status() -> Result<Vec<SyncStatus>, XskillError> {
+    Xskill::load()?.sync_status()
+}
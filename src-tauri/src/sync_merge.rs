@@ -0,0 +1,397 @@
+use crate::error::XskillError;
+use crate::plan::Plan;
+use crate::utils::list_files;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Where the last-synced snapshot of every copied skill is kept, used as the merge base.
+pub const SYNC_BASE_DIR: &str = ".xskill/sync-base";
+
+/// How to handle a file the user edited inside an agent's skill directory.
+///
+/// "Ours" is the Hub version being pushed, "theirs" is the agent copy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Three-way merge Hub and agent changes against the last synced base.
+    #[default]
+    Merge,
+    /// Keep the agent's edited file.
+    KeepTheirs,
+    /// Overwrite the agent's file with the Hub version.
+    KeepOurs,
+    /// Leave the agent directory untouched if it has any local edits.
+    Abort,
+}
+
+impl FromStr for ConflictStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.replace('-', "_").as_str() {
+            "merge" => Ok(Self::Merge),
+            "keep_theirs" => Ok(Self::KeepTheirs),
+            "keep_ours" => Ok(Self::KeepOurs),
+            "abort" => Ok(Self::Abort),
+            _ => Err(format!("Unknown conflict strategy '{}' (expected merge, keep-theirs, keep-ours or abort)", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resolution {
+    /// Hub and agent edits were combined cleanly.
+    Merged,
+    /// The Hub version replaced the agent edit.
+    KeptOurs,
+    /// The agent edit was kept.
+    KeptTheirs,
+    /// Could not be merged; the agent file was left as is and will be reported again next sync.
+    Unresolved,
+    /// The whole agent directory was left untouched.
+    Aborted,
+}
+
+/// A file that was edited in an agent directory since the last sync.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub agent: String,
+    pub dest_path: String,
+    /// Path relative to the skill directory.
+    pub file: String,
+    /// Whether the Hub also changed this file since the last sync.
+    pub hub_changed: bool,
+    pub resolution: Resolution,
+}
+
+/// Snapshot directory holding the base for `dest`.
+pub fn base_dir_for(home: &Path, dest: &Path) -> PathBuf {
+    let digest = Sha256::digest(dest.to_string_lossy().as_bytes());
+    home.join(SYNC_BASE_DIR).join(&format!("{:x}", digest)[..16])
+}
+
+enum Action {
+    Write(Vec<u8>),
+    Delete,
+    Keep,
+}
+
+struct FilePlan {
+    rel: PathBuf,
+    action: Action,
+    /// New base content for this file; `None` removes it from the base.
+    base: Option<Vec<u8>>,
+}
+
+pub struct MergeOutcome {
+    pub conflicts: Vec<SyncConflict>,
+    /// False if the sync was aborted and nothing was written.
+    pub applied: bool,
+}
+
+fn read(map: &std::collections::BTreeMap<PathBuf, PathBuf>, rel: &Path) -> Result<Option<Vec<u8>>, XskillError> {
+    match map.get(rel) {
+        Some(p) => fs::read(p).map(Some).map_err(|e| XskillError::io(p, e)),
+        None => Ok(None),
+    }
+}
+
+/// Three-way merge of text files; `None` when the inputs are not UTF-8 or the merge conflicts.
+fn merge_text(base: Option<&[u8]>, ours: &[u8], theirs: &[u8]) -> Option<Vec<u8>> {
+    let base = std::str::from_utf8(base.unwrap_or(b"")).ok()?;
+    let ours = std::str::from_utf8(ours).ok()?;
+    let theirs = std::str::from_utf8(theirs).ok()?;
+    diffy::merge(base, ours, theirs).ok().map(String::into_bytes)
+}
+
+/// Files in `dest` that differ from the base snapshot (added, edited or removed).
+pub fn local_edits(dest: &Path, base: &Path) -> Result<Vec<String>, XskillError> {
+    let dest_files = list_files(dest)?;
+    let base_files = list_files(base)?;
+    let mut edited = Vec::new();
+    for rel in dest_files.keys().chain(base_files.keys()).collect::<BTreeSet<_>>() {
+        if read(&dest_files, rel)? != read(&base_files, rel)? {
+            edited.push(rel.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(edited)
+}
+
+/// Push `src` (Hub) onto `dest` (agent copy) using `base` (last synced snapshot) to
+/// tell agent edits apart from Hub changes, then refresh the snapshot.
+///
+/// `resolutions` overrides `strategy` for individual files, keyed by relative path.
+/// Writes go through `plan`, so in dry-run mode only the intended changes are recorded.
+pub fn merge_into(
+    agent: &str,
+    src: &Path,
+    dest: &Path,
+    base: &Path,
+    strategy: ConflictStrategy,
+    resolutions: &HashMap<String, ConflictStrategy>,
+    plan: &mut Plan,
+) -> Result<MergeOutcome, XskillError> {
+    let src_files = list_files(src)?;
+    let dest_files = list_files(dest)?;
+    let base_files = list_files(base)?;
+
+    let all: BTreeSet<&PathBuf> = src_files.keys().chain(dest_files.keys()).chain(base_files.keys()).collect();
+
+    let mut files = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut abort = false;
+
+    for rel in all {
+        let ours = read(&src_files, rel)?;
+        let theirs = read(&dest_files, rel)?;
+        let ancestor = read(&base_files, rel)?;
+
+        let local_changed = theirs != ancestor;
+        let hub_changed = ours != ancestor;
+
+        if !local_changed {
+            let action = match &ours {
+                Some(_) if ours == theirs => Action::Keep,
+                Some(content) => Action::Write(content.clone()),
+                None => Action::Delete,
+            };
+            files.push(FilePlan { rel: rel.clone(), action, base: ours });
+            continue;
+        }
+
+        let rel_str = rel.to_string_lossy().replace('\\', "/");
+        let file_strategy = resolutions.get(&rel_str).copied().unwrap_or(strategy);
+
+        // Same edit on both sides is not a conflict
+        if hub_changed && ours == theirs {
+            files.push(FilePlan { rel: rel.clone(), action: Action::Keep, base: ours });
+            continue;
+        }
+
+        let (action, new_base, resolution) = match file_strategy {
+            ConflictStrategy::Abort => {
+                abort = true;
+                (Action::Keep, ancestor.clone(), Resolution::Aborted)
+            }
+            ConflictStrategy::KeepTheirs => (Action::Keep, ours.clone(), Resolution::KeptTheirs),
+            ConflictStrategy::KeepOurs => {
+                let action = match &ours {
+                    Some(content) => Action::Write(content.clone()),
+                    None => Action::Delete,
+                };
+                (action, ours.clone(), Resolution::KeptOurs)
+            }
+            ConflictStrategy::Merge => {
+                if !hub_changed {
+                    // Only the agent edited it: nothing to merge
+                    (Action::Keep, ours.clone(), Resolution::Merged)
+                } else {
+                    match (&ours, &theirs) {
+                        (Some(o), Some(t)) => match merge_text(ancestor.as_deref(), o, t) {
+                            Some(merged) => (Action::Write(merged), ours.clone(), Resolution::Merged),
+                            // Keep the old base so the conflict is reported again next time
+                            None => (Action::Keep, ancestor.clone(), Resolution::Unresolved),
+                        },
+                        // Deleted on one side, edited on the other
+                        _ => (Action::Keep, ancestor.clone(), Resolution::Unresolved),
+                    }
+                }
+            }
+        };
+
+        conflicts.push(SyncConflict {
+            agent: agent.to_string(),
+            dest_path: dest.to_string_lossy().to_string(),
+            file: rel_str,
+            hub_changed,
+            resolution,
+        });
+        files.push(FilePlan { rel: rel.clone(), action, base: new_base });
+    }
+
+    if abort {
+        for c in conflicts.iter_mut() {
+            c.resolution = Resolution::Aborted;
+        }
+        return Ok(MergeOutcome { conflicts, applied: false });
+    }
+
+    for item in &files {
+        let target = dest.join(&item.rel);
+        match &item.action {
+            Action::Write(content) => plan.write_file(&target, content)?,
+            Action::Delete => plan.delete_file(&target)?,
+            Action::Keep => {}
+        }
+    }
+
+    if plan.dry_run {
+        return Ok(MergeOutcome { conflicts, applied: true });
+    }
+    write_base(base, files.iter().map(|p| (p.rel.as_path(), p.base.as_deref())))?;
+
+    Ok(MergeOutcome { conflicts, applied: true })
+}
+
+/// [`merge_into`] for an agent that takes a single rendered file: `rendered` is the Hub
+/// side, `dest` the agent's file and `base/<file>` what was rendered at the last sync.
+///
+/// A `dest` with no base was not written by xskill, so it counts as a local edit.
+pub fn merge_file(
+    agent: &str,
+    file: &str,
+    rendered: &[u8],
+    dest: &Path,
+    base: &Path,
+    strategy: ConflictStrategy,
+    plan: &mut Plan,
+) -> Result<MergeOutcome, XskillError> {
+    let theirs = if dest.is_file() { Some(fs::read(dest).map_err(|e| XskillError::io(dest, e))?) } else { None };
+    let ancestor = fs::read(base.join(file)).ok();
+    let ours = rendered.to_vec();
+
+    let mut conflicts = Vec::new();
+    let (write, new_base) = match &theirs {
+        None => (Some(ours.clone()), Some(ours.clone())),
+        Some(t) if *t == ours => (None, Some(ours.clone())),
+        Some(t) if Some(t) == ancestor.as_ref() => (Some(ours.clone()), Some(ours.clone())),
+        Some(t) => {
+            let hub_changed = Some(&ours) != ancestor.as_ref();
+            let (write, new_base, resolution) = match strategy {
+                ConflictStrategy::Abort => (None, None, Resolution::Aborted),
+                ConflictStrategy::KeepTheirs => (None, Some(ours.clone()), Resolution::KeptTheirs),
+                ConflictStrategy::KeepOurs => (Some(ours.clone()), Some(ours.clone()), Resolution::KeptOurs),
+                ConflictStrategy::Merge if !hub_changed => (None, Some(ours.clone()), Resolution::Merged),
+                ConflictStrategy::Merge => match merge_text(ancestor.as_deref(), &ours, t) {
+                    Some(merged) => (Some(merged), Some(ours.clone()), Resolution::Merged),
+                    None => (None, ancestor.clone(), Resolution::Unresolved),
+                },
+            };
+            conflicts.push(SyncConflict {
+                agent: agent.to_string(),
+                dest_path: dest.to_string_lossy().to_string(),
+                file: file.to_string(),
+                hub_changed,
+                resolution,
+            });
+            if resolution == Resolution::Aborted {
+                return Ok(MergeOutcome { conflicts, applied: false });
+            }
+            (write, new_base)
+        }
+    };
+
+    if let Some(content) = write {
+        plan.write_file(dest, &content)?;
+    }
+    if !plan.dry_run {
+        write_base(base, std::iter::once((Path::new(file), new_base.as_deref())))?;
+    }
+    Ok(MergeOutcome { conflicts, applied: true })
+}
+
+fn write_base<'a, I>(base: &Path, files: I) -> Result<(), XskillError>
+where
+    I: Iterator<Item = (&'a Path, Option<&'a [u8]>)>,
+{
+    if base.exists() {
+        fs::remove_dir_all(base).map_err(|e| XskillError::io(base, e))?;
+    }
+    fs::create_dir_all(base).map_err(|e| XskillError::io(base, e))?;
+    for (rel, content) in files {
+        if let Some(content) = content {
+            let target = base.join(rel);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+            }
+            fs::write(&target, content).map_err(|e| XskillError::io(&target, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Record `src` as the new merge base after a plain copy.
+pub fn snapshot_base(src: &Path, base: &Path) -> Result<(), XskillError> {
+    crate::utils::copy_dir_all(&src.to_path_buf(), &base.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup(files: &[(&str, &str, &str, &str)]) -> (TempDir, PathBuf, PathBuf, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let (src, dest, base) = (temp_dir.path().join("src"), temp_dir.path().join("dest"), temp_dir.path().join("base"));
+        for dir in [&src, &dest, &base] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        for (name, b, s, d) in files {
+            for (dir, content) in [(&base, b), (&src, s), (&dest, d)] {
+                if !content.is_empty() {
+                    fs::write(dir.join(name), content).unwrap();
+                }
+            }
+        }
+        (temp_dir, src, dest, base)
+    }
+
+    #[test]
+    fn test_merge_combines_non_overlapping_edits() {
+        let (_t, src, dest, base) = setup(&[
+            ("SKILL.md", "a\nb\nc\n", "A\nb\nc\n", "a\nb\nC\n"),
+            ("hub-only.md", "1\n", "2\n", "1\n"),
+            ("agent-only.md", "1\n", "1\n", "3\n"),
+        ]);
+
+        let outcome = merge_into("cursor", &src, &dest, &base, ConflictStrategy::Merge, &HashMap::new(), &mut Plan::new(false)).unwrap();
+        assert!(outcome.applied);
+        assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), "A\nb\nC\n");
+        assert_eq!(fs::read_to_string(dest.join("hub-only.md")).unwrap(), "2\n");
+        assert_eq!(fs::read_to_string(dest.join("agent-only.md")).unwrap(), "3\n");
+        assert!(outcome.conflicts.iter().all(|c| c.resolution == Resolution::Merged));
+        assert_eq!(fs::read_to_string(base.join("SKILL.md")).unwrap(), "A\nb\nc\n");
+    }
+
+    #[test]
+    fn test_conflicting_edit_is_unresolved_and_kept() {
+        let (_t, src, dest, base) = setup(&[("SKILL.md", "a\n", "hub\n", "agent\n")]);
+
+        let outcome = merge_into("cursor", &src, &dest, &base, ConflictStrategy::Merge, &HashMap::new(), &mut Plan::new(false)).unwrap();
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(outcome.conflicts[0].resolution, Resolution::Unresolved);
+        assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), "agent\n");
+        assert_eq!(fs::read_to_string(base.join("SKILL.md")).unwrap(), "a\n", "Base is kept so the conflict resurfaces");
+
+        let mut per_file = HashMap::new();
+        per_file.insert("SKILL.md".to_string(), ConflictStrategy::KeepOurs);
+        let outcome = merge_into("cursor", &src, &dest, &base, ConflictStrategy::Merge, &per_file, &mut Plan::new(false)).unwrap();
+        assert_eq!(outcome.conflicts[0].resolution, Resolution::KeptOurs);
+        assert_eq!(fs::read_to_string(dest.join("SKILL.md")).unwrap(), "hub\n");
+    }
+
+    #[test]
+    fn test_abort_leaves_destination_untouched() {
+        let (_t, src, dest, base) = setup(&[
+            ("SKILL.md", "a\n", "a\n", "agent\n"),
+            ("other.md", "1\n", "2\n", "1\n"),
+        ]);
+
+        let outcome = merge_into("cursor", &src, &dest, &base, ConflictStrategy::Abort, &HashMap::new(), &mut Plan::new(false)).unwrap();
+        assert!(!outcome.applied);
+        assert_eq!(outcome.conflicts[0].resolution, Resolution::Aborted);
+        assert_eq!(fs::read_to_string(dest.join("other.md")).unwrap(), "1\n");
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!("keep-theirs".parse::<ConflictStrategy>().unwrap(), ConflictStrategy::KeepTheirs);
+        assert_eq!("keep_ours".parse::<ConflictStrategy>().unwrap(), ConflictStrategy::KeepOurs);
+        assert!("whatever".parse::<ConflictStrategy>().is_err());
+    }
+}
@@ -0,0 +1,524 @@
+use crate::error::XskillError;
+use crate::xskill::Xskill;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where in-flight sync journals are kept until they commit or roll back.
+const JOURNAL_DIR: &str = ".xskill/journal";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxState {
+    /// New content is being written to staging directories; destinations are untouched.
+    Staging,
+    /// Staged directories are being renamed over their destinations.
+    Committing,
+    /// Every destination was swapped; only backups remain to be removed.
+    Committed,
+}
+
+/// One destination replaced by a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxTarget {
+    pub dest: String,
+    /// Sibling directory holding the new content. If it is never created the destination is removed.
+    pub stage: String,
+    /// Sibling path the previous content is moved to during commit.
+    pub backup: String,
+    /// Whether the stage existed when the commit started.
+    #[serde(default)]
+    pub has_stage: bool,
+    #[serde(default)]
+    pub backed_up: bool,
+    #[serde(default)]
+    pub swapped: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Journal {
+    pub id: String,
+    pub created_at: u64,
+    pub state: TxState,
+    pub targets: Vec<TxTarget>,
+}
+
+/// Stages a set of directory replacements so they all land or none do.
+///
+/// Callers ask for a staging path per destination with [`Transaction::stage`], write the
+/// new content there, then [`Transaction::commit`]. Every step is journaled under
+/// `~/.xskill/journal/` so an interrupted sync can be finished or undone with [`recover`].
+///
+/// The transaction holds a lock on a `.lock` file next to its journal for as long as it
+/// lives, so recovery can tell a running sync from one that was interrupted.
+pub struct Transaction {
+    journal: Journal,
+    path: PathBuf,
+    _lock: File,
+}
+
+fn journal_dir() -> Result<PathBuf, XskillError> {
+    let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+    Ok(journal_dir_in(&home))
+}
+
+pub fn journal_dir_in(home: &Path) -> PathBuf {
+    home.join(JOURNAL_DIR)
+}
+
+fn sibling(dest: &Path, kind: &str, id: &str) -> Result<PathBuf, XskillError> {
+    let name = dest.file_name().and_then(|n| n.to_str()).ok_or_else(|| XskillError::invalid(format!("Invalid destination: {}", dest.display())))?;
+    let parent = dest.parent().ok_or_else(|| XskillError::invalid(format!("Destination has no parent: {}", dest.display())))?;
+    Ok(parent.join(format!(".{}.xskill-{}-{}", name, kind, id)))
+}
+
+fn present(path: &Path) -> bool {
+    path.exists() || path.is_symlink()
+}
+
+fn remove_any(path: &Path) -> Result<(), XskillError> {
+    if !present(path) {
+        return Ok(());
+    }
+    let result = if path.is_symlink() || path.is_file() {
+        fs::remove_file(path)
+    } else {
+        fs::remove_dir_all(path)
+    };
+    result.map_err(|e| XskillError::io(path, e))
+}
+
+fn rename(from: &Path, to: &Path) -> Result<(), XskillError> {
+    fs::rename(from, to).map_err(|e| XskillError::io(from, e))
+}
+
+impl Journal {
+    fn save(&self, path: &Path) -> Result<(), XskillError> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        // Recovery trusts the journal, so it is never left half written
+        crate::utils::write_atomic(path, content.as_bytes())
+    }
+
+    /// Put back every destination touched so far and drop the staged content.
+    fn undo(&mut self) -> Result<(), XskillError> {
+        let mut errors: Vec<XskillError> = Vec::new();
+        for t in self.targets.iter_mut().rev() {
+            let (dest, stage, backup) = (Path::new(&t.dest), Path::new(&t.stage), Path::new(&t.backup));
+            // The stage may have been renamed just before a crash stopped the journal being saved
+            let swapped = t.swapped || (t.has_stage && !present(stage) && present(dest));
+            if swapped {
+                if let Err(e) = remove_any(dest) {
+                    errors.push(e);
+                    continue;
+                }
+            }
+            if present(backup) {
+                if let Err(e) = rename(backup, dest) {
+                    errors.push(e);
+                    continue;
+                }
+            }
+            if let Err(e) = remove_any(stage) {
+                errors.push(e);
+            }
+            t.swapped = false;
+            t.backed_up = false;
+        }
+        // One failure keeps its kind; several are reported together
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ").into()),
+        }
+    }
+
+    /// Swap every remaining staged directory into place, saving progress after each step.
+    fn replay(&mut self, path: &Path) -> Result<(), XskillError> {
+        self.state = TxState::Committing;
+        self.save(path)?;
+        for i in 0..self.targets.len() {
+            if self.targets[i].swapped {
+                continue;
+            }
+            let t = &self.targets[i];
+            let (dest, stage, backup) = (PathBuf::from(&t.dest), PathBuf::from(&t.stage), PathBuf::from(&t.backup));
+            if t.has_stage && !present(&stage) {
+                return Err(XskillError::path_not_found(&stage));
+            }
+            if !t.backed_up && present(&dest) {
+                rename(&dest, &backup)?;
+            }
+            self.targets[i].backed_up = true;
+            self.save(path)?;
+            if self.targets[i].has_stage {
+                rename(&stage, &dest)?;
+            }
+            self.targets[i].swapped = true;
+            self.save(path)?;
+        }
+        self.state = TxState::Committed;
+        self.save(path)
+    }
+
+    fn cleanup(&self, path: &Path) -> Result<(), XskillError> {
+        for t in &self.targets {
+            remove_any(Path::new(&t.backup))?;
+            remove_any(Path::new(&t.stage))?;
+        }
+        remove_journal(path)
+    }
+}
+
+/// Take the lock of the journal at `path`, or `None` when another transaction holds it.
+fn try_lock_journal(path: &Path) -> Result<Option<File>, XskillError> {
+    let lock_path = path.with_extension("lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| XskillError::io(&lock_path, e))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(TryLockError::WouldBlock) => Ok(None),
+        Err(TryLockError::Error(e)) => Err(XskillError::io(&lock_path, e)),
+    }
+}
+
+/// Remove a finished journal and its lock file.
+fn remove_journal(path: &Path) -> Result<(), XskillError> {
+    fs::remove_file(path).map_err(|e| XskillError::io(path, e))?;
+    let _ = fs::remove_file(path.with_extension("lock"));
+    Ok(())
+}
+
+impl Transaction {
+    pub fn begin() -> Result<Self, XskillError> {
+        Self::begin_in(&journal_dir()?)
+    }
+
+    pub fn begin_in(dir: &Path) -> Result<Self, XskillError> {
+        fs::create_dir_all(dir).map_err(|e| XskillError::io(dir, e))?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let id = format!("{}-{}", now.as_nanos(), std::process::id());
+        let journal = Journal { id: id.clone(), created_at: now.as_secs(), state: TxState::Staging, targets: Vec::new() };
+        let path = dir.join(format!("{}.json", id));
+        let lock = try_lock_journal(&path)?.ok_or_else(|| XskillError::Conflict { path: path.clone(), message: format!("Sync journal {} is already in use", id) })?;
+        journal.save(&path)?;
+        Ok(Self { journal, path, _lock: lock })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.journal.id
+    }
+
+    /// Register `dest` for replacement and return the (not yet created) staging path for it.
+    ///
+    /// The stage is a sibling of `dest` so the final rename stays on one filesystem.
+    pub fn stage(&mut self, dest: &Path) -> Result<PathBuf, XskillError> {
+        if let Some(existing) = self.journal.targets.iter().find(|t| Path::new(&t.dest) == dest) {
+            return Ok(PathBuf::from(&existing.stage));
+        }
+        let stage = sibling(dest, "stage", &self.journal.id)?;
+        let backup = sibling(dest, "backup", &self.journal.id)?;
+        if let Some(parent) = stage.parent() {
+            fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
+        }
+        remove_any(&stage)?;
+        self.journal.targets.push(TxTarget {
+            dest: dest.to_string_lossy().to_string(),
+            stage: stage.to_string_lossy().to_string(),
+            backup: backup.to_string_lossy().to_string(),
+            has_stage: false,
+            backed_up: false,
+            swapped: false,
+        });
+        self.journal.save(&self.path)?;
+        Ok(stage)
+    }
+
+    /// Drop a destination registered with `stage` that will not be replaced after all.
+    pub fn unstage(&mut self, dest: &Path) -> Result<(), XskillError> {
+        if let Some(pos) = self.journal.targets.iter().position(|t| Path::new(&t.dest) == dest) {
+            let t = self.journal.targets.remove(pos);
+            remove_any(Path::new(&t.stage))?;
+            self.journal.save(&self.path)?;
+        }
+        Ok(())
+    }
+
+    /// Move every staged directory into place. If any step fails, all destinations are
+    /// restored to their previous content before the error is returned.
+    pub fn commit(mut self) -> Result<(), XskillError> {
+        for t in self.journal.targets.iter_mut() {
+            t.has_stage = present(Path::new(&t.stage));
+        }
+        if let Err(e) = self.journal.replay(&self.path) {
+            return match self.journal.undo() {
+                Ok(_) => {
+                    let _ = remove_journal(&self.path);
+                    Err(e.context("Sync rolled back"))
+                }
+                Err(undo_err) => Err(XskillError::Other {
+                    message: format!(
+                        "Sync failed ({}) and could not be rolled back ({}); run `xskill recover`",
+                        e, undo_err
+                    ),
+                }),
+            };
+        }
+        self.journal.cleanup(&self.path)
+    }
+
+    /// Discard all staged content; destinations are left as they were.
+    pub fn rollback(mut self) -> Result<(), XskillError> {
+        self.journal.undo()?;
+        remove_journal(&self.path)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoverMode {
+    /// Finish journals that were already committing, undo the ones still staging.
+    #[default]
+    Auto,
+    /// Roll every interrupted journal forward.
+    Replay,
+    /// Restore every destination to its content before the interrupted sync.
+    Undo,
+}
+
+impl FromStr for RecoverMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "replay" => Ok(Self::Replay),
+            "undo" => Ok(Self::Undo),
+            _ => Err(format!("Unknown recover mode '{}' (expected auto, replay or undo)", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recovery {
+    pub id: String,
+    /// State the journal was found in.
+    pub state: TxState,
+    /// `"replayed"`, `"undone"` or `"failed"`.
+    pub outcome: String,
+    pub destinations: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// Journals left behind by syncs that did not finish.
+pub fn pending() -> Result<Vec<Journal>, XskillError> {
+    pending_in(&journal_dir()?)
+}
+
+fn pending_in(dir: &Path) -> Result<Vec<Journal>, XskillError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut journals = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| XskillError::io(dir, e))?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let content = fs::read_to_string(&path).map_err(|e| XskillError::io(&path, e))?;
+        let journal: Journal = serde_json::from_str(&content)
+            .map_err(|e| XskillError::integrity(format!("Sync journal {} is corrupt: {}", path.display(), e)))?;
+        journals.push(journal);
+    }
+    journals.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(journals)
+}
+
+/// Replay or undo every interrupted journal. Journals of syncs still running in another
+/// process are left alone.
+pub fn recover(mode: RecoverMode) -> Result<Vec<Recovery>, XskillError> {
+    recover_in(&journal_dir()?, mode)
+}
+
+impl Xskill {
+    /// Replay or undo every interrupted journal under this home, skipping syncs still running.
+    pub fn recover(&self, mode: RecoverMode) -> Result<Vec<Recovery>, XskillError> {
+        recover_in(&journal_dir_in(self.home()), mode)
+    }
+}
+
+fn recover_in(dir: &Path, mode: RecoverMode) -> Result<Vec<Recovery>, XskillError> {
+    let mut recoveries = Vec::new();
+    for mut journal in pending_in(dir)? {
+        let path = dir.join(format!("{}.json", journal.id));
+        // Held for the whole recovery; a running sync holds it until it finishes
+        let Some(_lock) = try_lock_journal(&path)? else {
+            continue;
+        };
+        // Re-read under the lock: the sync may have moved on or finished since it was listed
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        journal = serde_json::from_str(&content)
+            .map_err(|e| XskillError::integrity(format!("Sync journal {} is corrupt: {}", path.display(), e)))?;
+        let state = journal.state;
+        let replay = match mode {
+            RecoverMode::Replay => true,
+            RecoverMode::Undo => false,
+            // A journal only reaches Committing once staging finished, so it is safe to complete
+            RecoverMode::Auto => state != TxState::Staging,
+        };
+        let result = if replay {
+            if state == TxState::Staging {
+                for t in journal.targets.iter_mut() {
+                    t.has_stage = present(Path::new(&t.stage));
+                }
+            }
+            journal.replay(&path).and_then(|_| journal.cleanup(&path))
+        } else {
+            journal.undo().and_then(|_| remove_journal(&path))
+        };
+        recoveries.push(Recovery {
+            id: journal.id.clone(),
+            state,
+            outcome: match (&result, replay) {
+                (Err(_), _) => "failed",
+                (Ok(_), true) => "replayed",
+                (Ok(_), false) => "undone",
+            }
+            .to_string(),
+            destinations: journal.targets.iter().map(|t| t.dest.clone()).collect(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+    Ok(recoveries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn with_home<F: FnOnce(&Path, &Path)>(f: F) {
+        let temp_dir = TempDir::new().unwrap();
+        f(temp_dir.path(), &temp_dir.path().join("journal"));
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn test_commit_and_interrupted_recovery() {
+        with_home(|home, journals| {
+            let a = home.join("agent-a/skill");
+            let b = home.join("agent-b/skill");
+            for dest in [&a, &b] {
+                fs::create_dir_all(dest).unwrap();
+                fs::write(dest.join("SKILL.md"), "old").unwrap();
+            }
+
+            let mut tx = Transaction::begin_in(journals).unwrap();
+            for dest in [&a, &b] {
+                let stage = tx.stage(dest).unwrap();
+                fs::create_dir_all(&stage).unwrap();
+                fs::write(stage.join("SKILL.md"), "new").unwrap();
+            }
+            assert_eq!(read(&a.join("SKILL.md")), "old", "Nothing lands before commit");
+            tx.commit().unwrap();
+            assert_eq!(read(&a.join("SKILL.md")), "new");
+            assert_eq!(read(&b.join("SKILL.md")), "new");
+            assert!(pending_in(journals).unwrap().is_empty());
+            assert_eq!(fs::read_dir(home.join("agent-a")).unwrap().count(), 1, "Stage and backup are cleaned up");
+
+            // Simulate a crash after the first destination was swapped
+            let mut tx = Transaction::begin_in(journals).unwrap();
+            for dest in [&a, &b] {
+                let stage = tx.stage(dest).unwrap();
+                fs::create_dir_all(&stage).unwrap();
+                fs::write(stage.join("SKILL.md"), "newer").unwrap();
+            }
+            let path = tx.path.clone();
+            let mut journal = tx.journal.clone();
+            for t in journal.targets.iter_mut() {
+                t.has_stage = true;
+            }
+            journal.state = TxState::Committing;
+            rename(&a, Path::new(&journal.targets[0].backup)).unwrap();
+            rename(Path::new(&journal.targets[0].stage), &a).unwrap();
+            journal.targets[0].backed_up = true;
+            journal.save(&path).unwrap();
+            drop(tx);
+
+            let recoveries = recover_in(journals, RecoverMode::Undo).unwrap();
+            assert_eq!(recoveries[0].outcome, "undone");
+            assert_eq!(read(&a.join("SKILL.md")), "new");
+            assert_eq!(read(&b.join("SKILL.md")), "new");
+            assert!(pending_in(journals).unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_recovery_skips_running_syncs() {
+        with_home(|home, journals| {
+            let dest = home.join("agent/skill");
+            fs::create_dir_all(&dest).unwrap();
+            fs::write(dest.join("SKILL.md"), "old").unwrap();
+
+            let mut tx = Transaction::begin_in(journals).unwrap();
+            let stage = tx.stage(&dest).unwrap();
+            fs::create_dir_all(&stage).unwrap();
+            fs::write(stage.join("SKILL.md"), "new").unwrap();
+
+            // Still staging, but its owner is alive: not an interrupted sync
+            assert!(recover_in(journals, RecoverMode::Auto).unwrap().is_empty());
+            assert!(stage.exists());
+            tx.commit().unwrap();
+            assert_eq!(read(&dest.join("SKILL.md")), "new");
+            assert_eq!(fs::read_dir(journals).unwrap().count(), 0, "Journal and lock are removed");
+
+            // Once the owner is gone, the same journal is undone
+            let mut tx = Transaction::begin_in(journals).unwrap();
+            let stage = tx.stage(&dest).unwrap();
+            fs::create_dir_all(&stage).unwrap();
+            drop(tx);
+            let recoveries = recover_in(journals, RecoverMode::Auto).unwrap();
+            assert_eq!(recoveries[0].outcome, "undone");
+            assert!(!stage.exists());
+            assert_eq!(read(&dest.join("SKILL.md")), "new");
+            assert_eq!(fs::read_dir(journals).unwrap().count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_rollback_and_removal_targets() {
+        with_home(|home, journals| {
+            let dest = home.join("agent/skill");
+            let gone = home.join("base/skill");
+            for d in [&dest, &gone] {
+                fs::create_dir_all(d).unwrap();
+                fs::write(d.join("f"), "old").unwrap();
+            }
+
+            let mut tx = Transaction::begin_in(journals).unwrap();
+            let stage = tx.stage(&dest).unwrap();
+            fs::create_dir_all(&stage).unwrap();
+            tx.stage(&gone).unwrap();
+            tx.rollback().unwrap();
+            assert_eq!(read(&dest.join("f")), "old");
+            assert!(gone.exists());
+
+            // A destination with no staged content is removed on commit
+            let mut tx = Transaction::begin_in(journals).unwrap();
+            tx.stage(&gone).unwrap();
+            tx.commit().unwrap();
+            assert!(!gone.exists());
+            assert!(dest.exists());
+        });
+    }
+}
@@ -0,0 +1,435 @@
+use crate::config::{SyncPolicy, XskillConfig};
+use crate::error::XskillError;
+use crate::pack::key_id;
+use crate::skill_manager::{read_skill_meta, SkillOrigin};
+use crate::xskill::Xskill;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory of trusted publisher keys: one `<publisher>.pub` per publisher.
+pub const TRUST_DIR: &str = ".xskill/trust";
+
+const SSH_SIG_BEGIN: &str = "-----BEGIN SSH SIGNATURE-----";
+const SSH_SIG_END: &str = "-----END SSH SIGNATURE-----";
+const PGP_SIG_BEGIN: &str = "-----BEGIN PGP SIGNATURE-----";
+
+/// A key in `~/.xskill/trust/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Publisher {
+    /// File name without `.pub`.
+    pub name: String,
+    pub key_id: String,
+    /// Base64 of the 32-byte ed25519 public key.
+    pub public_key: String,
+}
+
+/// What signed a skill, recorded in `.xskill-meta.json` when it was installed or updated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureRecord {
+    pub method: SignatureMethod,
+    pub key_id: String,
+    /// [`crate::pack::content_hash`] of the skill when the signature was checked. A skill
+    /// whose files no longer match is not treated as verified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+impl SignatureRecord {
+    /// Record the current content of `skill_dir` as what this signature covers.
+    pub fn seal(mut self, skill_dir: &Path) -> Result<Self, String> {
+        self.content_hash = Some(crate::pack::content_hash(skill_dir)?);
+        Ok(self)
+    }
+}
+
+/// Outcome of checking the signature on a git tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagVerification {
+    pub signature: Option<SignatureRecord>,
+    /// Why a signed tag could not be checked, for the caller to report.
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignatureMethod {
+    /// Package written by `xskill pack --sign`.
+    Package,
+    /// Annotated git tag signed with an SSH ed25519 key.
+    GitTag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustState {
+    /// Signed by a key in `~/.xskill/trust/`.
+    Verified,
+    /// Signed, but by a key nobody trusted.
+    Untrusted,
+    /// Signed, but the files changed since the signature was checked.
+    Modified,
+    /// Installed from a repository or archive without a signature, or of unknown origin.
+    Unsigned,
+    /// Created or imported on this machine by xskill.
+    Local,
+}
+
+/// Trust status shown for each skill.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustStatus {
+    pub state: TrustState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+}
+
+impl Default for TrustStatus {
+    fn default() -> Self {
+        Self { state: TrustState::Unsigned, publisher: None, key_id: None }
+    }
+}
+
+/// Read an ed25519 public key written as xskill base64 (`xskill keygen`) or as an
+/// OpenSSH `ssh-ed25519 AAAA... comment` line.
+pub fn parse_public_key(text: &str) -> Result<VerifyingKey, String> {
+    let text = text.trim();
+    match text.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["ssh-ed25519", blob, ..] => {
+            let blob = BASE64.decode(blob).map_err(|_| "Invalid OpenSSH public key".to_string())?;
+            ssh_ed25519_key(&mut SshReader(&blob))
+        }
+        [key] => crate::pack::decode_public_key(key),
+        _ => Err("Expected an xskill public key or an ssh-ed25519 key".to_string()),
+    }
+}
+
+fn trust_dir(home: &Path) -> PathBuf {
+    home.join(TRUST_DIR)
+}
+
+/// Every trusted key. Keys that cannot be parsed are skipped; see [`load_publishers_checked`].
+pub fn load_publishers(home: &Path) -> Vec<Publisher> {
+    load_publishers_checked(home).0
+}
+
+/// Every trusted key, with a warning for each key that could not be parsed and was skipped.
+pub fn load_publishers_checked(home: &Path) -> (Vec<Publisher>, Vec<String>) {
+    let mut publishers = Vec::new();
+    let mut warnings = Vec::new();
+    let Ok(entries) = fs::read_dir(trust_dir(home)) else {
+        return (publishers, warnings);
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("pub") {
+            continue;
+        }
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        for line in content.lines().filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#')) {
+            match parse_public_key(line) {
+                Ok(key) => publishers.push(Publisher {
+                    name: name.clone(),
+                    key_id: key_id(&key),
+                    public_key: BASE64.encode(key.as_bytes()),
+                }),
+                Err(e) => warnings.push(format!("Skipping key in {}: {}", path.display(), e)),
+            }
+        }
+    }
+    publishers.sort_by(|a, b| a.name.cmp(&b.name));
+    (publishers, warnings)
+}
+
+/// Trust `key` (xskill or OpenSSH format) as publisher `name`.
+pub fn add_publisher(home: &Path, name: &str, key: &str) -> Result<Publisher, XskillError> {
+    validate_publisher_name(name)?;
+    let parsed = parse_public_key(key).map_err(XskillError::invalid)?;
+    let dir = trust_dir(home);
+    fs::create_dir_all(&dir).map_err(|e| XskillError::io(&dir, e))?;
+    let public_key = BASE64.encode(parsed.as_bytes());
+    let path = dir.join(format!("{}.pub", name));
+    fs::write(&path, format!("{}\n", public_key)).map_err(|e| XskillError::io(&path, e))?;
+    Ok(Publisher { name: name.to_string(), key_id: key_id(&parsed), public_key })
+}
+
+/// Stop trusting publisher `name`. Returns whether it was trusted.
+pub fn remove_publisher(home: &Path, name: &str) -> Result<bool, XskillError> {
+    validate_publisher_name(name)?;
+    let path = trust_dir(home).join(format!("{}.pub", name));
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path).map_err(|e| XskillError::io(&path, e))?;
+    Ok(true)
+}
+
+/// Publisher names become file names under `~/.xskill/trust`, so they follow skill names.
+fn validate_publisher_name(name: &str) -> Result<(), XskillError> {
+    crate::scaffold::validate_skill_name(name)
+        .map_err(|reason| XskillError::InvalidName { name: name.to_string(), reason })
+}
+
+struct SshReader<'a>(&'a [u8]);
+
+impl<'a> SshReader<'a> {
+    fn bytes(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.0.len() < n {
+            return Err("Truncated SSH signature".to_string());
+        }
+        let (head, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn string(&mut self) -> Result<&'a [u8], String> {
+        let len = self.u32()? as usize;
+        self.bytes(len)
+    }
+}
+
+fn ssh_string(out: &mut Vec<u8>, data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(data);
+}
+
+fn ssh_ed25519_key(blob: &mut SshReader) -> Result<VerifyingKey, String> {
+    if blob.string()? != b"ssh-ed25519" {
+        return Err("Only ssh-ed25519 keys are supported".to_string());
+    }
+    let bytes: [u8; 32] = blob.string()?.try_into().map_err(|_| "Invalid ssh-ed25519 key".to_string())?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| format!("Invalid ssh-ed25519 key: {}", e))
+}
+
+/// Check an armored SSH signature (`ssh-keygen -Y sign`, as used by git) over `message`
+/// and return the key that made it.
+pub fn verify_ssh_signature(message: &[u8], armored: &str, namespace: &str) -> Result<VerifyingKey, String> {
+    let body: String = armored
+        .lines()
+        .map(str::trim)
+        .skip_while(|l| *l != SSH_SIG_BEGIN)
+        .skip(1)
+        .take_while(|l| *l != SSH_SIG_END)
+        .collect();
+    let blob = BASE64.decode(body).map_err(|_| "Malformed SSH signature".to_string())?;
+    let mut r = SshReader(&blob);
+    if r.bytes(6)? != b"SSHSIG" || r.u32()? != 1 {
+        return Err("Unsupported SSH signature format".to_string());
+    }
+    let key = ssh_ed25519_key(&mut SshReader(r.string()?))?;
+    let signed_namespace = r.string()?;
+    let reserved = r.string()?;
+    let hash_alg = r.string()?;
+    let mut sig = SshReader(r.string()?);
+    if signed_namespace != namespace.as_bytes() {
+        return Err(format!("SSH signature is for '{}', not '{}'", String::from_utf8_lossy(signed_namespace), namespace));
+    }
+    if sig.string()? != b"ssh-ed25519" {
+        return Err("Only ssh-ed25519 signatures are supported".to_string());
+    }
+    let sig_bytes: [u8; 64] = sig.string()?.try_into().map_err(|_| "Malformed SSH signature".to_string())?;
+
+    let digest = match hash_alg {
+        b"sha512" => Sha512::digest(message).to_vec(),
+        b"sha256" => Sha256::digest(message).to_vec(),
+        _ => return Err("Unsupported SSH signature hash".to_string()),
+    };
+    let mut signed = b"SSHSIG".to_vec();
+    ssh_string(&mut signed, signed_namespace);
+    ssh_string(&mut signed, reserved);
+    ssh_string(&mut signed, hash_alg);
+    ssh_string(&mut signed, &digest);
+    key.verify(&signed, &Signature::from_bytes(&sig_bytes)).map_err(|_| "SSH signature does not match".to_string())?;
+    Ok(key)
+}
+
+/// Verify the annotated tag `tag` in the repository at `dir` if it is signed and points
+/// at `commit`. Lightweight and unsigned tags give no signature, GPG-signed ones a warning;
+/// a signature that does not match is an error.
+pub fn verify_git_tag(dir: &Path, tag: &str, commit: &str) -> Result<TagVerification, String> {
+    let Some((raw, target)) = crate::git_backend::backend().read_tag(dir, tag)? else {
+        return Ok(TagVerification::default());
+    };
+    if target != commit {
+        return Ok(TagVerification::default());
+    }
+    let text = String::from_utf8_lossy(&raw);
+    if text.contains(PGP_SIG_BEGIN) {
+        return Ok(TagVerification {
+            signature: None,
+            warning: Some(format!("Tag {} has a GPG signature, which xskill cannot check", tag)),
+        });
+    }
+    let Some(pos) = text.find(SSH_SIG_BEGIN) else {
+        return Ok(TagVerification::default());
+    };
+    let key = verify_ssh_signature(&raw[..pos], &text[pos..], "git").map_err(|e| format!("Tag {}: {}", tag, e))?;
+    let signature = SignatureRecord { method: SignatureMethod::GitTag, key_id: key_id(&key), content_hash: None };
+    Ok(TagVerification { signature: Some(signature), warning: None })
+}
+
+/// Trust status of a skill from its recorded signature, against the trusted keys. The
+/// files are re-hashed, so a signed skill edited after it was checked is `Modified`.
+pub fn status_with(publishers: &[Publisher], skill_dir: &Path) -> TrustStatus {
+    let meta = read_skill_meta(skill_dir).unwrap_or_default();
+    match meta.signature {
+        Some(sig) => {
+            let publisher = publishers.iter().find(|p| p.key_id == sig.key_id).map(|p| p.name.clone());
+            let unchanged = sig.content_hash.is_some() && sig.content_hash == crate::pack::content_hash(skill_dir).ok();
+            TrustStatus {
+                state: match (unchanged, publisher.is_some()) {
+                    (false, _) => TrustState::Modified,
+                    (true, true) => TrustState::Verified,
+                    (true, false) => TrustState::Untrusted,
+                },
+                publisher,
+                key_id: Some(sig.key_id),
+            }
+        }
+        None if meta.source.is_none() && meta.original_url.is_none() && meta.origin == Some(SkillOrigin::Local) => {
+            TrustStatus { state: TrustState::Local, ..Default::default() }
+        }
+        None => TrustStatus::default(),
+    }
+}
+
+pub fn status_of(skill_dir: &Path) -> TrustStatus {
+    let publishers = crate::utils::get_home_dir().map(|h| load_publishers(&h)).unwrap_or_default();
+    status_with(&publishers, skill_dir)
+}
+
+impl Xskill {
+    /// Refuse to sync `skill_dir` when the security audit blocks it (the Hub copy may have
+    /// been edited since it was installed), or when the sync policy blocks unverified skills
+    /// and it is neither verified nor local.
+    pub fn check_sync_allowed(&self, skill_dir: &Path) -> Result<(), XskillError> {
+        crate::audit::gate(skill_dir, self.config().audit.block, |_| {})?;
+        if self.config().trust.sync_policy != SyncPolicy::BlockUnverified {
+            return Ok(());
+        }
+        check_trusted(&load_publishers(self.home()), skill_dir)
+    }
+}
+
+fn check_trusted(publishers: &[Publisher], skill_dir: &Path) -> Result<(), XskillError> {
+    let (reason, fix) = match status_with(publishers, skill_dir).state {
+        TrustState::Verified | TrustState::Local => return Ok(()),
+        TrustState::Untrusted => ("signed by an untrusted key", "trust its publisher with `xskill trust add`"),
+        TrustState::Modified => ("not what its publisher signed", "update or reinstall it to verify it again"),
+        TrustState::Unsigned => ("unsigned", "trust its publisher with `xskill trust add`"),
+    };
+    let name = skill_dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    Err(XskillError::Blocked {
+        message: format!("Skill '{}' is {} and the sync policy blocks unverified skills; {}", name, reason, fix),
+        skill: name,
+    })
+}
+
+fn home() -> Result<PathBuf, String> {
+    crate::utils::get_home_dir().ok_or_else(|| "Could not find home directory".to_string())
+}
+
+#[tauri::command]
+pub async fn list_trusted_publishers() -> Result<Vec<Publisher>, XskillError> {
+    Ok(load_publishers(&home()?))
+}
+
+#[tauri::command]
+pub async fn add_trusted_publisher(name: String, key: String) -> Result<Publisher, XskillError> {
+    add_publisher(&home()?, &name, &key)
+}
+
+#[tauri::command]
+pub async fn remove_trusted_publisher(name: String) -> Result<bool, XskillError> {
+    remove_publisher(&home()?, &name)
+}
+
+#[tauri::command]
+pub async fn get_sync_policy() -> Result<SyncPolicy, XskillError> {
+    Ok(XskillConfig::load()?.trust.sync_policy)
+}
+
+#[tauri::command]
+pub async fn set_sync_policy(policy: SyncPolicy) -> Result<(), XskillError> {
+    let home = home()?;
+    let mut config = XskillConfig::load_from_home(&home)?;
+    config.trust.sync_policy = policy;
+    Ok(config.save_to_home(&home)?)
+}
+
+/// Armored SSH signature over `message`, as `ssh-keygen -Y sign -n <namespace>` writes it.
+#[cfg(test)]
+pub fn ssh_sign(key: &ed25519_dalek::SigningKey, message: &[u8], namespace: &str) -> String {
+    use ed25519_dalek::Signer;
+    let mut public = Vec::new();
+    ssh_string(&mut public, b"ssh-ed25519");
+    ssh_string(&mut public, key.verifying_key().as_bytes());
+
+    let mut signed = b"SSHSIG".to_vec();
+    ssh_string(&mut signed, namespace.as_bytes());
+    ssh_string(&mut signed, b"");
+    ssh_string(&mut signed, b"sha512");
+    ssh_string(&mut signed, &Sha512::digest(message));
+    let mut sig = Vec::new();
+    ssh_string(&mut sig, b"ssh-ed25519");
+    ssh_string(&mut sig, &key.sign(&signed).to_bytes());
+
+    let mut blob = b"SSHSIG".to_vec();
+    blob.extend_from_slice(&1u32.to_be_bytes());
+    ssh_string(&mut blob, &public);
+    ssh_string(&mut blob, namespace.as_bytes());
+    ssh_string(&mut blob, b"");
+    ssh_string(&mut blob, b"sha512");
+    ssh_string(&mut blob, &sig);
+    let body = BASE64.encode(blob);
+    let lines: Vec<&str> = body.as_bytes().chunks(70).map(|c| std::str::from_utf8(c).unwrap()).collect();
+    format!("{}\n{}\n{}\n", SSH_SIG_BEGIN, lines.join("\n"), SSH_SIG_END)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ssh_signature_roundtrip_and_tamper() {
+        let key = SigningKey::from_bytes(&[3u8; 32]);
+        let armored = ssh_sign(&key, b"object abc\ntag v1\n\nrelease\n", "git");
+        let signer = verify_ssh_signature(b"object abc\ntag v1\n\nrelease\n", &armored, "git").unwrap();
+        assert_eq!(signer, key.verifying_key());
+        assert!(verify_ssh_signature(b"object abc\ntag v1\n\nevil\n", &armored, "git").is_err());
+        assert!(verify_ssh_signature(b"object abc\ntag v1\n\nrelease\n", &armored, "file").unwrap_err().contains("not 'file'"));
+    }
+
+    #[test]
+    fn test_publishers_accept_xskill_and_openssh_keys() {
+        let home = TempDir::new().unwrap();
+        let key = SigningKey::from_bytes(&[5u8; 32]).verifying_key();
+        let mut blob = Vec::new();
+        ssh_string(&mut blob, b"ssh-ed25519");
+        ssh_string(&mut blob, key.as_bytes());
+        let openssh = format!("ssh-ed25519 {} dev@acme", BASE64.encode(blob));
+
+        let added = add_publisher(home.path(), "acme", &openssh).unwrap();
+        assert_eq!(added.key_id, key_id(&key));
+        add_publisher(home.path(), "other", &BASE64.encode([9u8; 32])).unwrap();
+        assert!(add_publisher(home.path(), "bad", "not a key").is_err());
+
+        let names: Vec<_> = load_publishers(home.path()).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["acme", "other"]);
+        assert!(remove_publisher(home.path(), "other").unwrap());
+        assert_eq!(load_publishers(home.path()).len(), 1);
+    }
+}
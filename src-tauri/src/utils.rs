@@ -1,27 +1,28 @@
-use std::path::PathBuf;
+use crate::error::XskillError;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::fs;
 use walkdir::WalkDir;
 
 /// Create a symlink at `dst` pointing to `src`.
 /// On macOS/Linux uses `std::os::unix::fs::symlink`.
 #[cfg(unix)]
-pub fn symlink_dir(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
+pub fn symlink_dir(src: &PathBuf, dst: &PathBuf) -> Result<(), XskillError> {
     if dst.exists() || dst.is_symlink() {
         fs::remove_file(dst)
             .or_else(|_| fs::remove_dir_all(dst))
-            .map_err(|e| format!("Failed to remove existing target {}: {}", dst.display(), e))?;
+            .map_err(|e| XskillError::io(dst, e))?;
     }
     if let Some(parent) = dst.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create parent dir {}: {}", parent.display(), e))?;
+        fs::create_dir_all(parent).map_err(|e| XskillError::io(parent, e))?;
     }
-    std::os::unix::fs::symlink(src, dst)
-        .map_err(|e| format!("Failed to create symlink {} -> {}: {}", dst.display(), src.display(), e))
+    std::os::unix::fs::symlink(src, dst).map_err(|e| XskillError::io(dst, e))
 }
 
 #[cfg(not(unix))]
-pub fn symlink_dir(_src: &PathBuf, _dst: &PathBuf) -> Result<(), String> {
-    Err("Symlink mode is only supported on macOS/Linux".to_string())
+pub fn symlink_dir(_src: &PathBuf, _dst: &PathBuf) -> Result<(), XskillError> {
+    Err(XskillError::invalid("Symlink mode is only supported on macOS/Linux"))
 }
 
 pub fn get_home_dir() -> Option<PathBuf> {
@@ -31,68 +32,169 @@ pub fn get_home_dir() -> Option<PathBuf> {
     dirs::home_dir()
 }
 
-pub fn copy_dir_all(src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
-    println!("Starting copy_dir_all from {:?} to {:?}", src, dst);
+/// Per-user app data directory (`~/Library/Application Support` on macOS), following
+/// `XSKILL_TEST_HOME` like `get_home_dir`.
+pub fn get_app_data_dir() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var("XSKILL_TEST_HOME") {
+        return Some(PathBuf::from(path).join("Library/Application Support"));
+    }
+    dirs::data_dir()
+}
+
+/// A failed directory walk, as an I/O error on the entry it could not read.
+fn walk_error(e: walkdir::Error) -> XskillError {
+    let path = e.path().map(Path::to_path_buf);
+    match (e.into_io_error(), path) {
+        (Some(io), Some(path)) => XskillError::io(&path, io),
+        (Some(io), None) => io.into(),
+        (None, path) => XskillError::Io { path, message: "Filesystem loop".to_string() },
+    }
+}
+
+// Reduced ignored list to prevent accidental filtering of valid skill content
+pub const COPY_IGNORED: &[&str] = &["node_modules", ".git", ".idea", ".vscode", ".DS_Store", "__pycache__"];
+
+/// Files under `root` that `copy_dir_all` would copy, keyed by their path relative to `root`.
+/// Symlinks are skipped, so a skill cannot pull in files from outside its own directory.
+pub fn list_files(root: &Path) -> Result<BTreeMap<PathBuf, PathBuf>, XskillError> {
+    let mut files = BTreeMap::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    let root = fs::canonicalize(root).map_err(|e| XskillError::io(root, e))?;
+    for entry in WalkDir::new(&root).min_depth(1).follow_links(false) {
+        let entry = entry.map_err(walk_error)?;
+        let path = entry.path();
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = path.strip_prefix(&root).map_err(|e| e.to_string())?;
+        let ignored = relative.components()
+            .any(|c| COPY_IGNORED.contains(&c.as_os_str().to_str().unwrap_or("")));
+        if !ignored {
+            files.insert(relative.to_path_buf(), path.to_path_buf());
+        }
+    }
+    Ok(files)
+}
+
+/// Symlinks under `dir` that [`copy_dir_all`] and [`list_files`] leave out, as relative
+/// paths. Their targets live outside the skill, so copies report them instead of following.
+pub fn skipped_links(dir: &Path) -> Result<Vec<String>, XskillError> {
+    let root = if dir.is_symlink() {
+        fs::canonicalize(dir).map_err(|e| XskillError::io(dir, e))?
+    } else {
+        dir.to_path_buf()
+    };
+    let mut links = Vec::new();
+    let walker = WalkDir::new(&root).min_depth(1).follow_links(false).into_iter().filter_entry(|e| {
+        !(e.file_type().is_dir() && COPY_IGNORED.contains(&e.file_name().to_str().unwrap_or("")))
+    });
+    for entry in walker {
+        let entry = entry.map_err(walk_error)?;
+        if entry.path_is_symlink() {
+            let relative = entry.path().strip_prefix(&root).map_err(|e| e.to_string())?;
+            links.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(links)
+}
+
+pub fn copy_dir_all(src: &PathBuf, dst: &PathBuf) -> Result<(), XskillError> {
     if dst.exists() || dst.is_symlink() {
         fs::remove_file(dst)
             .or_else(|_| fs::remove_dir_all(dst))
-            .map_err(|e| format!("Failed to remove existing target {}: {}", dst.display(), e))?;
+            .map_err(|e| XskillError::io(dst, e))?;
     }
-    fs::create_dir_all(dst)
-        .map_err(|e| format!("Failed to create dir {}: {}", dst.display(), e))?;
-
-    // Reduced ignored list to prevent accidental filtering of valid skill content
-    const IGNORED_DIRS: &[&str] = &["node_modules", ".git", ".idea", ".vscode", ".DS_Store", "__pycache__"];
+    fs::create_dir_all(dst).map_err(|e| XskillError::io(dst, e))?;
 
     // Resolve symlinks in source path to ensure WalkDir works correctly
     let src_path = if src.is_symlink() {
-        fs::canonicalize(src).map_err(|e| format!("Failed to canonicalize src {}: {}", src.display(), e))?
+        fs::canonicalize(src).map_err(|e| XskillError::io(src, e))?
     } else {
         src.clone()
     };
-    
-    println!("Resolved src_path: {:?}", src_path);
 
     for entry in WalkDir::new(&src_path).min_depth(1).follow_links(false) {
-        let entry = entry.map_err(|e| format!("Walk error: {}", e))?;
+        let entry = entry.map_err(walk_error)?;
         
         let path = entry.path();
         
         let relative = path.strip_prefix(&src_path)
-            .map_err(|e| format!("Strip prefix error: {}", e))?;
+            .map_err(|e| e.to_string())?;
             
         // Check if relative path or any parent is inside an ignored directory
         let should_ignore = relative.to_string_lossy().split('/')
-            .any(|component| IGNORED_DIRS.contains(&component));
+            .any(|component| COPY_IGNORED.contains(&component));
         
         if should_ignore {
-            // println!("Ignoring {:?}", relative);
             continue;
         }
         
         let dest_path = dst.join(relative);
 
-        if path.is_dir() {
-            fs::create_dir_all(&dest_path)
-                .map_err(|e| format!("Failed to create dir {}: {}", dest_path.display(), e))?;
-        } else if path.is_file() {
+        // Symlinks inside the skill are not followed: their targets live outside it
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&dest_path).map_err(|e| XskillError::io(&dest_path, e))?;
+        } else if file_type.is_file() {
             // Check if source file exists before copying
             if !path.exists() {
                 continue;
             }
 
-            fs::copy(path, &dest_path)
-                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
-            println!("Copied {:?} to {:?}", path, dest_path);
+            fs::copy(path, &dest_path).map_err(|e| XskillError::io(path, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy everything under `src` to `dst`, ignored directories and symlinks included.
+/// Used to stage an exact replica of a directory before changing it.
+pub fn copy_tree(src: &Path, dst: &Path) -> Result<(), XskillError> {
+    fs::create_dir_all(dst).map_err(|e| XskillError::io(dst, e))?;
+    for entry in WalkDir::new(src).min_depth(1).follow_links(false) {
+        let entry = entry.map_err(walk_error)?;
+        let relative = entry.path().strip_prefix(src).map_err(|e| e.to_string())?;
+        let dest_path = dst.join(relative);
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&dest_path).map_err(|e| XskillError::io(&dest_path, e))?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            {
+                let target = fs::read_link(entry.path()).map_err(|e| XskillError::io(entry.path(), e))?;
+                std::os::unix::fs::symlink(target, &dest_path).map_err(|e| XskillError::io(&dest_path, e))?;
+            }
+        } else {
+            fs::copy(entry.path(), &dest_path).map_err(|e| XskillError::io(entry.path(), e))?;
         }
     }
-    println!("Finished copy_dir_all");
+    Ok(())
+}
+
+/// Replace `path` with `content` so that a crash or a full disk leaves either the old file or
+/// the new one, never half of it: write a sibling temp file, flush it, then rename it over.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), XskillError> {
+    let name = path.file_name().ok_or_else(|| XskillError::invalid(format!("Not a file path: {}", path.display())))?;
+    let tmp = path.with_file_name(format!(".{}.{}.tmp", name.to_string_lossy(), std::process::id()));
+    let written = fs::File::create(&tmp)
+        .and_then(|mut file| file.write_all(content).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(XskillError::io(path, e));
+    }
+    // Make the rename itself durable; not every platform can open a directory for this
+    if let Some(dir) = path.parent().and_then(|d| fs::File::open(d).ok()) {
+        let _ = dir.sync_all();
+    }
     Ok(())
 }
 
 #[tauri::command]
-pub fn open_folder(path: String) -> Result<(), String> {
-    open::that(&path).map_err(|e| format!("Failed to open folder: {}", e))
+pub fn open_folder(path: String) -> Result<(), XskillError> {
+    open::that(&path).map_err(|e| XskillError::io(Path::new(&path), e))
 }
 
 pub fn get_system_proxy() -> Option<String> {
@@ -179,4 +281,40 @@ mod tests {
 
         assert!(dst.join("file.txt").exists(), "file.txt should be copied even if src is symlink");
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlinks_inside_a_skill_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let outside = temp_dir.path().join("outside");
+        let skill = temp_dir.path().join("skill");
+        let dst = temp_dir.path().join("dst");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("id_rsa"), "secret").unwrap();
+        fs::create_dir_all(skill.join("scripts")).unwrap();
+        fs::write(skill.join("SKILL.md"), "body").unwrap();
+        fs::write(skill.join("scripts/run.sh"), "echo hi").unwrap();
+        std::os::unix::fs::symlink(outside.join("id_rsa"), skill.join("key")).unwrap();
+        std::os::unix::fs::symlink(&outside, skill.join("linked")).unwrap();
+
+        let listed: Vec<PathBuf> = list_files(&skill).unwrap().into_keys().collect();
+        assert_eq!(listed, vec![PathBuf::from("SKILL.md"), PathBuf::from("scripts/run.sh")]);
+
+        copy_dir_all(&skill, &dst).unwrap();
+        assert!(dst.join("scripts/run.sh").exists());
+        assert!(!dst.join("key").exists() && !dst.join("linked").exists());
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_without_leftovers() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("ledger.json");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        let missing_dir = temp_dir.path().join("gone/ledger.json");
+        assert_eq!(write_atomic(&missing_dir, b"x").unwrap_err().code(), "path_not_found");
+    }
 }
@@ -0,0 +1,76 @@
+use crate::agent_registry::AgentRegistry;
+use crate::config::{XskillConfig, CONFIG_FILE};
+use crate::error::XskillError;
+use crate::skill_manager::CENTRAL_SKILLS_DIR;
+use std::path::{Path, PathBuf};
+
+/// Everything an xskill operation depends on: the home directory, its Hub, the agent
+/// registry and the user config.
+///
+/// The operations themselves are methods on `Xskill`, defined next to the code they belong
+/// to (`list_skills` in `skill_manager`, `sync_skill` in `ide_sync`, ...). The Tauri commands
+/// build one with [`Xskill::load`] and delegate, so other tools can embed xskill against any
+/// home directory without going through the app:
+///
+/// ```no_run
+/// # fn main() -> Result<(), xskill_lib::error::XskillError> {
+/// let xskill = xskill_lib::xskill::Xskill::open(std::path::Path::new("/tmp/home"))?;
+/// for skill in xskill.hub_skills() {
+///     xskill.sync_skill(std::path::Path::new(&skill.path), &["cursor".to_string()], &Default::default())?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Xskill {
+    home: PathBuf,
+    hub: PathBuf,
+    registry: AgentRegistry,
+    config: XskillConfig,
+}
+
+impl Xskill {
+    /// Context for the current user's home (or `XSKILL_TEST_HOME`).
+    pub fn load() -> Result<Self, XskillError> {
+        let home = crate::utils::get_home_dir().ok_or("Could not find home directory")?;
+        Self::open(&home)
+    }
+
+    /// Context for `home`, reading `.xskill/agents.toml` and `.xskill/config.toml` under it.
+    /// Either file being invalid is a `Config` error; the context is only ever built from
+    /// what the user configured.
+    pub fn open(home: &Path) -> Result<Self, XskillError> {
+        let registry = AgentRegistry::load_from_home(home)?;
+        let config = XskillConfig::load_from_home(home)
+            .map_err(|message| XskillError::Config { path: home.join(CONFIG_FILE), message })?;
+        Ok(Self::new(home.to_path_buf(), registry, config))
+    }
+
+    pub fn new(home: PathBuf, registry: AgentRegistry, config: XskillConfig) -> Self {
+        Self { hub: home.join(CENTRAL_SKILLS_DIR), home, registry, config }
+    }
+
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// `~/.xskill/skills`, the source of truth for every synced skill.
+    pub fn hub(&self) -> &Path {
+        &self.hub
+    }
+
+    pub fn registry(&self) -> &AgentRegistry {
+        &self.registry
+    }
+
+    pub fn config(&self) -> &XskillConfig {
+        &self.config
+    }
+
+    /// Global skills directory of `agent`.
+    pub fn agent_skills_dir(&self, agent: &str) -> Result<PathBuf, XskillError> {
+        self.registry
+            .skills_dir(agent, &self.home)
+            .ok_or_else(|| XskillError::UnknownAgent { agent: agent.to_string() })
+    }
+}
@@ -7,6 +7,7 @@ import { Input } from "@/components/ui/input";
 import { RefreshCw, Search, CheckCircle2, Box } from "lucide-react";
 import { useAppStore, type Project, type LocalSkill } from "@/hooks/useAppStore";
 import type { Suite } from "@/hooks/useSuitesStore";
+import { errorMessage } from "@/lib/utils";
 
 interface ApplySkillsDialogProps {
   isOpen: boolean;
@@ -72,7 +73,7 @@ export function ApplySkillsDialog({ isOpen, onClose, project, skills }: ApplySki
       }, 1500);
     } catch (err: unknown) {
       console.error("Apply failed", err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setApplying(false);
     }
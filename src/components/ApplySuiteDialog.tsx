@@ -6,6 +6,7 @@ import { Badge } from "@/components/ui/badge";
 import { useSuitesStore, type Suite } from "@/hooks/useSuitesStore";
 import { Layers, CheckCircle2, RefreshCw, Box } from "lucide-react";
 import { useAppStore, type Project } from "@/hooks/useAppStore";
+import { errorMessage } from "@/lib/utils";
 
 export function ApplySuiteDialog({
   isOpen,
@@ -43,7 +44,7 @@ export function ApplySuiteDialog({
       }, 1500);
     } catch (err: unknown) {
       console.error("Apply failed", err);
-      setError(err instanceof Error ? err.message : String(err));
+      setError(errorMessage(err));
     } finally {
       setApplyingSuite(null);
     }
@@ -6,6 +6,7 @@ import {
 import { Button } from "@/components/ui/button";
 import { RefreshCw, Trash2, FolderOpen, Import, Folder } from "lucide-react";
 import type { Project, LocalSkill } from "@/hooks/useAppStore";
+import { errorMessage } from "@/lib/utils";
 
 interface ManageProjectSkillsDialogProps {
   isOpen: boolean;
@@ -48,7 +49,7 @@ export function ManageProjectSkillsDialog({ isOpen, onClose, project }: ManagePr
       await invoke("delete_skill", { path: skillPath });
       await loadSkills(); // Reload list
     } catch (err) {
-      alert(`Delete failed: ${err}`);
+      alert(`Delete failed: ${errorMessage(err)}`);
     } finally {
       setDeletingPath(null);
     }
@@ -61,7 +62,7 @@ export function ManageProjectSkillsDialog({ isOpen, onClose, project }: ManagePr
       await invoke("skill_collect_to_hub", { skillDir: skill.path });
       alert(`✅ Skill "${skill.name}" imported to Hub successfully!`);
     } catch (err) {
-      alert(`❌ Import failed: ${err}`);
+      alert(`❌ Import failed: ${errorMessage(err)}`);
     } finally {
       // Minimum 1s animation effect
       await new Promise(resolve => setTimeout(resolve, 1000));
@@ -74,7 +75,7 @@ export function ManageProjectSkillsDialog({ isOpen, onClose, project }: ManagePr
       await invoke("open_folder", { path: skillPath });
     } catch (err) {
       console.error("Failed to open folder:", err);
-      alert(`Failed to open folder: ${err}`);
+      alert(`Failed to open folder: ${errorMessage(err)}`);
     }
   };
 
@@ -8,6 +8,7 @@ import { Input } from "@/components/ui/input";
 import { FixedSizeGrid as Grid } from "react-window";
 import AutoSizer from "react-virtualized-auto-sizer";
 import { useAppStore } from "@/hooks/useAppStore";
+import { errorMessage } from "@/lib/utils";
 
 interface MarketplaceSkill {
   id: string;
@@ -189,7 +190,7 @@ export function MarketplacePage() {
     } catch (err: unknown) {
       console.error("Marketplace fetch error:", err);
       if (skills.length === 0) {
-        const message = err instanceof Error ? err.message : String(err);
+        const message = errorMessage(err);
         setError(`Failed to load marketplace data: ${message}`);
       }
     } finally {
@@ -222,7 +223,7 @@ export function MarketplacePage() {
       refreshSkills(); 
       alert(`✅ "${skill.name}" installed successfully! Go to Hub to view.`);
     } catch (err) {
-      alert(`❌ Install failed: ${err}`);
+      alert(`❌ Install failed: ${errorMessage(err)}`);
     } finally {
       setInstallingId(null);
     }
@@ -7,6 +7,7 @@ import { Checkbox } from "@/components/ui/checkbox";
 import { Label } from "@/components/ui/label";
 import type { Tool } from "@/hooks/useAppStore";
 import { AlertCircle, CheckCircle2, Sparkles } from "lucide-react";
+import { errorMessage } from "@/lib/utils";
 
 interface NewSkillDialogProps {
   isOpen: boolean;
@@ -96,7 +97,7 @@ export function NewSkillDialog({ isOpen, onClose, tools, onCreated }: NewSkillDi
       setAllowedTools([]);
       setCollectToHub(true);
     } catch (error) {
-      alert(`Failed to create skill: ${error}`);
+      alert(`Failed to create skill: ${errorMessage(error)}`);
     } finally {
       setIsLoading(false);
     }
@@ -17,6 +17,7 @@ import { Input } from "@/components/ui/input";
 import { AlertCircle, CheckCircle2, Copy, Move, Loader2, GitBranch, FolderSearch, ArrowLeft, Download,  } from "lucide-react";
 import { Tabs, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import { Card } from "@/components/ui/card";
+import { errorMessage } from "@/lib/utils";
 
 export interface DiscoveredSkill {
   name: string;
@@ -106,7 +107,7 @@ export function OnboardingDialog({
       onClose();
     } catch (error) {
       console.error("Failed to import skills:", error);
-      alert(`Import failed: ${error}`);
+      alert(`Import failed: ${errorMessage(error)}`);
     } finally {
       setImporting(false);
     }
@@ -124,7 +125,7 @@ export function OnboardingDialog({
             onClose();
         }, 1000);
     } catch (error) {
-        setGitStatus(`Error: ${error}`);
+        setGitStatus(`Error: ${errorMessage(error)}`);
         // Keep loading false but show error state if we had one
     } finally {
         setGitLoading(false);
@@ -7,12 +7,13 @@ import { Badge } from "@/components/ui/badge";
 import { Wrench, RefreshCw, Link as LinkIcon, ArrowUpCircle, Trash2, Check, Copy, FolderOpen, Eye, FileText, ExternalLink, Pencil, X, Save } from "lucide-react";
 import { Tooltip } from "@/components/ui/tooltip-simple";
 import { AgentIcons } from "@/components/ui/icons";
-import type { LocalSkill, Tool } from "@/hooks/useAppStore";
+import type { LocalSkill, SyncReport, Tool } from "@/hooks/useAppStore";
 import { motion } from "framer-motion";
 import ReactMarkdown from 'react-markdown';
 import remarkGfm from 'remark-gfm';
 import { Input } from "@/components/ui/input";
 import 'github-markdown-css/github-markdown.css';
+import { errorMessage } from "@/lib/utils";
 
 interface SkillCardProps {
   skill: LocalSkill;
@@ -46,7 +47,7 @@ export function SkillCard({ skill, tools, syncedTools = [], onRefresh, onConfigu
         onRefresh();
     } catch (err) {
         console.error("Failed to update remark:", err);
-        alert(`Failed to update remark: ${err}`);
+        alert(`Failed to update remark: ${errorMessage(err)}`);
     }
   };
 
@@ -81,14 +82,18 @@ export function SkillCard({ skill, tools, syncedTools = [], onRefresh, onConfigu
   const handleSync = async (targetKey: string, mode: "copy" | "link") => {
     setSyncing(`${targetKey}-${mode}`);
     try {
-      await invoke("sync_skill", {
+      const report = await invoke<SyncReport>("sync_skill", {
         skillDir: skill.path,
         targetToolKeys: [targetKey],
         mode,
       });
+      const unresolved = report.conflicts.filter(c => c.resolution === "unresolved" || c.resolution === "aborted");
+      if (unresolved.length > 0) {
+        alert(`Some files were edited in the agent copy and were left untouched:\n${unresolved.map(c => c.file).join("\n")}`);
+      }
       onRefresh();
     } catch (err) {
-      alert(`Sync failed: ${err}`);
+      alert(`Sync failed: ${errorMessage(err)}`);
     } finally {
       setSyncing(null);
     }
@@ -101,7 +106,7 @@ export function SkillCard({ skill, tools, syncedTools = [], onRefresh, onConfigu
       await invoke("delete_skill", { path: skill.path });
       onRefresh();
     } catch (err) {
-      alert(`Delete failed: ${err}`);
+      alert(`Delete failed: ${errorMessage(err)}`);
     }
   };
 
@@ -110,7 +115,7 @@ export function SkillCard({ skill, tools, syncedTools = [], onRefresh, onConfigu
       await invoke("skill_collect_to_hub", { skillDir: skill.path });
       onRefresh();
     } catch (err) {
-      alert(`Collect failed: ${err}`);
+      alert(`Collect failed: ${errorMessage(err)}`);
     }
   };
 
@@ -14,6 +14,7 @@ import { Label } from "@/components/ui/label";
 import { ScrollArea } from "@/components/ui/scroll-area";
 import { Plus, Trash2, Loader2, Save } from "lucide-react";
 import { type LocalSkill } from "@/hooks/useAppStore";
+import { errorMessage } from "@/lib/utils";
 
 interface SkillConfig {
   command: string | null;
@@ -89,7 +90,7 @@ export function SkillConfigDialog({
       onClose();
     } catch (error) {
       console.error("Failed to save skill config:", error);
-      alert(`Save failed: ${error}`);
+      alert(`Save failed: ${errorMessage(error)}`);
     } finally {
       setSaving(false);
     }
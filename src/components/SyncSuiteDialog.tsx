@@ -9,6 +9,7 @@ import { Suite } from "@/hooks/useSuitesStore";
 import { useAppStore } from "@/hooks/useAppStore";
 import { Combobox } from "@/components/ui/combobox";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "@/components/ui/select";
+import { errorMessage } from "@/lib/utils";
 
 interface SyncSuiteDialogProps {
   isOpen: boolean;
@@ -64,7 +65,7 @@ export function SyncSuiteDialog({ isOpen, onClose, suite }: SyncSuiteDialogProps
       onClose();
     } catch (err) {
       console.error("Sync failed:", err);
-      setError(err as string);
+      setError(errorMessage(err));
     } finally {
       setSyncing(false);
     }
@@ -9,11 +9,185 @@ export interface LocalSkill {
   tool_key: string;
   disable_model_invocation: boolean;
   allowed_tools: string[];
+  tags?: string[];
   content: string;
   original_url?: string;
   remark?: string;
+  parse_error?: FrontmatterError | null;
+  trust?: TrustStatus;
 }
 
+export interface TrustStatus {
+  state: "verified" | "untrusted" | "unsigned" | "local";
+  publisher?: string;
+  key_id?: string;
+}
+
+/** Error object rejected by every backend command. */
+export interface XskillError {
+  code: string;
+  message: string;
+  skill?: string;
+  agent?: string;
+  path?: string;
+}
+
+export interface FrontmatterError {
+  kind: "unterminated" | "invalid_yaml" | "not_a_mapping" | "invalid_field";
+  line?: number | null;
+  column?: number | null;
+  message?: string;
+}
+
+export interface SyncConflict {
+  agent: string;
+  dest_path: string;
+  file: string;
+  hub_changed: boolean;
+  resolution: "merged" | "kept_ours" | "kept_theirs" | "unresolved" | "aborted";
+}
+
+export type PlannedOp =
+  | { op: "create_dir"; path: string }
+  | { op: "remove_dir"; path: string }
+  | { op: "copy_file"; from: string; to: string }
+  | { op: "write_file"; path: string; overwrite: boolean }
+  | { op: "delete_file"; path: string }
+  | { op: "create_symlink"; path: string; target: string; replaces: boolean }
+  | { op: "move_dir"; from: string; to: string }
+  | { op: "write_agents_md"; path: string; overwrite: boolean }
+  | { op: "patch_claude_config"; path: string; server: string };
+
+export interface SyncReport {
+  written: string[];
+  conflicts: SyncConflict[];
+  errors: string[];
+  operations: PlannedOp[];
+  dry_run: boolean;
+}
+
+export interface Profile {
+  skills?: string[];
+  tags?: string[];
+  exclude?: string[];
+  inherit?: boolean;
+}
+
+export interface Profiles {
+  default: Profile;
+  agents: Record<string, Profile>;
+}
+
+export interface ReconcileReport {
+  agent: string;
+  assigned: string[];
+  added: string[];
+  pruned: string[];
+  unassigned: string[];
+  unknown: string[];
+  prune_skipped?: string;
+  conflicts: SyncConflict[];
+  errors: string[];
+  operations: PlannedOp[];
+}
+
+export interface PruneReport {
+  removed: string[];
+  kept: string[];
+  operations: PlannedOp[];
+  dry_run: boolean;
+}
+
+export interface UpdateCheck {
+  name: string;
+  path: string;
+  repo: string;
+  ref: string | null;
+  subpath: string | null;
+  pinned: boolean;
+  current: string | null;
+  latest: string | null;
+  ahead: number;
+  behind: number;
+  changed_files: string[];
+  local_changes: string[];
+  update_available: boolean;
+  error: string | null;
+}
+
+export interface SkillAction {
+  name: string;
+  action: "installed" | "updated" | "unchanged" | "skipped" | "failed";
+  detail: string | null;
+}
+
+export interface SkillSource {
+  repo: string;
+  ref?: string;
+  subpath?: string;
+  commit: string;
+  pinned: boolean;
+}
+
+export interface DiscoveredSkill {
+  name: string;
+  description: string;
+  subpath: string;
+  installed: boolean;
+  parse_error: string | null;
+}
+
+export interface RepoDiscovery {
+  source: SkillSource;
+  skills: DiscoveredSkill[];
+}
+
+export type ForgeKind = 'github' | 'gitlab' | 'gitea' | 'git';
+
+export interface ForgeHost {
+  host: string;
+  kind: ForgeKind;
+}
+
+export interface StoredCredential {
+  host: string;
+  store: 'keyring' | 'file';
+}
+
+export interface PackageReport {
+  path: string;
+  name: string;
+  version: string | null;
+  fingerprint: string;
+  files: number;
+  signed_by: string | null;
+}
+
+export type AuditSeverity = "low" | "medium" | "high" | "critical";
+
+export interface AuditFinding {
+  check: string;
+  severity: AuditSeverity;
+  message: string;
+  file: string;
+  line: number | null;
+  excerpt: string | null;
+}
+
+export interface AuditReport {
+  skill_name: string;
+  path: string;
+  findings: AuditFinding[];
+}
+
+export interface Publisher {
+  name: string;
+  key_id: string;
+  public_key: string;
+}
+
+export type SyncPolicy = "allow" | "block-unverified";
+
 export interface Tool {
   key: string;
   display_name: string;